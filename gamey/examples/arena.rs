@@ -1,35 +1,137 @@
-use gamey::{GameY, GameStatus, MctsBot, MctsCompletoBot, Movement, YBot};
-use std::time::Instant;
+use gamey::{
+    BridgePlayout, DEFAULT_PRIOR_WEIGHT, DEFAULT_RAVE_EQUIVALENCE, ExternalBot, GameY, GameStatus, GreedyBot, MctsBot,
+    MctsCompletoBot, Movement, RemoteBot, YBot, derive_seed, move_seed,
+};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+/// Uso:
+///
+/// ```sh
+/// cargo run --release --example arena                # MCTS vs MCTS Completo
+/// cargo run --release --example arena -- paralelo    # 1 hilo vs todos los núcleos, mismo tiempo por jugada
+/// cargo run --release --example arena -- servidor    # configuración del servidor: 1 árbol vs 4 con las mismas iteraciones
+/// cargo run --release --example arena -- rave [k]    # MCTS con RAVE vs mcts_completo_dificil (k = equivalencia RAVE)
+/// cargo run --release --example arena -- puentes     # playout que salva puentes vs playout de proximidad
+/// cargo run --release --example arena -- prior [w]   # MCTS con la evaluación estática como prior (w = peso)
+/// cargo run --release --example arena -- greedy      # greedy_bot vs mcts_completo_medio
+/// cargo run --release --example arena -- externo <programa> [args...]   # motor externo vs mcts_completo_medio
+/// cargo run --release --example arena -- remoto <url> <bot_id>          # bot de otro servidor /play vs mcts_completo_medio
+/// cargo run --release --example arena -- rave 1000 --seed 7   # partidas reproducibles
+/// ```
+///
+/// Con `--seed` cada partida y jugada usa una semilla derivada de la dada, así
+/// que el resultado se repite (no con presupuesto de tiempo).
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let seed: Option<u64> = args
+        .iter()
+        .position(|arg| arg == "--seed")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|seed| seed.parse().ok());
+    let matchup = std::env::args().nth(1).unwrap_or_else(|| "clasico".to_string());
+
+    match matchup.as_str() {
+        "paralelo" => {
+            // Mismo tiempo por jugada para ambos: solo cambia el número de hilos.
+            let budget = Duration::from_millis(500);
+            let single = MctsCompletoBot::new("Completo 1 hilo", u32::MAX).with_time_budget(budget);
+            let parallel = MctsCompletoBot::new("Completo N hilos", u32::MAX)
+                .with_threads(0)
+                .with_time_budget(budget);
+            run_arena(&single, &parallel, 50, 7, seed);
+        }
+        "servidor" => {
+            // Como en `config/bots.json`: número fijo de iteraciones. Con más
+            // hilos las iteraciones se reparten entre árboles independientes.
+            // Sin `--seed`: con semilla ambos construirían un solo árbol.
+            let single = MctsCompletoBot::new("30000 it, 1 hilo", 30000).with_threads(1);
+            let split = MctsCompletoBot::new("30000 it, 4 hilos", 30000).with_threads(4);
+            run_arena(&single, &split, 50, 7, seed);
+        }
+        "rave" => {
+            // Mismas iteraciones que los bots registrados en el servidor.
+            let equivalence = std::env::args()
+                .nth(2)
+                .and_then(|k| k.parse().ok())
+                .unwrap_or(DEFAULT_RAVE_EQUIVALENCE);
+            let rave = MctsCompletoBot::new("mcts_rave", 30000).with_rave(equivalence);
+            let completo = MctsCompletoBot::new("mcts_completo_dificil", 30000);
+            run_arena(&rave, &completo, 50, 7, seed);
+        }
+        "puentes" => {
+            // Mismas iteraciones: solo cambia la política de las simulaciones.
+            let puentes = MctsCompletoBot::new("mcts_puentes", 30000).with_playout(Arc::new(BridgePlayout));
+            let completo = MctsCompletoBot::new("mcts_completo_dificil", 30000);
+            run_arena(&puentes, &completo, 50, 7, seed);
+        }
+        "prior" => {
+            let weight = std::env::args()
+                .nth(2)
+                .and_then(|w| w.parse().ok())
+                .unwrap_or(DEFAULT_PRIOR_WEIGHT);
+            let prior = MctsCompletoBot::new("mcts_evaluado", 30000).with_prior(weight);
+            let completo = MctsCompletoBot::new("mcts_completo_dificil", 30000);
+            run_arena(&prior, &completo, 50, 7, seed);
+        }
+        "greedy" => {
+            let completo = MctsCompletoBot::new("mcts_completo_medio", 15000);
+            run_arena(&GreedyBot, &completo, 50, 7, seed);
+        }
+        "externo" => {
+            // Cualquier programa que hable el protocolo de `gamey --mode engine`.
+            let command: Vec<String> = args.iter().skip(2).take_while(|arg| *arg != "--seed").cloned().collect();
+            let Some((program, engine_args)) = command.split_first() else {
+                eprintln!("Uso: arena -- externo <programa> [args...]");
+                return;
+            };
+            let externo = ExternalBot::new("externo", program.clone(), engine_args.to_vec());
+            let completo = MctsCompletoBot::new("mcts_completo_medio", 15000);
+            run_arena(&externo, &completo, 50, 7, seed);
+        }
+        "remoto" => {
+            // Cualquier servidor con la misma API `GET /play` que `game_server`.
+            let (Some(url), Some(bot_id)) = (args.get(2), args.get(3)) else {
+                eprintln!("Uso: arena -- remoto <url> <bot_id>");
+                return;
+            };
+            let remoto = RemoteBot::new(format!("{bot_id}@{url}"), url.clone(), bot_id.clone());
+            let completo = MctsCompletoBot::new("mcts_completo_medio", 15000);
+            run_arena(&remoto, &completo, 50, 7, seed);
+        }
+        _ => {
+            let mcts = MctsBot::new("MCTS", 15000); // Ajusta la cantidad de simulaciones si tarda mucho
+            let completo = MctsCompletoBot::new("MCTS Completo", 15000);
+            run_arena(&mcts, &completo, 100, 7, seed);
+        }
+    }
+}
+
+fn run_arena(bot_a: &dyn YBot, bot_b: &dyn YBot, num_games: u32, size: u32, seed: Option<u64>) {
     println!("==============================================");
-    println!("=        Arena: MCTS vs MCTS Completo         =");
+    println!("Arena: {} vs {}", bot_a.name(), bot_b.name());
     println!("==============================================");
 
-    let num_games = 100;
-    let size = 7; // Tamaño del tablero
-    let mut mcts_wins = 0;
-    let mut completo_wins = 0;
-
-    let mcts = MctsBot::new("MCTS", 15000); // Ajusta la cantidad de simulaciones si tarda mucho
-    let completo = MctsCompletoBot::new("MCTS Completo", 15000); 
+    let mut a_wins = 0;
+    let mut b_wins = 0;
 
     for i in 0..num_games {
         println!("----------------------------------------------");
         println!("Partida {} / {}", i + 1, num_games);
-        
+
         let mut game = GameY::new(size);
+        let game_seed = seed.map(|seed| derive_seed(seed, i as u64));
         let start_time = Instant::now();
 
         // Alternamos el que empieza:
-        // En juegos pares empieza MCTS (Player 0)
-        // En juegos impares empieza MCTS Completo (Player 0)
-        let mcts_starts = i % 2 == 0;
-        
-        if mcts_starts {
-            println!("Empieza: {}", mcts.name());
+        // En juegos pares empieza A (Player 0)
+        // En juegos impares empieza B (Player 0)
+        let a_starts = i % 2 == 0;
+
+        if a_starts {
+            println!("Empieza: {}", bot_a.name());
         } else {
-            println!("Empieza: {}", completo.name());
+            println!("Empieza: {}", bot_b.name());
         }
 
         loop {
@@ -38,17 +140,19 @@ fn main() {
             }
 
             let next_player = game.next_player().unwrap();
-            let is_mcts_turn = if mcts_starts {
-                next_player.id() == 0 
+            let is_a_turn = if a_starts {
+                next_player.id() == 0
             } else {
                 next_player.id() == 1
             };
 
-            let move_coords = if is_mcts_turn {
-                mcts.choose_move(&game).expect("MCTS no encontró movimiento válido")
-            } else {
-                completo.choose_move(&game).expect("MCTS Completo no encontró movimiento válido")
+            let bot = if is_a_turn { bot_a } else { bot_b };
+            let chosen = match game_seed {
+                Some(game_seed) => bot.choose_move_seeded(&game, move_seed(game_seed, &game)),
+                None => bot.choose_move(&game),
             };
+            let move_coords = chosen
+                .unwrap_or_else(|| panic!("{} no encontró movimiento válido", bot.name()));
 
             game.add_move(Movement::Placement {
                 player: next_player,
@@ -63,18 +167,18 @@ fn main() {
         match game.status() {
             GameStatus::Finished { winner } => {
                 if let Some(winner) = winner {
-                    let mcts_won = if mcts_starts {
+                    let a_won = if a_starts {
                         winner.id() == 0
                     } else {
                         winner.id() == 1
                     };
 
-                    if mcts_won {
-                        println!("=> ¡Ganador: {}! (Tiempo: {:.2?})", mcts.name(), elapsed);
-                        mcts_wins += 1;
+                    if a_won {
+                        println!("=> ¡Ganador: {}! (Tiempo: {:.2?})", bot_a.name(), elapsed);
+                        a_wins += 1;
                     } else {
-                        println!("=> ¡Ganador: {}! (Tiempo: {:.2?})", completo.name(), elapsed);
-                        completo_wins += 1;
+                        println!("=> ¡Ganador: {}! (Tiempo: {:.2?})", bot_b.name(), elapsed);
+                        b_wins += 1;
                     }
                 } else {
                     println!("=> Empate. (Tiempo: {:.2?})", elapsed);
@@ -88,6 +192,6 @@ fn main() {
     println!("                 RESULTADOS                   ");
     println!("==============================================");
     println!("Partidas Jugadas: {}", num_games);
-    println!("{:<20}: {} victorias", mcts.name(), mcts_wins);
-    println!("{:<20}: {} victorias", completo.name(), completo_wins);
+    println!("{:<20}: {} victorias", bot_a.name(), a_wins);
+    println!("{:<20}: {} victorias", bot_b.name(), b_wins);
 }
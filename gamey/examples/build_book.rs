@@ -0,0 +1,44 @@
+use gamey::{BookGeneration, MctsCompletoBot, OpeningBook, generate_opening_book};
+use std::time::Instant;
+
+/// Genera un libro de aperturas por autojuego y lo guarda en JSON.
+///
+/// Uso:
+///
+/// ```sh
+/// cargo run --release --example build_book -- <fichero> [tamaño] [partidas] [jugadas] [exploración]
+/// cargo run --release --example build_book -- book.json 11 40 4 0.3
+/// ```
+///
+/// Si el fichero ya existe, las posiciones nuevas se añaden a las que tenía.
+/// El libro se usa con `gamey --book <fichero>` (CLI y servidor).
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let Some(path) = args.get(1) else {
+        eprintln!("Uso: build_book <fichero> [tamaño] [partidas] [jugadas] [exploración]");
+        std::process::exit(1);
+    };
+    let arg = |i: usize, default: &str| args.get(i).cloned().unwrap_or_else(|| default.to_string());
+    let config = BookGeneration {
+        size: arg(2, "11").parse().expect("tamaño inválido"),
+        games: arg(3, "40").parse().expect("número de partidas inválido"),
+        max_plies: arg(4, "4").parse().expect("número de jugadas inválido"),
+        exploration: arg(5, "0.3").parse().expect("exploración inválida"),
+    };
+
+    // El bot más fuerte del servidor, con todos los núcleos.
+    let bot = MctsCompletoBot::new("mcts_completo_dificil", 30000).with_threads(0);
+
+    println!(
+        "Generando libro: tamaño {}, {} partidas, {} jugadas por partida, exploración {}",
+        config.size, config.games, config.max_plies, config.exploration
+    );
+    let start = Instant::now();
+    let generated = generate_opening_book(&bot, &config);
+
+    let mut book = OpeningBook::load(path).unwrap_or_default();
+    book.merge(generated);
+    book.save(path).expect("no se pudo guardar el libro");
+
+    println!("{} posiciones en {} ({:.2?})", book.len(), path, start.elapsed());
+}
@@ -0,0 +1,159 @@
+//! Acciones especiales de los bots MCTS: abandonar y aplicar la regla del intercambio.
+//!
+//! Los bots de búsqueda estiman la probabilidad de ganar de su mejor jugada.
+//! [`ActionPolicy`] decide, a partir de esa estimación, si en lugar de colocar
+//! una ficha conviene devolver un [`GameAction`].
+//!
+//! Un bot sin estado (cada llamada de [`crate::YBot`], p. ej. `/play`) solo
+//! tiene la estimación de la búsqueda actual, así que abandona con una sola
+//! estimación por debajo del umbral. Las sesiones de partida
+//! ([`crate::BotSession`]) recuerdan las anteriores y solo abandonan tras
+//! [`RESIGN_CONFIRMATIONS`] estimaciones seguidas por debajo.
+
+use crate::{BotDecision, Coordinates, GameAction, GameY, Movement, RuleSet};
+
+/// Umbral de abandono recomendado: por debajo de un 5 % de victorias estimadas.
+pub const DEFAULT_RESIGN_THRESHOLD: f32 = 0.05;
+
+/// Estimaciones seguidas por debajo del umbral que necesita una sesión para
+/// abandonar (ver [`ActionPolicy::decide_in_session`]).
+pub const RESIGN_CONFIRMATIONS: u32 = 2;
+
+/// Si en la segunda jugada el mejor win rate del segundo jugador queda por
+/// debajo de este valor, la primera ficha favorece a quien la puso y conviene
+/// quedársela.
+pub const SWAP_THRESHOLD: f32 = 0.5;
+
+/// Qué acciones puede devolver un bot además de colocar fichas. Por defecto
+/// ninguna: el bot siempre coloca.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ActionPolicy {
+    /// Abandona si el win rate estimado de su mejor jugada es menor que este valor.
+    pub resign_threshold: Option<f32>,
+    /// Decide si intercambiar en la segunda jugada de la partida.
+    pub swap: bool,
+}
+
+impl ActionPolicy {
+    /// Decisión final a partir de la mejor jugada encontrada y su win rate
+    /// estimado (desde el punto de vista del jugador que mueve). Abandona con
+    /// esta sola estimación.
+    ///
+    /// En la segunda jugada se valora el intercambio antes que el abandono:
+    /// si la posición es mala para quien mueve, es buena para quien tiene la
+    /// ficha, y con el intercambio pasa a ser nuestra.
+    pub fn decide(&self, board: &GameY, best_move: Coordinates, win_rate: f32) -> BotDecision {
+        if self.swap && is_swap_turn(board) {
+            if win_rate < SWAP_THRESHOLD {
+                return BotDecision::Action(GameAction::Swap);
+            }
+            return BotDecision::Move(best_move);
+        }
+        if self.resign_threshold.is_some_and(|threshold| win_rate < threshold) {
+            return BotDecision::Action(GameAction::Resign);
+        }
+        BotDecision::Move(best_move)
+    }
+
+    /// Como [`ActionPolicy::decide`], pero para una sesión que lleva en
+    /// `low_estimates` cuántas de sus estimaciones seguidas han quedado por
+    /// debajo del umbral: solo abandona cuando llegan a
+    /// [`RESIGN_CONFIRMATIONS`], así que un único mal resultado de la búsqueda
+    /// no termina la partida.
+    pub fn decide_in_session(
+        &self,
+        board: &GameY,
+        best_move: Coordinates,
+        win_rate: f32,
+        low_estimates: &mut u32,
+    ) -> BotDecision {
+        let decision = self.decide(board, best_move, win_rate);
+        if decision != BotDecision::Action(GameAction::Resign) {
+            *low_estimates = 0;
+            return decision;
+        }
+        *low_estimates += 1;
+        if *low_estimates >= RESIGN_CONFIRMATIONS {
+            decision
+        } else {
+            BotDecision::Move(best_move)
+        }
+    }
+
+    /// Variantes de reglas que el bot sabe jugar (para [`crate::BotMetadata`]).
+    pub fn rule_sets(&self) -> Vec<RuleSet> {
+        if self.swap {
+            vec![RuleSet::Standard, RuleSet::Swap]
+        } else {
+            vec![RuleSet::Standard]
+        }
+    }
+}
+
+/// Indica si se puede intercambiar: solo hay una jugada en la partida y es
+/// una colocación.
+pub fn is_swap_turn(board: &GameY) -> bool {
+    matches!(board.history(), [Movement::Placement { .. }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PlayerId;
+
+    fn opened_board() -> GameY {
+        let mut board = GameY::new(5);
+        board
+            .add_move(Movement::Placement { player: PlayerId::new(0), coords: Coordinates::new(2, 1, 1) })
+            .unwrap();
+        board
+    }
+
+    #[test]
+    fn test_default_policy_always_places() {
+        let policy = ActionPolicy::default();
+        let coords = Coordinates::new(4, 0, 0);
+        assert_eq!(policy.decide(&opened_board(), coords, 0.0), BotDecision::Move(coords));
+    }
+
+    #[test]
+    fn test_resigns_below_threshold() {
+        let policy = ActionPolicy { resign_threshold: Some(0.1), swap: false };
+        let coords = Coordinates::new(4, 0, 0);
+        let board = GameY::new(5);
+        assert_eq!(policy.decide(&board, coords, 0.05), BotDecision::Action(GameAction::Resign));
+        assert_eq!(policy.decide(&board, coords, 0.2), BotDecision::Move(coords));
+    }
+
+    #[test]
+    fn test_session_resigns_only_after_consecutive_low_estimates() {
+        let policy = ActionPolicy { resign_threshold: Some(0.1), swap: false };
+        let coords = Coordinates::new(4, 0, 0);
+        let board = GameY::new(5);
+        let mut low_estimates = 0;
+
+        assert_eq!(policy.decide_in_session(&board, coords, 0.05, &mut low_estimates), BotDecision::Move(coords));
+        // Una estimación mejor reinicia la cuenta.
+        assert_eq!(policy.decide_in_session(&board, coords, 0.5, &mut low_estimates), BotDecision::Move(coords));
+        assert_eq!(policy.decide_in_session(&board, coords, 0.05, &mut low_estimates), BotDecision::Move(coords));
+        assert_eq!(
+            policy.decide_in_session(&board, coords, 0.05, &mut low_estimates),
+            BotDecision::Action(GameAction::Resign)
+        );
+    }
+
+    #[test]
+    fn test_swaps_only_on_second_move() {
+        let policy = ActionPolicy { resign_threshold: Some(0.1), swap: true };
+        let coords = Coordinates::new(4, 0, 0);
+        let board = opened_board();
+        assert!(is_swap_turn(&board));
+        assert_eq!(policy.decide(&board, coords, 0.3), BotDecision::Action(GameAction::Swap));
+        // Con el intercambio disponible no se abandona: basta con intercambiar.
+        assert_eq!(policy.decide(&board, coords, 0.01), BotDecision::Action(GameAction::Swap));
+        assert_eq!(policy.decide(&board, coords, 0.7), BotDecision::Move(coords));
+
+        assert!(!is_swap_turn(&GameY::new(5)));
+        assert_eq!(policy.decide(&GameY::new(5), coords, 0.3), BotDecision::Move(coords));
+    }
+}
@@ -0,0 +1,511 @@
+//! Libro de aperturas.
+//!
+//! Las primeras jugadas casi siempre acaban cerca del centro, pero los bots
+//! gastan en ellas todo su presupuesto. El libro guarda, para cada posición de
+//! apertura, la jugada que eligió un bot fuerte en partidas de autojuego.
+//!
+//! Las posiciones se canonicalizan con las 6 simetrías del tablero triangular
+//! (permutaciones de las coordenadas x, y, z), así que una entrada sirve para
+//! todas las posiciones equivalentes. El libro se guarda como JSON
+//! ([`OpeningBook::save`] / [`OpeningBook::load`]) y [`BookBot`] lo consulta
+//! antes de delegar en cualquier otro [`YBot`].
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+
+use rand::Rng;
+use rand::seq::IndexedRandom;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BotAnalysis, BotDecision, BotMetadata, Cell, Coordinates, GameY, GameYError, Movement, Result, RuleSet,
+    SearchControl,
+};
+use super::actions::is_swap_turn;
+use super::ybot::{BotSession, YBot};
+
+/// Versión del formato del fichero del libro.
+pub const OPENING_BOOK_VERSION: u32 = 1;
+
+/// Las 6 simetrías del triángulo: `nueva[i] = vieja[perm[i]]`.
+const SYMMETRIES: [[usize; 3]; 6] = [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]];
+
+fn apply_symmetry(coords: &Coordinates, perm: &[usize; 3]) -> Coordinates {
+    let v = [coords.x(), coords.y(), coords.z()];
+    Coordinates::new(v[perm[0]], v[perm[1]], v[perm[2]])
+}
+
+fn undo_symmetry(coords: &Coordinates, perm: &[usize; 3]) -> Coordinates {
+    let v = [coords.x(), coords.y(), coords.z()];
+    let mut original = [0; 3];
+    for (i, &axis) in perm.iter().enumerate() {
+        original[axis] = v[i];
+    }
+    Coordinates::new(original[0], original[1], original[2])
+}
+
+/// Clave de la posición vista con la simetría `perm`:
+/// `"<tamaño>:<jugador que mueve>:<casillas>"`, con una letra por casilla
+/// (`.` vacía, `a` jugador 0, `b` jugador 1).
+fn position_key(board: &GameY, perm: &[usize; 3]) -> Option<String> {
+    let next = board.next_player()?;
+    let size = board.board_size();
+    let mut cells = vec!['.'; board.total_cells() as usize];
+    for idx in 0..board.total_cells() {
+        let coords = Coordinates::from_index(idx, size);
+        if let Cell::Occupied(player) = board.cell(&coords) {
+            let target = apply_symmetry(&coords, perm).to_index(size) as usize;
+            cells[target] = if player.id() == 0 { 'a' } else { 'b' };
+        }
+    }
+    Some(format!("{}:{}:{}", size, next.id(), cells.into_iter().collect::<String>()))
+}
+
+/// Clave canónica (la menor de las 6 simétricas) y la simetría que la produce.
+fn canonical_key(board: &GameY) -> Option<(String, [usize; 3])> {
+    SYMMETRIES
+        .iter()
+        .filter_map(|perm| Some((position_key(board, perm)?, *perm)))
+        .min_by(|a, b| a.0.cmp(&b.0))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookEntry {
+    /// Jugada (índice de casilla) en la orientación canónica.
+    pub cell: u32,
+    /// Partidas de autojuego en las que se eligió esta jugada.
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpeningBook {
+    version: u32,
+    entries: BTreeMap<String, BookEntry>,
+}
+
+impl OpeningBook {
+    pub fn new() -> Self {
+        Self {
+            version: OPENING_BOOK_VERSION,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Guarda `coords` como la jugada del libro para `board` (y sus simétricas).
+    pub fn insert(&mut self, board: &GameY, coords: Coordinates, count: u32) {
+        if let Some((key, perm)) = canonical_key(board) {
+            let cell = apply_symmetry(&coords, &perm).to_index(board.board_size());
+            self.entries.insert(key, BookEntry { cell, count });
+        }
+    }
+
+    /// Jugada del libro para `board`, ya deshecha la simetría. `None` si la
+    /// posición no está en el libro o la jugada guardada no es legal.
+    pub fn lookup(&self, board: &GameY) -> Option<Coordinates> {
+        let (key, perm) = canonical_key(board)?;
+        let entry = self.entries.get(&key)?;
+        let size = board.board_size();
+        if entry.cell >= board.total_cells() {
+            return None;
+        }
+        let coords = undo_symmetry(&Coordinates::from_index(entry.cell, size), &perm);
+        (board.cell(&coords) == Cell::Empty).then_some(coords)
+    }
+
+    /// Añade las entradas de `other`. Si una posición está en los dos libros se
+    /// queda la jugada con más partidas.
+    pub fn merge(&mut self, other: OpeningBook) {
+        for (key, entry) in other.entries {
+            let current = self.entries.entry(key).or_insert(entry);
+            if entry.count > current.count {
+                *current = entry;
+            }
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| GameYError::SerdeError { error: e })
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| GameYError::SerdeError { error: e })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let filename = path.as_ref().display().to_string();
+        let content = std::fs::read_to_string(path).map_err(|e| GameYError::IoError {
+            message: format!("Failed to read opening book: {}", filename),
+            error: e.to_string(),
+        })?;
+        Self::from_json(&content)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let filename = path.as_ref().display().to_string();
+        std::fs::write(path, self.to_json()?).map_err(|e| GameYError::IoError {
+            message: format!("Failed to write opening book: {}", filename),
+            error: e.to_string(),
+        })
+    }
+}
+
+impl Default for OpeningBook {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Acumula votos (posición canónica → jugada) y construye el libro con la
+/// jugada más votada de cada posición.
+#[derive(Debug, Default)]
+pub struct OpeningBookBuilder {
+    votes: HashMap<String, HashMap<u32, u32>>,
+}
+
+impl OpeningBookBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, board: &GameY, coords: Coordinates) {
+        if let Some((key, perm)) = canonical_key(board) {
+            let cell = apply_symmetry(&coords, &perm).to_index(board.board_size());
+            *self.votes.entry(key).or_default().entry(cell).or_default() += 1;
+        }
+    }
+
+    pub fn build(self) -> OpeningBook {
+        let mut book = OpeningBook::new();
+        for (key, votes) in self.votes {
+            // En caso de empate gana la casilla de menor índice, para que el resultado sea estable.
+            if let Some((&cell, &count)) = votes.iter().max_by_key(|&(&cell, &count)| (count, std::cmp::Reverse(cell))) {
+                book.entries.insert(key, BookEntry { cell, count });
+            }
+        }
+        book
+    }
+}
+
+/// Parámetros de la generación del libro por autojuego.
+#[derive(Debug, Clone, Copy)]
+pub struct BookGeneration {
+    pub size: u32,
+    /// Número de partidas de autojuego.
+    pub games: u32,
+    /// Jugadas que se registran de cada partida (profundidad del libro).
+    pub max_plies: u32,
+    /// Probabilidad de jugar una casilla al azar en lugar de la del bot, para
+    /// que el libro cubra más respuestas del rival. Esas jugadas no se registran.
+    pub exploration: f64,
+}
+
+/// Genera un libro jugando `games` partidas de `bot` contra sí mismo.
+pub fn generate_opening_book(bot: &dyn YBot, config: &BookGeneration) -> OpeningBook {
+    let mut builder = OpeningBookBuilder::new();
+    let mut rng = rand::rng();
+
+    for _ in 0..config.games {
+        let mut board = GameY::new(config.size);
+        for _ in 0..config.max_plies {
+            let Some(player) = board.next_player() else { break };
+            let Some(coords) = bot.choose_move(&board) else { break };
+            builder.record(&board, coords);
+
+            let coords = if rng.random_bool(config.exploration.clamp(0.0, 1.0)) {
+                let size = board.board_size();
+                board
+                    .available_cells()
+                    .choose(&mut rng)
+                    .map(|&idx| Coordinates::from_index(idx, size))
+                    .unwrap_or(coords)
+            } else {
+                coords
+            };
+            if board.add_move(Movement::Placement { player, coords }).is_err() {
+                break;
+            }
+        }
+    }
+
+    builder.build()
+}
+
+/// Envuelve un bot: juega la jugada del libro si la hay y, si no, delega en él.
+/// Conserva el nombre del bot envuelto.
+///
+/// Si el bot envuelto sabe intercambiar ([`RuleSet::Swap`]), en el turno del
+/// intercambio sus acciones no consultan el libro: el libro solo guarda
+/// colocaciones y taparía la decisión de intercambiar.
+pub struct BookBot {
+    book: Arc<OpeningBook>,
+    inner: Arc<dyn YBot>,
+    swaps: bool,
+}
+
+impl BookBot {
+    pub fn new(book: Arc<OpeningBook>, inner: Arc<dyn YBot>) -> Self {
+        let swaps = inner.metadata().rule_sets.contains(&RuleSet::Swap);
+        Self { book, inner, swaps }
+    }
+
+    /// La jugada del libro como acción, salvo en el turno del intercambio.
+    fn book_action(&self, board: &GameY) -> Option<BotDecision> {
+        book_action(&self.book, self.swaps, board)
+    }
+}
+
+fn book_action(book: &OpeningBook, swaps: bool, board: &GameY) -> Option<BotDecision> {
+    if swaps && is_swap_turn(board) {
+        return None;
+    }
+    book.lookup(board).map(BotDecision::Move)
+}
+
+impl YBot for BookBot {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn metadata(&self) -> BotMetadata {
+        self.inner.metadata()
+    }
+
+    fn choose_action(&self, board: &GameY) -> Option<BotDecision> {
+        self.book_action(board).or_else(|| self.inner.choose_action(board))
+    }
+
+    fn choose_action_seeded(&self, board: &GameY, seed: u64) -> Option<BotDecision> {
+        self.book_action(board).or_else(|| self.inner.choose_action_seeded(board, seed))
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        self.book.lookup(board).or_else(|| self.inner.choose_move(board))
+    }
+
+    fn choose_move_seeded(&self, board: &GameY, seed: u64) -> Option<Coordinates> {
+        self.book.lookup(board).or_else(|| self.inner.choose_move_seeded(board, seed))
+    }
+
+    fn choose_action_controlled(
+        &self,
+        board: &GameY,
+        seed: Option<u64>,
+        control: &SearchControl,
+    ) -> Option<BotDecision> {
+        self.book_action(board)
+            .or_else(|| self.inner.choose_action_controlled(board, seed, control))
+    }
+
+    fn choose_move_controlled(
+        &self,
+        board: &GameY,
+        seed: Option<u64>,
+        control: &SearchControl,
+    ) -> Option<Coordinates> {
+        self.book
+            .lookup(board)
+            .or_else(|| self.inner.choose_move_controlled(board, seed, control))
+    }
+
+    fn analyze(&self, board: &GameY) -> Option<BotAnalysis> {
+        self.inner.analyze(board)
+    }
+
+    fn analyze_controlled(&self, board: &GameY, control: &SearchControl) -> Option<BotAnalysis> {
+        self.inner.analyze_controlled(board, control)
+    }
+
+    fn new_session(&self) -> Option<Box<dyn BotSession>> {
+        let inner = self.inner.new_session()?;
+        Some(Box::new(BookSession {
+            book: self.book.clone(),
+            inner,
+            swaps: self.swaps,
+        }))
+    }
+}
+
+/// Sesión de un [`BookBot`]: consulta el libro antes que la sesión del bot envuelto.
+struct BookSession {
+    book: Arc<OpeningBook>,
+    inner: Box<dyn BotSession>,
+    swaps: bool,
+}
+
+impl BotSession for BookSession {
+    fn choose_action(&mut self, board: &GameY) -> Option<BotDecision> {
+        book_action(&self.book, self.swaps, board).or_else(|| self.inner.choose_action(board))
+    }
+
+    fn choose_move(&mut self, board: &GameY) -> Option<Coordinates> {
+        self.book.lookup(board).or_else(|| self.inner.choose_move(board))
+    }
+
+    fn choose_move_controlled(&mut self, board: &GameY, control: &SearchControl) -> Option<Coordinates> {
+        self.book
+            .lookup(board)
+            .or_else(|| self.inner.choose_move_controlled(board, control))
+    }
+
+    fn analyze(&mut self, board: &GameY) -> Option<BotAnalysis> {
+        self.inner.analyze(board)
+    }
+
+    fn ponder(&mut self, board: &GameY, stop: &AtomicBool, max_time: Duration) {
+        self.inner.ponder(board, stop, max_time);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameAction, MctsCompletoBot, PlayerId, RandomBot};
+
+    fn place(board: &mut GameY, player: u32, coords: Coordinates) {
+        board
+            .add_move(Movement::Placement { player: PlayerId::new(player), coords })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_symmetry_roundtrip() {
+        let coords = Coordinates::new(4, 1, 2);
+        for perm in &SYMMETRIES {
+            assert_eq!(undo_symmetry(&apply_symmetry(&coords, perm), perm), coords);
+        }
+    }
+
+    #[test]
+    fn test_lookup_uses_symmetric_positions() {
+        let mut book = OpeningBook::new();
+        let mut board = GameY::new(5);
+        place(&mut board, 0, Coordinates::new(4, 0, 0));
+        place(&mut board, 1, Coordinates::new(3, 1, 0));
+        book.insert(&board, Coordinates::new(2, 1, 1), 1);
+
+        // La misma posición reflejada (x <-> y).
+        let mut mirrored = GameY::new(5);
+        place(&mut mirrored, 0, Coordinates::new(0, 4, 0));
+        place(&mut mirrored, 1, Coordinates::new(1, 3, 0));
+        assert_eq!(book.lookup(&mirrored), Some(Coordinates::new(1, 2, 1)));
+        assert_eq!(book.lookup(&board), Some(Coordinates::new(2, 1, 1)));
+        assert_eq!(book.len(), 1);
+    }
+
+    #[test]
+    fn test_lookup_misses_unknown_positions() {
+        let mut book = OpeningBook::new();
+        book.insert(&GameY::new(5), Coordinates::new(2, 1, 1), 1);
+
+        assert!(book.lookup(&GameY::new(7)).is_none());
+        let mut board = GameY::new(5);
+        place(&mut board, 0, Coordinates::new(2, 1, 1));
+        assert!(book.lookup(&board).is_none());
+    }
+
+    #[test]
+    fn test_builder_keeps_most_voted_move() {
+        let board = GameY::new(5);
+        let mut builder = OpeningBookBuilder::new();
+        builder.record(&board, Coordinates::new(2, 1, 1));
+        builder.record(&board, Coordinates::new(2, 1, 1));
+        builder.record(&board, Coordinates::new(4, 0, 0));
+
+        let book = builder.build();
+        assert_eq!(book.lookup(&board), Some(Coordinates::new(2, 1, 1)));
+    }
+
+    #[test]
+    fn test_merge_keeps_most_played_entry() {
+        let board = GameY::new(5);
+        let mut book = OpeningBook::new();
+        book.insert(&board, Coordinates::new(2, 1, 1), 5);
+        let mut other = OpeningBook::new();
+        other.insert(&board, Coordinates::new(4, 0, 0), 2);
+        other.insert(&GameY::new(6), Coordinates::new(2, 2, 1), 1);
+
+        book.merge(other);
+        assert_eq!(book.len(), 2);
+        assert_eq!(book.lookup(&board), Some(Coordinates::new(2, 1, 1)));
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let mut book = OpeningBook::new();
+        book.insert(&GameY::new(7), Coordinates::new(2, 2, 2), 3);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("book.json");
+        book.save(&path).unwrap();
+
+        assert_eq!(OpeningBook::load(&path).unwrap(), book);
+        assert!(OpeningBook::load(dir.path().join("missing.json")).is_err());
+        assert!(OpeningBook::from_json("not json").is_err());
+    }
+
+    #[test]
+    fn test_generate_opening_book_records_first_moves() {
+        let config = BookGeneration { size: 4, games: 3, max_plies: 2, exploration: 0.0 };
+        let book = generate_opening_book(&MctsCompletoBot::new("mcts", 50), &config);
+
+        assert!(!book.is_empty());
+        assert!(book.lookup(&GameY::new(4)).is_some());
+    }
+
+    #[test]
+    fn test_book_bot_consults_book_before_search() {
+        let mut book = OpeningBook::new();
+        book.insert(&GameY::new(5), Coordinates::new(2, 1, 1), 1);
+        let bot = BookBot::new(Arc::new(book), Arc::new(RandomBot));
+
+        assert_eq!(bot.name(), "random_bot");
+        for _ in 0..10 {
+            assert_eq!(bot.choose_move(&GameY::new(5)), Some(Coordinates::new(2, 1, 1)));
+        }
+        assert!(bot.choose_move(&GameY::new(6)).is_some());
+        assert!(bot.new_session().is_none());
+    }
+
+    #[test]
+    fn test_book_bot_lets_a_swapping_bot_decide_the_swap_turn() {
+        let mut opened = GameY::new(4);
+        place(&mut opened, 0, Coordinates::new(1, 1, 1));
+        let corner = Coordinates::new(3, 0, 0);
+        let mut book = OpeningBook::new();
+        book.insert(&opened, corner, 1);
+        let book = Arc::new(book);
+
+        // La ficha central es muy fuerte: un bot con intercambio se la queda.
+        let swapping = BookBot::new(book.clone(), Arc::new(MctsCompletoBot::new("mcts", 2000).with_swap()));
+        assert_eq!(swapping.choose_action_seeded(&opened, 11), Some(BotDecision::Action(GameAction::Swap)));
+        let mut session = swapping.new_session().unwrap();
+        assert_ne!(session.choose_action(&opened), Some(BotDecision::Move(corner)));
+        // Las colocaciones siguen usando el libro.
+        assert_eq!(swapping.choose_move(&opened), Some(corner));
+
+        let standard = BookBot::new(book, Arc::new(MctsCompletoBot::new("mcts", 50)));
+        assert_eq!(standard.choose_action(&opened), Some(BotDecision::Move(corner)));
+    }
+
+    #[test]
+    fn test_book_session_consults_book_before_search() {
+        let mut book = OpeningBook::new();
+        book.insert(&GameY::new(5), Coordinates::new(2, 1, 1), 1);
+        let bot = BookBot::new(Arc::new(book), Arc::new(MctsCompletoBot::new("mcts", 50)));
+
+        let mut session = bot.new_session().unwrap();
+        assert_eq!(session.choose_move(&GameY::new(5)), Some(Coordinates::new(2, 1, 1)));
+        assert!(session.choose_move(&GameY::new(4)).is_some());
+    }
+}
@@ -0,0 +1,569 @@
+//! Declarative bot registry configuration.
+//!
+//! Bots are declared in a JSON file instead of being hard-coded, so their
+//! difficulty can be tuned without recompiling:
+//!
+//! ```json
+//! {
+//!   "bots": [
+//!     { "type": "random", "name": "random_bot" },
+//!     { "type": "mcts_completo", "name": "mcts_rave", "iterations": 30000, "rave": 1000.0 },
+//!     { "type": "mcts", "name": "mcts_rapido", "time_budget_ms": 500, "threads": 0, "seed": 7 },
+//!     { "type": "remote", "name": "equipo_b", "url": "http://localhost:4000", "bot_id": "mcts_dificil" }
+//!   ]
+//! }
+//! ```
+//!
+//! Every bot has a `type`, a unique `name` and optionally a `seed` (wraps it
+//! in a [`SeededBot`]) and an `opening_book` (a book file, wraps it in a
+//! [`BookBot`]). `display_name`, `description`, `difficulty` (1 to
+//! [`MAX_DIFFICULTY`]) and `max_board_size` override what the bot reports in
+//! its [`BotMetadata`]. The other fields depend on the type:
+//!
+//! | type            | fields                                                        |
+//! |-----------------|---------------------------------------------------------------|
+//! | `random`        | —                                                             |
+//! | `greedy`        | —                                                             |
+//! | `mcts`          | search fields                                                 |
+//! | `mcts_completo` | search fields, `rave`, `prior`, `max_nodes`                   |
+//! | `solver`        | `node_budget` and the `mcts_completo` fields for its fallback |
+//! | `external`      | `program`, `args`                                             |
+//! | `remote`        | `url`, `bot_id`, `timeout_ms`, `retries`                      |
+//!
+//! The search fields are `iterations`, `time_budget_ms`, `threads`,
+//! `playout` (`random`, `proximity` or `bridge`), `resign_threshold` and
+//! `swap`. At least one of `iterations` and `time_budget_ms` is required.
+//! Fields not listed for a bot's type are rejected.
+//!
+//! Relative paths are resolved against the directory of the configuration
+//! file. [`DEFAULT_BOT_CONFIG`] holds the bots the servers use when no file
+//! is given.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    BookBot, BotAnalysis, BotDecision, BotMetadata, BotSession, Coordinates, ExternalBot, GameY, GameYError, GreedyBot,
+    MctsBot, MctsCompletoBot, OpeningBook, RandomBot, RemoteBot, SearchControl, SeededBot, SolverBot, YBot, YBotRegistry,
+    playout_from_name, DEFAULT_SOLVER_NODE_BUDGET, MAX_DIFFICULTY,
+};
+
+/// Bots registered by default (`config/bots.json`).
+pub const DEFAULT_BOT_CONFIG: &str = include_str!("../../config/bots.json");
+
+/// A whole registry: the list of bots to build.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BotRegistryConfig {
+    pub bots: Vec<BotConfig>,
+    /// Directory relative paths are resolved against.
+    #[serde(skip)]
+    base_dir: Option<PathBuf>,
+}
+
+/// One bot of the registry.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BotConfig {
+    pub name: String,
+    #[serde(flatten)]
+    pub kind: BotKind,
+    /// Makes every decision reproducible from this seed.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Opening book file consulted before the bot searches.
+    #[serde(default)]
+    pub opening_book: Option<PathBuf>,
+    /// Overrides of the bot's [`BotMetadata`].
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub difficulty: Option<u8>,
+    #[serde(default)]
+    pub max_board_size: Option<u32>,
+}
+
+/// The kind of bot and its type-specific settings.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BotKind {
+    Random,
+    Greedy,
+    Mcts(SearchConfig),
+    MctsCompleto(MctsCompletoConfig),
+    Solver(SolverConfig),
+    External {
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default)]
+        timeout_ms: Option<u64>,
+    },
+    Remote {
+        url: String,
+        bot_id: String,
+        #[serde(default)]
+        timeout_ms: Option<u64>,
+        #[serde(default)]
+        retries: Option<u32>,
+    },
+}
+
+/// Settings shared by the MCTS bots.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SearchConfig {
+    #[serde(default)]
+    pub iterations: Option<u32>,
+    #[serde(default)]
+    pub time_budget_ms: Option<u64>,
+    /// Worker threads; `0` uses every core.
+    #[serde(default)]
+    pub threads: Option<usize>,
+    #[serde(default)]
+    pub playout: Option<String>,
+    #[serde(default)]
+    pub resign_threshold: Option<f32>,
+    #[serde(default)]
+    pub swap: bool,
+}
+
+/// Settings of an `mcts_completo` bot.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MctsCompletoConfig {
+    #[serde(flatten)]
+    pub search: SearchConfig,
+    #[serde(default)]
+    pub rave: Option<f32>,
+    #[serde(default)]
+    pub prior: Option<f32>,
+    #[serde(default)]
+    pub max_nodes: Option<usize>,
+}
+
+/// Settings of a `solver` bot; the rest configure its MCTS fallback.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SolverConfig {
+    #[serde(default)]
+    pub node_budget: Option<u64>,
+    #[serde(flatten)]
+    pub fallback: MctsCompletoConfig,
+}
+
+fn invalid(message: impl Into<String>) -> GameYError {
+    GameYError::InvalidBotConfig { message: message.into() }
+}
+
+/// Fields every bot accepts, whatever its type.
+const COMMON_FIELDS: &[&str] = &[
+    "type", "name", "seed", "opening_book", "display_name", "description", "difficulty", "max_board_size",
+];
+
+/// Fields of [`SearchConfig`].
+const SEARCH_FIELDS: &[&str] = &["iterations", "time_budget_ms", "threads", "playout", "resign_threshold", "swap"];
+
+/// Fields [`MctsCompletoConfig`] adds to [`SearchConfig`].
+const MCTS_COMPLETO_FIELDS: &[&str] = &["rave", "prior", "max_nodes"];
+
+/// Whether bots of type `kind` accept `field`.
+fn is_known_field(kind: &str, field: &str) -> bool {
+    let search = SEARCH_FIELDS.contains(&field);
+    let mcts_completo = search || MCTS_COMPLETO_FIELDS.contains(&field);
+    COMMON_FIELDS.contains(&field)
+        || match kind {
+            "mcts" => search,
+            "mcts_completo" => mcts_completo,
+            "solver" => mcts_completo || field == "node_budget",
+            "external" => ["program", "args", "timeout_ms"].contains(&field),
+            "remote" => ["url", "bot_id", "timeout_ms", "retries"].contains(&field),
+            _ => false,
+        }
+}
+
+/// Rejects bot fields that their type does not use. `deny_unknown_fields`
+/// cannot do it: it does not work through the flattened, tagged [`BotKind`].
+fn check_fields(json: &Value) -> Result<(), GameYError> {
+    let bots = json.get("bots").and_then(Value::as_array).into_iter().flatten();
+    for fields in bots.filter_map(Value::as_object) {
+        let kind = fields.get("type").and_then(Value::as_str).unwrap_or_default();
+        if let Some(field) = fields.keys().find(|field| !is_known_field(kind, field)) {
+            let name = fields.get("name").and_then(Value::as_str).unwrap_or_default();
+            return Err(invalid(format!("{name}: unknown field {field} for type {kind}")));
+        }
+    }
+    Ok(())
+}
+
+impl BotRegistryConfig {
+    pub fn from_json(json: &str) -> Result<Self, GameYError> {
+        let value: Value = serde_json::from_str(json).map_err(|e| invalid(e.to_string()))?;
+        let config = Self::deserialize(&value).map_err(|e| invalid(e.to_string()))?;
+        check_fields(&value)?;
+        Ok(config)
+    }
+
+    /// Reads a configuration file; relative paths inside it are resolved
+    /// against its directory.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, GameYError> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).map_err(|e| GameYError::IoError {
+            message: format!("reading bot config {}", path.display()),
+            error: e.to_string(),
+        })?;
+        let mut config = Self::from_json(&json)?;
+        config.base_dir = path.parent().map(Path::to_path_buf);
+        Ok(config)
+    }
+
+    /// The configuration in [`DEFAULT_BOT_CONFIG`].
+    pub fn default_config() -> Self {
+        Self::from_json(DEFAULT_BOT_CONFIG).expect("config/bots.json is valid")
+    }
+
+    /// Builds every bot. Fails on the first invalid bot or repeated name.
+    pub fn build(&self) -> Result<YBotRegistry, GameYError> {
+        let mut registry = YBotRegistry::new();
+        for bot in &self.bots {
+            if registry.find(&bot.name).is_some() {
+                return Err(invalid(format!("duplicate bot name: {}", bot.name)));
+            }
+            registry = registry.with_bot(bot.build(self.base_dir.as_deref())?);
+        }
+        Ok(registry)
+    }
+}
+
+impl BotConfig {
+    /// Builds the bot; relative paths are resolved against `base_dir`.
+    pub fn build(&self, base_dir: Option<&Path>) -> Result<Arc<dyn YBot>, GameYError> {
+        let name = self.name.clone();
+        let mut bot: Arc<dyn YBot> = match &self.kind {
+            BotKind::Random => Arc::new(RandomBot),
+            BotKind::Greedy => Arc::new(GreedyBot),
+            BotKind::Mcts(search) => Arc::new(mcts(&name, search)?),
+            BotKind::MctsCompleto(config) => Arc::new(mcts_completo(&name, config)?),
+            BotKind::Solver(config) => Arc::new(SolverBot::new(
+                name.clone(),
+                config.node_budget.unwrap_or(DEFAULT_SOLVER_NODE_BUDGET),
+                mcts_completo(&name, &config.fallback)?,
+            )),
+            BotKind::External { program, args, timeout_ms } => {
+                let mut external = ExternalBot::new(name, program.clone(), args.clone());
+                if let Some(ms) = timeout_ms {
+                    external = external.with_reply_timeout(Duration::from_millis(*ms));
+                }
+                Arc::new(external)
+            }
+            BotKind::Remote { url, bot_id, timeout_ms, retries } => {
+                let mut remote = RemoteBot::new(name, url.clone(), bot_id.clone());
+                if let Some(ms) = timeout_ms {
+                    remote = remote.with_timeout(Duration::from_millis(*ms));
+                }
+                if let Some(retries) = retries {
+                    remote = remote.with_retries(*retries);
+                }
+                Arc::new(remote)
+            }
+        };
+
+        if let Some(seed) = self.seed {
+            bot = Arc::new(SeededBot::new(bot, seed));
+        }
+        if let Some(path) = &self.opening_book {
+            let path = match base_dir {
+                Some(dir) if path.is_relative() => dir.join(path),
+                _ => path.clone(),
+            };
+            let book = OpeningBook::load(&path)
+                .map_err(|e| invalid(format!("{}: opening book {}: {e}", self.name, path.display())))?;
+            bot = Arc::new(BookBot::new(Arc::new(book), bot));
+        }
+        self.configure(bot)
+    }
+
+    /// Applies the configured name and metadata overrides, wrapping the bot
+    /// only if something changes.
+    fn configure(&self, bot: Arc<dyn YBot>) -> Result<Arc<dyn YBot>, GameYError> {
+        let overrides = self.display_name.is_some()
+            || self.description.is_some()
+            || self.difficulty.is_some()
+            || self.max_board_size.is_some();
+        if bot.name() == self.name && !overrides {
+            return Ok(bot);
+        }
+
+        let mut metadata = bot.metadata();
+        if metadata.display_name == bot.name() {
+            metadata.display_name = self.name.clone();
+        }
+        if let Some(display_name) = &self.display_name {
+            metadata.display_name = display_name.clone();
+        }
+        if let Some(description) = &self.description {
+            metadata.description = description.clone();
+        }
+        if let Some(difficulty) = self.difficulty {
+            if !(1..=MAX_DIFFICULTY).contains(&difficulty) {
+                return Err(invalid(format!(
+                    "{}: difficulty must be between 1 and {MAX_DIFFICULTY}",
+                    self.name
+                )));
+            }
+            metadata.difficulty = Some(difficulty);
+        }
+        if let Some(max_board_size) = self.max_board_size {
+            metadata.max_board_size = Some(max_board_size);
+        }
+        Ok(Arc::new(ConfiguredBot { name: self.name.clone(), metadata, inner: bot }))
+    }
+}
+
+fn iterations(name: &str, search: &SearchConfig) -> Result<u32, GameYError> {
+    match (search.iterations, search.time_budget_ms) {
+        (Some(iterations), _) => Ok(iterations),
+        (None, Some(_)) => Ok(u32::MAX),
+        (None, None) => Err(invalid(format!("{name}: iterations or time_budget_ms is required"))),
+    }
+}
+
+fn playout(name: &str, search: &SearchConfig) -> Result<Option<Arc<dyn crate::PlayoutPolicy>>, GameYError> {
+    search
+        .playout
+        .as_deref()
+        .map(|playout| {
+            playout_from_name(playout).ok_or_else(|| invalid(format!("{name}: unknown playout {playout}")))
+        })
+        .transpose()
+}
+
+fn mcts(name: &str, search: &SearchConfig) -> Result<MctsBot, GameYError> {
+    let mut bot = MctsBot::new(name, iterations(name, search)?);
+    if let Some(budget) = search.time_budget_ms {
+        bot = bot.with_time_budget(Duration::from_millis(budget));
+    }
+    if let Some(threads) = search.threads {
+        bot = bot.with_threads(threads);
+    }
+    if let Some(playout) = playout(name, search)? {
+        bot = bot.with_playout(playout);
+    }
+    if let Some(threshold) = search.resign_threshold {
+        bot = bot.with_resign_threshold(threshold);
+    }
+    if search.swap {
+        bot = bot.with_swap();
+    }
+    Ok(bot)
+}
+
+fn mcts_completo(name: &str, config: &MctsCompletoConfig) -> Result<MctsCompletoBot, GameYError> {
+    let search = &config.search;
+    let mut bot = MctsCompletoBot::new(name, iterations(name, search)?);
+    if let Some(budget) = search.time_budget_ms {
+        bot = bot.with_time_budget(Duration::from_millis(budget));
+    }
+    if let Some(threads) = search.threads {
+        bot = bot.with_threads(threads);
+    }
+    if let Some(playout) = playout(name, search)? {
+        bot = bot.with_playout(playout);
+    }
+    if let Some(threshold) = search.resign_threshold {
+        bot = bot.with_resign_threshold(threshold);
+    }
+    if search.swap {
+        bot = bot.with_swap();
+    }
+    if let Some(equivalence) = config.rave {
+        bot = bot.with_rave(equivalence);
+    }
+    if let Some(weight) = config.prior {
+        bot = bot.with_prior(weight);
+    }
+    if let Some(max_nodes) = config.max_nodes {
+        bot = bot.with_max_nodes(max_nodes);
+    }
+    Ok(bot)
+}
+
+/// A bot exposed under the configured name and metadata.
+struct ConfiguredBot {
+    name: String,
+    metadata: BotMetadata,
+    inner: Arc<dyn YBot>,
+}
+
+impl YBot for ConfiguredBot {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn metadata(&self) -> BotMetadata {
+        self.metadata.clone()
+    }
+
+    fn choose_action(&self, board: &GameY) -> Option<BotDecision> {
+        self.inner.choose_action(board)
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        self.inner.choose_move(board)
+    }
+
+    fn choose_action_seeded(&self, board: &GameY, seed: u64) -> Option<BotDecision> {
+        self.inner.choose_action_seeded(board, seed)
+    }
+
+    fn choose_move_seeded(&self, board: &GameY, seed: u64) -> Option<Coordinates> {
+        self.inner.choose_move_seeded(board, seed)
+    }
+
+    fn choose_action_controlled(
+        &self,
+        board: &GameY,
+        seed: Option<u64>,
+        control: &SearchControl,
+    ) -> Option<BotDecision> {
+        self.inner.choose_action_controlled(board, seed, control)
+    }
+
+    fn choose_move_controlled(
+        &self,
+        board: &GameY,
+        seed: Option<u64>,
+        control: &SearchControl,
+    ) -> Option<Coordinates> {
+        self.inner.choose_move_controlled(board, seed, control)
+    }
+
+    fn analyze(&self, board: &GameY) -> Option<BotAnalysis> {
+        self.inner.analyze(board)
+    }
+
+    fn analyze_controlled(&self, board: &GameY, control: &SearchControl) -> Option<BotAnalysis> {
+        self.inner.analyze_controlled(board, control)
+    }
+
+    fn new_session(&self) -> Option<Box<dyn BotSession>> {
+        self.inner.new_session()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_builds() {
+        let registry = BotRegistryConfig::default_config().build().unwrap();
+        for name in ["random_bot", "greedy_bot", "mcts_medio", "mcts_puentes", "mcts_evaluado", "solver"] {
+            assert_eq!(registry.find(name).unwrap().name(), name);
+        }
+        assert_eq!(registry.names().len(), 10);
+    }
+
+    #[test]
+    fn test_builds_every_type() {
+        let config = BotRegistryConfig::from_json(
+            r#"{ "bots": [
+                { "type": "random", "name": "facil", "seed": 3 },
+                { "type": "mcts", "name": "rapido", "time_budget_ms": 50, "playout": "proximity" },
+                { "type": "mcts_completo", "name": "completo", "iterations": 100, "rave": 500.0, "prior": 5.0 },
+                { "type": "solver", "name": "exacto", "node_budget": 1000, "iterations": 100 },
+                { "type": "external", "name": "motor", "program": "gamey", "args": ["--mode", "engine"] },
+                { "type": "remote", "name": "lejano", "url": "http://localhost:1", "bot_id": "x", "retries": 0 }
+            ] }"#,
+        )
+        .unwrap();
+
+        let registry = config.build().unwrap();
+        assert_eq!(registry.names().len(), 6);
+        let facil = registry.find("facil").unwrap();
+        assert_eq!(facil.name(), "facil");
+        assert_eq!(facil.choose_move(&GameY::new(5)), facil.choose_move(&GameY::new(5)));
+        assert!(registry.find("completo").unwrap().choose_move(&GameY::new(3)).is_some());
+    }
+
+    #[test]
+    fn test_metadata_overrides() {
+        let registry = BotRegistryConfig::from_json(
+            r#"{ "bots": [
+                { "type": "random", "name": "facil" },
+                { "type": "mcts_completo", "name": "maestro", "iterations": 100,
+                  "display_name": "Maestro", "difficulty": 5, "max_board_size": 9 }
+            ] }"#,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+        let facil = registry.find("facil").unwrap().metadata();
+        assert_eq!(facil.display_name, "Random");
+        assert_eq!(facil.difficulty, Some(1));
+
+        let maestro = registry.find("maestro").unwrap().metadata();
+        assert_eq!(maestro.display_name, "Maestro");
+        assert_eq!(maestro.difficulty, Some(5));
+        assert_eq!(maestro.max_board_size, Some(9));
+        assert!(maestro.supports_analysis);
+    }
+
+    #[test]
+    fn test_rejects_invalid_configs() {
+        for json in [
+            r#"{ "bots": [ { "type": "mcts", "name": "sin_iteraciones" } ] }"#,
+            r#"{ "bots": [ { "type": "mcts", "name": "x", "iterations": 10, "playout": "magic" } ] }"#,
+            r#"{ "bots": [ { "type": "random", "name": "a" }, { "type": "greedy", "name": "a" } ] }"#,
+            r#"{ "bots": [ { "type": "quantum", "name": "q" } ] }"#,
+            r#"{ "bots": [ { "type": "random", "name": "r", "difficulty": 6 } ] }"#,
+            r#"{ "bot": [] }"#,
+            r#"{ "bots": [ { "type": "mcts", "name": "x", "iterations": 10, "iteratons": 20 } ] }"#,
+            r#"{ "bots": [ { "type": "mcts", "name": "x", "iterations": 10, "rave": 1000.0 } ] }"#,
+            r#"{ "bots": [ { "type": "random", "name": "r", "iterations": 10 } ] }"#,
+            r#"{ "bots": [ { "type": "remote", "name": "r", "url": "http://x", "bot_id": "b", "args": [] } ] }"#,
+        ] {
+            let result = BotRegistryConfig::from_json(json).and_then(|config| config.build());
+            assert!(
+                matches!(result, Err(GameYError::InvalidBotConfig { .. })),
+                "accepted {json}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_rejects_unknown_fields_by_name() {
+        let json = r#"{ "bots": [ { "type": "mcts_completo", "name": "typo", "iterations": 10, "treads": 1 } ] }"#;
+        let Err(GameYError::InvalidBotConfig { message }) = BotRegistryConfig::from_json(json) else {
+            panic!("accepted an unknown field");
+        };
+        assert!(message.contains("treads"), "{message}");
+    }
+
+    #[test]
+    fn test_load_resolves_book_next_to_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut book = OpeningBook::new();
+        book.insert(&GameY::new(4), Coordinates::new(1, 1, 1), 1);
+        book.save(dir.path().join("book.json")).unwrap();
+        let path = dir.path().join("bots.json");
+        std::fs::write(
+            &path,
+            r#"{ "bots": [ { "type": "random", "name": "random_bot", "opening_book": "book.json" } ] }"#,
+        )
+        .unwrap();
+
+        let registry = BotRegistryConfig::load(&path).unwrap().build().unwrap();
+        let bot = registry.find("random_bot").unwrap();
+        assert_eq!(bot.choose_move(&GameY::new(4)), Some(Coordinates::new(1, 1, 1)));
+    }
+
+    #[test]
+    fn test_load_reports_missing_file() {
+        let err = BotRegistryConfig::load("/nonexistent/bots.json").unwrap_err();
+        assert!(matches!(err, GameYError::IoError { .. }));
+    }
+}
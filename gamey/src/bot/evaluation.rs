@@ -0,0 +1,307 @@
+//! Evaluación estática de posiciones basada en conectividad.
+//!
+//! Para cada jugador y cada lado del triángulo se calcula la *two-distance*
+//! de cada casilla al lado: una casilla junto al lado (o unida a un grupo que
+//! ya lo toca) está a distancia 1; el resto está a `1 +` la **segunda** menor
+//! distancia de sus vecinas, porque el rival puede cortar la mejor de ellas.
+//! Las fichas propias se agrupan con union-find y cada grupo cuenta como una
+//! sola pieza: todas las casillas libres de su frontera son vecinas entre sí.
+//!
+//! Con las tres distancias, [`stones_to_connect`] estima cuántas fichas le
+//! faltan a un jugador para formar su Y, y [`evaluate`] compara esa cifra
+//! entre los dos jugadores.
+
+use crate::{Cell, Coordinates, GameY, PlayerId};
+
+/// Distancia de las casillas desde las que no se puede llegar a un lado.
+pub const UNREACHABLE: u32 = u32::MAX / 4;
+
+/// Escala de [`win_probability`]: con esta diferencia de fichas la
+/// probabilidad estimada es de un 73 %.
+const EVALUATION_SCALE: f32 = 2.0;
+
+/// Two-distance de cada casilla (por índice) a los lados A, B y C para
+/// `player`. Las casillas del rival quedan a [`UNREACHABLE`] y las propias
+/// a la distancia de su grupo.
+pub fn side_distances(board: &GameY, player: PlayerId) -> [Vec<u32>; 3] {
+    let connectivity = Connectivity::new(board, player);
+    [0, 1, 2].map(|side| connectivity.two_distance(side))
+}
+
+/// Número estimado de fichas que le faltan a `player` para unir los tres
+/// lados (`0` si ya los une). [`UNREACHABLE`] si el rival ya lo ha impedido.
+pub fn stones_to_connect(board: &GameY, player: PlayerId) -> u32 {
+    let distances = side_distances(board, player);
+    let size = board.board_size();
+
+    (0..board.total_cells() as usize)
+        .filter_map(|cell| {
+            let sum: u32 = distances.iter().map(|side| side[cell]).sum();
+            if distances.iter().any(|side| side[cell] >= UNREACHABLE) {
+                return None;
+            }
+            match board.cell(&Coordinates::from_index(cell as u32, size)) {
+                // La casilla libre cuenta en las tres distancias pero es una sola ficha.
+                Cell::Empty => Some(sum - 2),
+                Cell::Occupied(owner) if owner == player => Some(sum),
+                Cell::Occupied(_) => None,
+            }
+        })
+        .min()
+        .unwrap_or(UNREACHABLE)
+}
+
+/// Ventaja de `player`: cuántas fichas menos que el rival le faltan para
+/// ganar. Positivo es bueno para `player`.
+pub fn evaluate(board: &GameY, player: PlayerId) -> i32 {
+    let cap = board.total_cells() + 1;
+    let mine = stones_to_connect(board, player).min(cap) as i32;
+    let theirs = stones_to_connect(board, opponent(player)).min(cap) as i32;
+    theirs - mine
+}
+
+/// Convierte [`evaluate`] en una probabilidad de victoria aproximada para
+/// `player`, en `[0, 1]`.
+pub fn win_probability(board: &GameY, player: PlayerId) -> f32 {
+    let advantage = evaluate(board, player) as f32;
+    1.0 / (1.0 + (-advantage / EVALUATION_SCALE).exp())
+}
+
+fn opponent(player: PlayerId) -> PlayerId {
+    PlayerId::new(1 - player.id())
+}
+
+/// Estructura del tablero vista por un jugador: grupos de fichas propias y,
+/// para cada casilla libre, sus vecinas contando los grupos adyacentes.
+struct Connectivity {
+    /// Estado de cada casilla por índice.
+    cells: Vec<CellKind>,
+    /// Lados (A, B, C) que toca cada casilla.
+    sides: Vec<[bool; 3]>,
+    /// Para casillas libres: vecinas libres, directas o a través de un grupo propio.
+    reach: Vec<Vec<usize>>,
+    /// Para casillas libres: lados alcanzados a través de un grupo propio adyacente.
+    group_sides: Vec<[bool; 3]>,
+    /// Representante union-find de cada ficha propia.
+    group_of: Vec<usize>,
+    /// Frontera (casillas libres adyacentes) de cada grupo, por representante.
+    frontier: Vec<Vec<usize>>,
+    /// Lados que toca cada grupo, por representante.
+    touches: Vec<[bool; 3]>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CellKind {
+    Empty,
+    Mine,
+    Theirs,
+}
+
+impl Connectivity {
+    fn new(board: &GameY, player: PlayerId) -> Self {
+        let size = board.board_size();
+        let total = board.total_cells() as usize;
+        let coords: Vec<Coordinates> = (0..total as u32).map(|i| Coordinates::from_index(i, size)).collect();
+        let cells: Vec<CellKind> = coords
+            .iter()
+            .map(|c| match board.cell(c) {
+                Cell::Empty => CellKind::Empty,
+                Cell::Occupied(owner) if owner == player => CellKind::Mine,
+                Cell::Occupied(_) => CellKind::Theirs,
+            })
+            .collect();
+        let sides: Vec<[bool; 3]> =
+            coords.iter().map(|c| [c.touches_side_a(), c.touches_side_b(), c.touches_side_c()]).collect();
+        let neighbors: Vec<Vec<usize>> = coords
+            .iter()
+            .map(|c| board.get_neighbors(c).iter().map(|n| n.to_index(size) as usize).collect())
+            .collect();
+
+        // Union-find de las fichas propias.
+        let mut parent: Vec<usize> = (0..total).collect();
+        for cell in 0..total {
+            if cells[cell] != CellKind::Mine {
+                continue;
+            }
+            for &n in &neighbors[cell] {
+                if cells[n] == CellKind::Mine {
+                    let (a, b) = (find(&mut parent, cell), find(&mut parent, n));
+                    if a != b {
+                        parent[a] = b;
+                    }
+                }
+            }
+        }
+        let group_of: Vec<usize> = (0..total).map(|cell| find(&mut parent, cell)).collect();
+
+        let mut frontier = vec![Vec::new(); total];
+        let mut touches = vec![[false; 3]; total];
+        for cell in (0..total).filter(|&cell| cells[cell] == CellKind::Mine) {
+            let root = group_of[cell];
+            for side in 0..3 {
+                touches[root][side] |= sides[cell][side];
+            }
+            for &n in &neighbors[cell] {
+                if cells[n] == CellKind::Empty && !frontier[root].contains(&n) {
+                    frontier[root].push(n);
+                }
+            }
+        }
+
+        let mut reach = vec![Vec::new(); total];
+        let mut group_sides = vec![[false; 3]; total];
+        for cell in (0..total).filter(|&cell| cells[cell] == CellKind::Empty) {
+            let mut cell_reach: Vec<usize> = Vec::new();
+            for &n in &neighbors[cell] {
+                match cells[n] {
+                    CellKind::Empty => cell_reach.push(n),
+                    CellKind::Mine => {
+                        let root = group_of[n];
+                        for side in 0..3 {
+                            group_sides[cell][side] |= touches[root][side];
+                        }
+                        cell_reach.extend(frontier[root].iter().copied());
+                    }
+                    CellKind::Theirs => {}
+                }
+            }
+            cell_reach.sort_unstable();
+            cell_reach.dedup();
+            cell_reach.retain(|&n| n != cell);
+            reach[cell] = cell_reach;
+        }
+
+        Self { cells, sides, reach, group_sides, group_of, frontier, touches }
+    }
+
+    /// Two-distance de cada casilla al lado `side`, iterando hasta el punto fijo.
+    fn two_distance(&self, side: usize) -> Vec<u32> {
+        let total = self.cells.len();
+        let mut distance = vec![UNREACHABLE; total];
+        let empties: Vec<usize> = (0..total).filter(|&cell| self.cells[cell] == CellKind::Empty).collect();
+
+        for &cell in &empties {
+            if self.sides[cell][side] || self.group_sides[cell][side] {
+                distance[cell] = 1;
+            }
+        }
+
+        // Las distancias solo bajan, así que el bucle termina.
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &cell in &empties {
+                let (mut best, mut second) = (UNREACHABLE, UNREACHABLE);
+                for &n in &self.reach[cell] {
+                    let d = distance[n];
+                    if d < best {
+                        second = best;
+                        best = d;
+                    } else if d < second {
+                        second = d;
+                    }
+                }
+                let candidate = (second + 1).min(UNREACHABLE);
+                if candidate < distance[cell] {
+                    distance[cell] = candidate;
+                    changed = true;
+                }
+            }
+        }
+
+        // Cada ficha propia está a la distancia de su grupo: 0 si ya toca el
+        // lado y, si no, la de la mejor casilla de su frontera.
+        for cell in (0..total).filter(|&cell| self.cells[cell] == CellKind::Mine) {
+            let root = self.group_of[cell];
+            distance[cell] = if self.touches[root][side] {
+                0
+            } else {
+                self.frontier[root].iter().map(|&n| distance[n]).min().unwrap_or(UNREACHABLE)
+            };
+        }
+        distance
+    }
+}
+
+fn find(parent: &mut [usize], mut cell: usize) -> usize {
+    while parent[cell] != cell {
+        parent[cell] = parent[parent[cell]];
+        cell = parent[cell];
+    }
+    cell
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Movement;
+
+    fn place(board: &mut GameY, player: u32, coords: Coordinates) {
+        board
+            .add_move(Movement::Placement { player: PlayerId::new(player), coords })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_empty_board_distances() {
+        let board = GameY::new(4);
+        let [a, _, _] = side_distances(&board, PlayerId::new(0));
+
+        // Las casillas del lado A (x = 0) están a distancia 1.
+        assert_eq!(a[Coordinates::new(0, 3, 0).to_index(4) as usize], 1);
+        assert_eq!(a[Coordinates::new(0, 1, 2).to_index(4) as usize], 1);
+        // Una fila más allá hacen falta dos vecinas a distancia 1.
+        assert_eq!(a[Coordinates::new(1, 1, 1).to_index(4) as usize], 2);
+        assert_eq!(a[Coordinates::new(3, 0, 0).to_index(4) as usize], 4);
+    }
+
+    #[test]
+    fn test_groups_touching_a_side_shorten_distances() {
+        let mut board = GameY::new(5);
+        // Cadena del jugador 0 desde el lado A hacia el interior.
+        place(&mut board, 0, Coordinates::new(0, 2, 2));
+        place(&mut board, 1, Coordinates::new(4, 0, 0));
+        place(&mut board, 0, Coordinates::new(1, 2, 1));
+
+        let [a, _, _] = side_distances(&board, PlayerId::new(0));
+        assert_eq!(a[Coordinates::new(1, 2, 1).to_index(5) as usize], 0);
+        // Junto al grupo que ya toca A basta una ficha.
+        assert_eq!(a[Coordinates::new(2, 1, 1).to_index(5) as usize], 1);
+    }
+
+    #[test]
+    fn test_opponent_stones_are_unreachable() {
+        let mut board = GameY::new(4);
+        place(&mut board, 0, Coordinates::new(1, 1, 1));
+        let distances = side_distances(&board, PlayerId::new(1));
+        let center = Coordinates::new(1, 1, 1).to_index(4) as usize;
+        assert!(distances.iter().all(|side| side[center] == UNREACHABLE));
+    }
+
+    #[test]
+    fn test_stones_to_connect_on_small_boards() {
+        // En un tablero de tamaño 1 basta con una ficha.
+        assert_eq!(stones_to_connect(&GameY::new(1), PlayerId::new(0)), 1);
+
+        let mut board = GameY::new(2);
+        place(&mut board, 0, Coordinates::new(1, 0, 0));
+        place(&mut board, 1, Coordinates::new(0, 1, 0));
+        assert_eq!(stones_to_connect(&board, PlayerId::new(1)), 1);
+
+        // Con la última casilla el jugador 0 une los tres lados y el 1 ya no puede.
+        place(&mut board, 0, Coordinates::new(0, 0, 1));
+        assert_eq!(stones_to_connect(&board, PlayerId::new(0)), 0);
+        assert_eq!(stones_to_connect(&board, PlayerId::new(1)), UNREACHABLE);
+    }
+
+    #[test]
+    fn test_evaluation_favours_the_player_with_the_center() {
+        let mut board = GameY::new(5);
+        place(&mut board, 0, Coordinates::new(2, 1, 1));
+
+        assert!(evaluate(&board, PlayerId::new(0)) > 0);
+        assert_eq!(evaluate(&board, PlayerId::new(0)), -evaluate(&board, PlayerId::new(1)));
+        assert!(win_probability(&board, PlayerId::new(0)) > 0.5);
+        assert!(win_probability(&GameY::new(5), PlayerId::new(0)) == 0.5);
+    }
+}
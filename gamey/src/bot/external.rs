@@ -0,0 +1,313 @@
+//! Bot backed by an external engine process.
+//!
+//! [`ExternalBot`] starts a program that speaks the engine protocol of
+//! [`crate::engine`] on its stdin/stdout (for example `gamey --mode engine`
+//! or any GTP-style Y engine that uses cell indices as vertices) and asks it
+//! for moves. The process is started on first use and kept for later calls;
+//! if it fails, replies with something unexpected or does not reply in time,
+//! it is killed and a new one is started on the next call.
+//!
+//! Every reply must arrive within the bot's reply timeout
+//! ([`DEFAULT_ENGINE_REPLY_TIMEOUT`] unless changed), and within the deadline
+//! of the [`SearchControl`] when the bot is driven through
+//! [`YBot::choose_action_controlled`]. Cancelling the control also stops the wait.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::engine::{engine_color, engine_vertex};
+use crate::{BotDecision, BotMetadata, Coordinates, GameAction, GameY, Movement};
+use super::ybot::{SearchControl, YBot};
+
+/// Longest wait for a single reply of the engine.
+pub const DEFAULT_ENGINE_REPLY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often a wait for a reply checks whether it was cancelled.
+const CANCEL_POLL: Duration = Duration::from_millis(50);
+
+/// A running engine process and its pipes.
+///
+/// The engine's output is read by a separate thread, so that waiting for a
+/// reply can give up without blocking on the pipe.
+struct EngineProcess {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: Receiver<io::Result<String>>,
+}
+
+impl EngineProcess {
+    fn spawn(program: &str, args: &[String]) -> io::Result<Self> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let stdin = child.stdin.take().ok_or_else(|| io::Error::other("engine stdin unavailable"))?;
+        let stdout = child.stdout.take().ok_or_else(|| io::Error::other("engine stdout unavailable"))?;
+        Ok(Self { child, stdin, stdout: read_lines(stdout) })
+    }
+
+    /// Waits for the next output line until `deadline`, or until `control`
+    /// is cancelled.
+    fn next_line(&self, deadline: Instant, control: &SearchControl) -> io::Result<String> {
+        loop {
+            let wait = deadline.saturating_duration_since(Instant::now()).min(CANCEL_POLL);
+            match self.stdout.recv_timeout(wait) {
+                Ok(line) => return line,
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "engine closed its output"));
+                }
+                Err(RecvTimeoutError::Timeout) if control.is_cancelled() => {
+                    return Err(io::Error::new(io::ErrorKind::Interrupted, "search cancelled"));
+                }
+                Err(RecvTimeoutError::Timeout) if Instant::now() >= deadline => {
+                    return Err(io::Error::new(io::ErrorKind::TimedOut, "engine did not reply in time"));
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+            }
+        }
+    }
+
+    /// Sends one command and returns the text of a successful reply, which
+    /// must arrive before `deadline`.
+    fn request(&mut self, command: &str, deadline: Instant, control: &SearchControl) -> io::Result<String> {
+        writeln!(self.stdin, "{command}")?;
+        self.stdin.flush()?;
+
+        let mut lines = Vec::new();
+        loop {
+            let line = self.next_line(deadline, control)?;
+            let line = line.trim_end();
+            if line.is_empty() {
+                if lines.is_empty() {
+                    continue;
+                }
+                break;
+            }
+            lines.push(line.to_string());
+        }
+
+        // The id after `=`/`?` is not used: requests are sent without one.
+        let reply = lines.join("\n");
+        if let Some(text) = reply.strip_prefix('=') {
+            Ok(text.trim().to_string())
+        } else if let Some(text) = reply.strip_prefix('?') {
+            Err(io::Error::other(format!("engine refused `{command}`: {}", text.trim())))
+        } else {
+            Err(io::Error::other(format!("malformed engine reply: {reply}")))
+        }
+    }
+}
+
+/// Forwards the lines of `stdout` to a channel from a thread of their own.
+/// The thread ends when the engine closes its output (for example when it is
+/// killed) or nobody is listening any more.
+fn read_lines(stdout: ChildStdout) -> Receiver<io::Result<String>> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            let failed = line.is_err();
+            if sender.send(line).is_err() || failed {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+impl Drop for EngineProcess {
+    fn drop(&mut self) {
+        let _ = writeln!(self.stdin, "quit");
+        let _ = self.stdin.flush();
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// A bot whose decisions come from an external engine process.
+pub struct ExternalBot {
+    name: String,
+    program: String,
+    args: Vec<String>,
+    reply_timeout: Duration,
+    process: Mutex<Option<EngineProcess>>,
+}
+
+impl ExternalBot {
+    /// Creates a bot that runs `program` with `args` when it first needs a move.
+    pub fn new(name: impl Into<String>, program: impl Into<String>, args: Vec<String>) -> Self {
+        Self {
+            name: name.into(),
+            program: program.into(),
+            args,
+            reply_timeout: DEFAULT_ENGINE_REPLY_TIMEOUT,
+            process: Mutex::new(None),
+        }
+    }
+
+    /// Changes how long the bot waits for each reply of the engine.
+    pub fn with_reply_timeout(mut self, reply_timeout: Duration) -> Self {
+        self.reply_timeout = reply_timeout;
+        self
+    }
+
+    /// Sets up `board` on the engine and asks it for a move under `control`.
+    ///
+    /// The whole game is replayed on every call, so the engine does not need
+    /// to have seen the previous positions.
+    fn decide(&self, board: &GameY, control: &SearchControl) -> io::Result<BotDecision> {
+        let player = board
+            .next_player()
+            .ok_or_else(|| io::Error::other("the game is over"))?;
+
+        let mut guard = self.process.lock().unwrap_or_else(|e| e.into_inner());
+        if guard.is_none() {
+            *guard = Some(EngineProcess::spawn(&self.program, &self.args)?);
+        }
+        let process = guard.as_mut().expect("engine process started above");
+
+        let mut request = |command: &str| {
+            let deadline = control
+                .deadline_within(Some(self.reply_timeout))
+                .expect("a reply timeout always gives a deadline");
+            process.request(command, deadline, control)
+        };
+        let result = (|| {
+            let size = board.board_size();
+            request(&format!("boardsize {size}"))?;
+            request("clear_board")?;
+            for movement in board.history() {
+                let (Movement::Placement { player: mover, .. } | Movement::Action { player: mover, .. }) = movement;
+                let vertex = engine_vertex(movement, size);
+                request(&format!("play {} {vertex}", engine_color(*mover)))?;
+            }
+            let reply = request(&format!("genmove {}", engine_color(player)))?;
+            parse_decision(&reply, board)
+        })();
+
+        if result.is_err() {
+            // A process in an unknown state (or still thinking) is killed and
+            // not reused.
+            *guard = None;
+        }
+        result
+    }
+
+    fn decide_or_warn(&self, board: &GameY, control: &SearchControl) -> Option<BotDecision> {
+        match self.decide(board, control) {
+            Ok(decision) => Some(decision),
+            Err(e) => {
+                tracing::warn!(bot = %self.name, program = %self.program, "external engine failed: {e}");
+                None
+            }
+        }
+    }
+}
+
+/// Turns a `genmove` reply into a decision.
+fn parse_decision(reply: &str, board: &GameY) -> io::Result<BotDecision> {
+    match reply.to_ascii_lowercase().as_str() {
+        "resign" => Ok(BotDecision::Action(GameAction::Resign)),
+        "swap" => Ok(BotDecision::Action(GameAction::Swap)),
+        vertex => vertex
+            .parse::<u32>()
+            .ok()
+            .filter(|idx| board.available_cells().contains(idx))
+            .map(|idx| BotDecision::Move(Coordinates::from_index(idx, board.board_size())))
+            .ok_or_else(|| io::Error::other(format!("engine played an illegal move: {reply}"))),
+    }
+}
+
+impl YBot for ExternalBot {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The engine's strength is unknown, so the bot is unrated.
+    fn metadata(&self) -> BotMetadata {
+        BotMetadata::new(&self.name).with_description(format!("External engine `{}`", self.program))
+    }
+
+    fn choose_action(&self, board: &GameY) -> Option<BotDecision> {
+        self.decide_or_warn(board, &SearchControl::new())
+    }
+
+    /// The engine has no seed to follow, so `seed` is ignored.
+    fn choose_action_controlled(
+        &self,
+        board: &GameY,
+        _seed: Option<u64>,
+        control: &SearchControl,
+    ) -> Option<BotDecision> {
+        self.decide_or_warn(board, control)
+    }
+
+    fn choose_move_controlled(
+        &self,
+        board: &GameY,
+        seed: Option<u64>,
+        control: &SearchControl,
+    ) -> Option<Coordinates> {
+        match self.choose_action_controlled(board, seed, control) {
+            Some(BotDecision::Move(coords)) => Some(coords),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PlayerId;
+
+    #[test]
+    fn test_parse_decision() {
+        let mut board = GameY::new(3);
+        board
+            .add_move(Movement::Placement { player: PlayerId::new(0), coords: Coordinates::from_index(0, 3) })
+            .unwrap();
+
+        assert_eq!(
+            parse_decision("4", &board).unwrap(),
+            BotDecision::Move(Coordinates::from_index(4, 3))
+        );
+        assert_eq!(parse_decision("SWAP", &board).unwrap(), BotDecision::Action(GameAction::Swap));
+        assert_eq!(parse_decision("resign", &board).unwrap(), BotDecision::Action(GameAction::Resign));
+        assert!(parse_decision("0", &board).is_err());
+        assert!(parse_decision("a1", &board).is_err());
+    }
+
+    #[test]
+    fn test_missing_program_gives_no_move() {
+        let bot = ExternalBot::new("ghost", "/nonexistent/engine", Vec::new());
+        assert_eq!(bot.name(), "ghost");
+        assert!(bot.choose_action(&GameY::new(3)).is_none());
+    }
+
+    #[test]
+    fn test_silent_engine_times_out_and_is_killed() {
+        let bot = ExternalBot::new("silent", "sleep", vec!["30".to_string()])
+            .with_reply_timeout(Duration::from_millis(100));
+
+        let started = Instant::now();
+        assert!(bot.choose_action(&GameY::new(3)).is_none());
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(bot.process.lock().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_cancelled_control_stops_waiting() {
+        let bot = ExternalBot::new("silent", "sleep", vec!["30".to_string()]);
+        let control = SearchControl::new();
+        control.cancel();
+
+        let started = Instant::now();
+        assert!(bot.choose_action_controlled(&GameY::new(3), None, &control).is_none());
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(bot.process.lock().unwrap().is_none());
+    }
+}
@@ -1,7 +1,9 @@
-use crate::{BotDecision, Coordinates, GameY, YBot};
+use crate::{BotDecision, BotMetadata, Coordinates, GameY, Movement, YBot, evaluate};
 
-/// Bot Greedy mejorado para el Juego de Y.
-/// Se enfoca en el "Potencial de Conectividad" para unir los tres lados.
+/// Bot Greedy para el Juego de Y.
+/// Prueba cada casilla libre y se queda con la que deja la mejor evaluación
+/// estática ([`evaluate`]): la que más reduce las fichas que le faltan para
+/// unir los tres lados respecto a las que le faltan al rival.
 pub struct GreedyBot;
 
 impl YBot for GreedyBot {
@@ -9,55 +11,47 @@ impl YBot for GreedyBot {
         "greedy_bot"
     }
 
+    fn metadata(&self) -> BotMetadata {
+        BotMetadata::new("Greedy")
+            .with_description("Plays the cell with the best static evaluation, without searching")
+            .with_difficulty(2)
+    }
+
     fn choose_action(&self, board: &GameY) -> Option<BotDecision> {
-        let available_cells = board.available_cells();
+        let player = board.next_player()?;
         let size = board.board_size();
 
-        if available_cells.is_empty() {
-            return None;
-        }
-
-        available_cells
+        board
+            .available_cells()
             .iter()
             .map(|&index| {
                 let coords = Coordinates::from_index(index, size);
-                
-                // Usamos los métodos públicos x(), y(), z() para respetar la privacidad
-                let x = coords.x();
-                let y = coords.y();
-                let z = coords.z();
-
-                // --- HEURÍSTICA DE PUNTUACIÓN (Menor es mejor) ---
-                let mut score: i32 = 0;
-
-                // 1. Proximidad a los bordes (Esencial para ganar en Y)
-                // Buscamos que al menos una coordenada tienda a 0.
-                let min_dist = x.min(y).min(z) as i32;
-                score += min_dist * 15;
-
-                // 2. Evitar el "aislamiento" en esquinas extremas
-                // Las esquinas puras (donde dos coordenadas son 0) son poco flexibles.
-                if (x == 0 && y == 0) || (y == 0 && z == 0) || (x == 0 && z == 0) {
-                    score += 25;
-                }
+                let mut next = board.clone();
+                let _ = next.add_move(Movement::Placement { player, coords });
 
-                // 3. Centralidad Estratégica
-                // Queremos que la pieza "mire" hacia el centro para conectar con otros lados.
-                // Calculamos la dispersión: si las coordenadas están balanceadas, la pieza es central.
-                let balance = (x.max(y).max(z) - x.min(y).min(z)) as i32;
-                score += balance * 5;
-
-                (coords, score)
+                // Una jugada ganadora siempre es la mejor.
+                let score = if next.check_game_over() { i32::MAX } else { evaluate(&next, player) };
+                (coords, score, centrality_penalty(coords))
             })
-            .min_by_key(|&(_, score)| score)
-            .map(|(coords, _)| BotDecision::Move(coords))
+            // Mayor evaluación; a igualdad, la casilla más central.
+            .max_by(|a, b| a.1.cmp(&b.1).then(b.2.cmp(&a.2)))
+            .map(|(coords, _, _)| BotDecision::Move(coords))
     }
 }
 
+/// Desempate geométrico (menor es mejor): evita las esquinas puras y favorece
+/// las casillas con las tres coordenadas equilibradas, que miran a los tres lados.
+fn centrality_penalty(coords: Coordinates) -> u32 {
+    let (x, y, z) = (coords.x(), coords.y(), coords.z());
+    let corner = [x, y, z].iter().filter(|&&v| v == 0).count() >= 2;
+    let balance = x.max(y).max(z) - x.min(y).min(z);
+    balance + if corner { 5 } else { 0 }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::GameY;
+    use crate::{GameY, PlayerId};
 
     #[test]
     fn test_greedy_bot_name() {
@@ -78,4 +72,51 @@ mod tests {
         let zeros = [coords.x(), coords.y(), coords.z()].iter().filter(|&&v| v == 0).count();
         assert!(zeros < 2, "El bot debería evitar las esquinas muertas en el primer movimiento");
     }
+
+    #[test]
+    fn test_greedy_bot_completes_a_winning_chain() {
+        let bot = GreedyBot;
+        let mut game = GameY::new(3);
+        // El jugador 0 tiene (0,1,1) y (1,0,1): le falta tocar el lado C.
+        for (player, coords) in [
+            (0, Coordinates::new(0, 1, 1)),
+            (1, Coordinates::new(0, 2, 0)),
+            (0, Coordinates::new(1, 0, 1)),
+            (1, Coordinates::new(0, 0, 2)),
+        ] {
+            game.add_move(Movement::Placement { player: PlayerId::new(player), coords }).unwrap();
+        }
+
+        let coords = bot.choose_move(&game).unwrap();
+        game.add_move(Movement::Placement { player: PlayerId::new(0), coords }).unwrap();
+        assert!(game.check_game_over());
+    }
+
+    #[test]
+    fn test_greedy_bot_blocks_when_it_cannot_win() {
+        let bot = GreedyBot;
+        let mut game = GameY::new(4);
+        // El jugador 0 ocupa el lado de las y = 0 salvo (1,0,2): esa casilla le
+        // une los tres lados y es la única que lo hace. El jugador 1 no gana en
+        // una jugada, así que tiene que taparla.
+        for (player, coords) in [
+            (0, Coordinates::new(3, 0, 0)),
+            (1, Coordinates::new(1, 1, 1)),
+            (0, Coordinates::new(0, 0, 3)),
+            (1, Coordinates::new(0, 2, 1)),
+            (0, Coordinates::new(2, 0, 1)),
+        ] {
+            game.add_move(Movement::Placement { player: PlayerId::new(player), coords }).unwrap();
+        }
+
+        assert_eq!(bot.choose_move(&game), Some(Coordinates::new(1, 0, 2)));
+    }
+
+    #[test]
+    fn test_greedy_bot_returns_none_when_game_is_over() {
+        let mut game = GameY::new(1);
+        game.add_move(Movement::Placement { player: PlayerId::new(0), coords: Coordinates::new(0, 0, 0) })
+            .unwrap();
+        assert!(GreedyBot.choose_move(&game).is_none());
+    }
 }
@@ -1,88 +1,195 @@
-use rand::seq::IndexedRandom;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
-use crate::{BotDecision, Coordinates, GameY, GameStatus, Movement, PlayerId};
+use rand::RngCore;
+use rand::rngs::StdRng;
+
+use crate::{
+    BotAnalysis, BotDecision, BotMetadata, Coordinates, GameY, GameStatus, MoveAnalysis, Movement, PlayerId,
+    SearchControl,
+};
+use super::actions::ActionPolicy;
+use super::playout::{PlayoutPolicy, RandomPlayout};
+use super::seeded::{derive_seed, seeded_rng};
+use super::parallel::{deadline_reached, describe_budget, effective_threads, split_iterations};
 use super::ybot::YBot;
 
 pub struct MctsBot {
     /// Nombre del bot (identifica el nivel de dificultad).
-    name: &'static str,
+    name: String,
     /// Número total de simulaciones (playouts) que el bot realizará en cada turno.
     /// A mayor número, más "inteligente" es el bot, pero más tiempo tarda en decidir.
     iterations: u32,
+    /// Hilos entre los que se reparten las casillas candidatas (`0` = todos los núcleos).
+    threads: usize,
+    /// Tiempo máximo por jugada. Si se agota antes que las iteraciones, se corta la búsqueda.
+    time_budget: Option<Duration>,
+    /// Política con la que se eligen las jugadas de las simulaciones.
+    playout: Arc<dyn PlayoutPolicy>,
+    /// Cuándo abandona o intercambia en lugar de colocar (por defecto, nunca).
+    actions: ActionPolicy,
 }
 
 impl MctsBot {
-    pub fn new(name: &'static str, iterations: u32) -> Self {
-        Self { name, iterations }
+    pub fn new(name: impl Into<String>, iterations: u32) -> Self {
+        Self {
+            name: name.into(),
+            iterations,
+            threads: 1,
+            time_budget: None,
+            playout: Arc::new(RandomPlayout),
+            actions: ActionPolicy::default(),
+        }
+    }
+
+    /// Abandona cuando el win rate estimado de su mejor jugada baja de `threshold`
+    /// (ver [`crate::DEFAULT_RESIGN_THRESHOLD`]).
+    pub fn with_resign_threshold(mut self, threshold: f32) -> Self {
+        self.actions.resign_threshold = Some(threshold);
+        self
+    }
+
+    /// En la segunda jugada de la partida, intercambia si la primera ficha
+    /// parece favorecer a quien la puso.
+    pub fn with_swap(mut self) -> Self {
+        self.actions.swap = true;
+        self
+    }
+
+    /// Cambia la política de las simulaciones (por defecto [`RandomPlayout`]).
+    pub fn with_playout(mut self, playout: Arc<dyn PlayoutPolicy>) -> Self {
+        self.playout = playout;
+        self
+    }
+
+    /// Reparte las simulaciones entre `threads` hilos (`0` = todos los núcleos).
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Limita el tiempo de cada jugada. Las iteraciones siguen siendo el máximo.
+    pub fn with_time_budget(mut self, budget: Duration) -> Self {
+        self.time_budget = Some(budget);
+        self
     }
 
     /// FASE DE SIMULACIÓN (Playout):
-    /// Toma un tablero y lo juega hasta el final de forma totalmente aleatoria.
-    /// No busca ganar de forma inteligente aquí, solo busca un resultado estadístico rápido.
-    fn simulate(&self, mut virtual_board: GameY) -> Option<PlayerId> {
-        let mut rng = rand::rng();
-        
+    /// Toma un tablero y lo juega hasta el final con la política de playout
+    /// (por defecto, totalmente al azar). No busca ganar de forma inteligente
+    /// aquí, solo busca un resultado estadístico rápido.
+    fn simulate(&self, mut virtual_board: GameY, rng: &mut dyn RngCore) -> Option<PlayerId> {
+        let mut last_move = virtual_board.last_placement();
+
         loop {
             match virtual_board.status() {
                 // Si la partida virtual terminó, devolvemos quién ganó.
                 GameStatus::Finished { winner } => return *winner,
-                
-                // Si la partida sigue, elegimos un movimiento al azar entre los disponibles.
+
+                // Si la partida sigue, la política elige entre los movimientos disponibles.
                 GameStatus::Ongoing { next_player } => {
-                    let available = virtual_board.available_cells();
-                    if let Some(&move_idx) = available.choose(&mut rng) {
-                        // Convertimos el índice a coordenadas
-                        let coords = Coordinates::from_index(move_idx, virtual_board.board_size());
-                        let player = *next_player; 
-                        
-                        // Aplicamos el movimiento al tablero virtual.
-                        let _ = virtual_board.add_move(Movement::Placement {
-                            player,
-                            coords,
-                        });
-                    } else {
+                    let player = *next_player;
+                    let Some(move_idx) = self.playout.choose(&virtual_board, last_move, rng) else {
                         // Si no hay celdas pero nadie ganó (empate técnico).
                         return None;
-                    }
+                    };
+
+                    // Convertimos el índice a coordenadas y lo aplicamos al tablero virtual.
+                    let coords = Coordinates::from_index(move_idx, virtual_board.board_size());
+                    last_move = Some(coords);
+                    let _ = virtual_board.add_move(Movement::Placement { player, coords });
                 }
             }
         }
     }
 }
 
-impl YBot for MctsBot {
-    fn name(&self) -> &str {
-        self.name
-    }
+impl MctsBot {
+    /// Ejecuta las simulaciones para cada casilla candidata (vacía y ni muerta
+    /// ni capturada, ver `GameY::candidate_moves`) y devuelve, por cada
+    /// una, `(índice de celda, victorias, simulaciones)`.
+    ///
+    /// Las casillas se reparten entre los hilos configurados: cada casilla es
+    /// independiente, así que no hace falta combinar estadísticas.
+    ///
+    /// Con `seed`, cada casilla usa su propio generador derivado de la semilla,
+    /// así que el resultado no depende del número de hilos. `control` puede
+    /// cortar la búsqueda antes (fecha límite o cancelación).
+    fn evaluate_moves(&self, board: &GameY, seed: Option<u64>, control: &SearchControl) -> Vec<(u32, u32, u32)> {
+        // Obtenemos información básica del estado actual. Las casillas muertas
+        // o capturadas no se evalúan: no cambian el resultado.
+        let available_cells = board.candidate_moves();
+        let Some(my_player) = board.next_player() else { return Vec::new() }; // Quién soy yo (el bot).
 
-    /// TOMA DE DECISIÓN:
-    /// Evalúa cada movimiento posible realizando múltiples simulaciones para cada uno.
-    fn choose_action(&self, board: &GameY) -> Option<BotDecision> {
-        // Obtenemos información básica del estado actual
-        let available_cells = board.available_cells();
-        let my_player = board.next_player()?; // Quién soy yo (el bot).
-        let size = board.board_size();
+        // Evita división por cero si iterations < available_cells.len()
+        let simulations_per_move = (self.iterations / (available_cells.len() as u32).max(1)).max(1);
+        let deadline = control.deadline_within(self.time_budget);
+        let limits = (deadline, control);
 
-        // Validación: si no hay celdas disponibles, no hay decisión que tomar
-        if available_cells.is_empty() { return None; }
+        let threads = effective_threads(self.threads).min(available_cells.len()).max(1);
+        if threads == 1 {
+            return self.evaluate_cells(board, (0, &available_cells), my_player, simulations_per_move, limits, seed);
+        }
 
-        // Variables para rastrear el mejor movimiento encontrado
-        let mut best_move = None;
-        let mut max_wins = -1.0;
+        // Trozos contiguos de casillas, uno por hilo.
+        let mut chunks = Vec::with_capacity(threads);
+        let mut start = 0;
+        for len in split_iterations(available_cells.len() as u32, threads) {
+            let end = start + len as usize;
+            chunks.push(&available_cells[start..end]);
+            start = end;
+        }
 
-        // Evita división por cero si iterations < available_cells.len()
-        let simulations_per_move = (self.iterations / (available_cells.len() as u32).max(1)).max(1);
+        thread::scope(|scope| {
+            let handles: Vec<_> = chunks
+                .into_iter()
+                .enumerate()
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        self.evaluate_cells(board, chunk, my_player, simulations_per_move, limits, seed)
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("MCTS worker thread panicked"))
+                .collect()
+        })
+    }
+
+    /// Evalúa un trozo de casillas `(índice del trozo, casillas)`. Las
+    /// simulaciones se hacen por rondas (una por casilla en cada ronda) para
+    /// que, si se agota el tiempo, todas hayan recibido el mismo número de
+    /// simulaciones. `limits` son la fecha límite y el control de la búsqueda,
+    /// al que se informa del progreso tras cada ronda.
+    fn evaluate_cells(
+        &self,
+        board: &GameY,
+        (chunk, cells): (usize, &[u32]),
+        my_player: PlayerId,
+        simulations_per_move: u32,
+        (deadline, control): (Option<Instant>, &SearchControl),
+        seed: Option<u64>,
+    ) -> Vec<(u32, u32, u32)> {
+        let size = board.board_size();
+        let mut stats: Vec<(u32, u32, u32)> = cells.iter().map(|&move_idx| (move_idx, 0, 0)).collect();
+        let mut rngs: Vec<StdRng> = cells
+            .iter()
+            .map(|&move_idx| seeded_rng(seed.map(|seed| derive_seed(seed, move_idx as u64))))
+            .collect();
 
-        // BUCLE PRINCIPAL: Iteramos por cada casilla vacía disponible en el tablero actual.
-        for &move_idx in available_cells.iter() {
-            let mut wins = 0u32;
+        for round in 0..simulations_per_move {
+            // Siempre completamos al menos una ronda para no dividir entre cero.
+            if round > 0 && (deadline_reached(deadline) || control.is_cancelled()) {
+                break;
+            }
 
-            // BUCLE DE SIMULACIONES: Ejecutamos múltiples playouts para este movimiento
-            for _ in 0..simulations_per_move {
+            for ((move_idx, wins, simulations), rng) in stats.iter_mut().zip(rngs.iter_mut()) {
                 // CLONACIÓN: Creamos una copia del estado real del juego para no alterarlo.
-                let mut sim_board = board.clone(); 
-                let coords = Coordinates::from_index(move_idx, size);
-                
+                let mut sim_board = board.clone();
+                let coords = Coordinates::from_index(*move_idx, size);
+
                 // Realizamos el primer movimiento (el que estamos evaluando).
                 let _ = sim_board.add_move(Movement::Placement {
                     player: my_player,
@@ -90,25 +197,146 @@ impl YBot for MctsBot {
                 });
 
                 // Ejecutamos la simulación aleatoria hasta el final desde este punto.
-                if let Some(winner) = self.simulate(sim_board) {
-                    if winner == my_player {
-                        wins += 1; // Si el bot gana en esta simulación, sumamos un punto.
-                    }
+                if self.simulate(sim_board, rng) == Some(my_player) {
+                    *wins += 1; // Si el bot gana en esta simulación, sumamos un punto.
                 }
+                *simulations += 1;
             }
 
+            control.add_iterations(stats.len() as u64);
+            if let Some(&(move_idx, wins, simulations)) = stats.iter().max_by_key(|&&(_, wins, _)| wins) {
+                let coords = Coordinates::from_index(move_idx, size);
+                control.propose_best_move(chunk, coords, wins as f32 / simulations as f32);
+            }
+        }
+
+        stats
+    }
+}
+
+impl MctsBot {
+    /// TOMA DE DECISIÓN:
+    /// Elige la mejor jugada y, según [`ActionPolicy`], la juega o la
+    /// sustituye por un abandono o un intercambio.
+    fn decide(&self, board: &GameY, seed: Option<u64>, control: &SearchControl) -> Option<BotDecision> {
+        let (best_move, win_rate) = self.best_move(board, seed, control)?;
+        Some(self.actions.decide(board, best_move, win_rate))
+    }
+
+    /// Evalúa cada movimiento posible realizando múltiples simulaciones para
+    /// cada uno y devuelve el de mayor win rate junto con ese win rate.
+    fn best_move(&self, board: &GameY, seed: Option<u64>, control: &SearchControl) -> Option<(Coordinates, f32)> {
+        // Validación: si no hay celdas disponibles, no hay decisión que tomar
+        if board.available_cells().is_empty() { return None; }
+        board.next_player()?;
+
+        // Variables para rastrear el mejor movimiento encontrado
+        let mut best_move = None;
+        let mut max_wins = -1.0;
+
+        for (move_idx, wins, simulations) in self.evaluate_moves(board, seed, control) {
             // Calculamos la tasa de victoria (win rate) para este movimiento específico.
-            let win_rate = wins as f32 / simulations_per_move as f32;
+            let win_rate = wins as f32 / simulations as f32;
             
             // Si este movimiento es mejor que el mejor encontrado hasta ahora, lo guardamos.
             if win_rate > max_wins {
                 max_wins = win_rate;
-                best_move = Some(Coordinates::from_index(move_idx, size));
+                best_move = Some(Coordinates::from_index(move_idx, board.board_size()));
             }
         }
 
         // Devolvemos las coordenadas que estadísticamente dieron más victorias.
-        best_move.map(BotDecision::Move)
+        best_move.map(|coords| (coords, max_wins))
+    }
+}
+
+impl YBot for MctsBot {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Sin árbol la búsqueda es plana: por muchas iteraciones que tenga, como
+    /// mucho dificultad 3.
+    fn metadata(&self) -> BotMetadata {
+        BotMetadata::new(&self.name)
+            .with_description(format!(
+                "Flat Monte Carlo search with {} playouts, {}",
+                self.playout.name(),
+                describe_budget(self.iterations, self.time_budget)
+            ))
+            .with_difficulty(if self.iterations >= 10_000 { 3 } else { 2 })
+            .with_rule_sets(self.actions.rule_sets())
+            .with_analysis()
+            .with_think_time(self.time_budget)
+    }
+
+    fn choose_action(&self, board: &GameY) -> Option<BotDecision> {
+        self.decide(board, None, &SearchControl::new())
+    }
+
+    fn choose_action_seeded(&self, board: &GameY, seed: u64) -> Option<BotDecision> {
+        self.decide(board, Some(seed), &SearchControl::new())
+    }
+
+    /// Siempre coloca, aunque con [`ActionPolicy`] fuese a abandonar o intercambiar.
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        self.choose_move_controlled(board, None, &SearchControl::new())
+    }
+
+    fn choose_move_seeded(&self, board: &GameY, seed: u64) -> Option<Coordinates> {
+        self.choose_move_controlled(board, Some(seed), &SearchControl::new())
+    }
+
+    fn choose_action_controlled(
+        &self,
+        board: &GameY,
+        seed: Option<u64>,
+        control: &SearchControl,
+    ) -> Option<BotDecision> {
+        self.decide(board, seed, control)
+    }
+
+    fn choose_move_controlled(&self, board: &GameY, seed: Option<u64>, control: &SearchControl) -> Option<Coordinates> {
+        self.best_move(board, seed, control).map(|(coords, _)| coords)
+    }
+
+    fn analyze(&self, board: &GameY) -> Option<BotAnalysis> {
+        self.analyze_controlled(board, &SearchControl::new())
+    }
+
+    /// Este bot no construye árbol: la variante principal es solo la mejor jugada.
+    fn analyze_controlled(&self, board: &GameY, control: &SearchControl) -> Option<BotAnalysis> {
+        if board.check_game_over() || board.available_cells().is_empty() {
+            return None;
+        }
+
+        let size = board.board_size();
+        let mut moves: Vec<MoveAnalysis> = self
+            .evaluate_moves(board, None, control)
+            .into_iter()
+            .map(|(cell_id, wins, visits)| MoveAnalysis {
+                cell_id,
+                coords: Coordinates::from_index(cell_id, size),
+                visits,
+                win_rate: wins as f32 / visits as f32,
+            })
+            .collect();
+        // Como en `MctsCompletoBot`, primero las más visitadas. Sin plazo todas
+        // reciben las mismas simulaciones y desempata el win rate, así que la
+        // primera es la que elige `best_move`.
+        moves.sort_by(|a, b| {
+            b.visits
+                .cmp(&a.visits)
+                .then(b.win_rate.total_cmp(&a.win_rate))
+                .then(a.cell_id.cmp(&b.cell_id))
+        });
+
+        Some(BotAnalysis {
+            total_visits: moves.iter().map(|m| m.visits).sum(),
+            evaluation: moves.first().map(|m| m.win_rate).unwrap_or(0.5),
+            principal_variation: moves.first().map(|m| vec![m.coords]).unwrap_or_default(),
+            moves,
+        })
     }
 }
 
@@ -156,5 +384,84 @@ mod tests {
         let chosen_move = bot.choose_move(&game);
         assert!(chosen_move.is_none());
     }
-}  
 
+    #[test]
+    fn test_mcts_bot_analysis_lists_every_available_move() {
+        let bot = MctsBot::new("mcts_medio", 300);
+        let game = GameY::new(4);
+        let analysis = bot.analyze(&game).unwrap();
+
+        assert_eq!(analysis.moves.len(), game.available_cells().len());
+        // Mismas visitas para todas: desempata el win rate, como en `best_move`.
+        assert!(analysis.moves.windows(2).all(|w| w[0].visits > w[1].visits || w[0].win_rate >= w[1].win_rate));
+        assert_eq!(analysis.principal_variation, vec![analysis.moves[0].coords]);
+        assert_eq!(analysis.evaluation, analysis.moves[0].win_rate);
+    }
+
+    #[test]
+    fn test_mcts_bot_parallel_evaluates_every_move_once() {
+        let bot = MctsBot::new("mcts_paralelo", 200).with_threads(4);
+        let game = GameY::new(4);
+
+        let mut cells: Vec<u32> = bot.evaluate_moves(&game, None, &SearchControl::new()).iter().map(|&(cell, _, sims)| {
+            assert_eq!(sims, 20);
+            cell
+        }).collect();
+        cells.sort();
+        assert_eq!(cells, (0..10).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn test_mcts_bot_resigns_but_still_suggests_a_move() {
+        // Con un umbral imposible de superar el bot siempre abandona.
+        let bot = MctsBot::new("mcts_abandona", 200).with_resign_threshold(1.1);
+        let game = GameY::new(4);
+
+        assert_eq!(bot.choose_action(&game), Some(BotDecision::Action(crate::GameAction::Resign)));
+        assert!(bot.choose_move(&game).is_some());
+        assert!(bot.choose_move_seeded(&game, 3).is_some());
+    }
+
+    #[test]
+    fn test_mcts_bot_time_budget_stops_early() {
+        let bot = MctsBot::new("mcts_tiempo", u32::MAX).with_time_budget(Duration::from_millis(20));
+        let game = GameY::new(5);
+
+        let start = Instant::now();
+        assert!(bot.choose_move(&game).is_some());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_mcts_bot_with_playout_policy() {
+        let bot = MctsBot::new("mcts_puentes", 200).with_playout(Arc::new(crate::BridgePlayout));
+        let game = GameY::new(4);
+
+        let analysis = bot.analyze(&game).unwrap();
+        assert_eq!(analysis.moves.len(), game.available_cells().len());
+    }
+
+    #[test]
+    fn test_mcts_bot_seeded_stats_do_not_depend_on_threads() {
+        let game = GameY::new(4);
+        let single = MctsBot::new("mcts_semilla", 200).evaluate_moves(&game, Some(9), &SearchControl::new());
+        let parallel = MctsBot::new("mcts_semilla", 200).with_threads(3).evaluate_moves(&game, Some(9), &SearchControl::new());
+
+        assert_eq!(single, parallel);
+        assert_eq!(
+            MctsBot::new("mcts_semilla", 200).choose_move_seeded(&game, 9),
+            MctsBot::new("mcts_semilla", 200).with_threads(3).choose_move_seeded(&game, 9)
+        );
+    }
+
+    #[test]
+    fn test_search_reports_progress() {
+        let game = GameY::new(3);
+        let control = SearchControl::new();
+
+        let coords = MctsBot::new("mcts_progreso", 60).choose_move_controlled(&game, Some(1), &control);
+        let progress = control.progress();
+        assert_eq!(progress.iterations, 60);
+        assert_eq!(progress.best_move, coords);
+    }
+}
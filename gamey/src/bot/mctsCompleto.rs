@@ -1,14 +1,30 @@
 #![allow(non_snake_case)]
 
-use rand::seq::IndexedRandom;
-use rand::Rng;
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use crate::{BotDecision, Coordinates, GameStatus, GameY, Movement, PlayerId};
-use super::ybot::YBot;
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore};
+
+use crate::{
+    BotAnalysis, BotDecision, BotMetadata, Coordinates, GameStatus, GameY, MoveAnalysis, Movement, PlayerId,
+    SearchControl,
+};
+use super::actions::ActionPolicy;
+use super::evaluation::win_probability;
+use super::playout::{PlayoutPolicy, ProximityPlayout};
+use super::seeded::{derive_seed, seeded_rng};
+use super::parallel::{deadline_reached, describe_budget, effective_threads, split_iterations};
+use super::ybot::{BotSession, YBot};
 
 /// Nodo para el Monte Carlo Tree Search.
 /// Usamos índices en lugar de referencias explícitas (pointers) para evitar los
 /// problemas de ownership en Rust. El árbol completo residirá en un "arena" (un Vec<Node>).
+#[derive(Clone, Default)]
 struct MctsNode {
     /// Índice del nodo padre en el arena (None para la raíz)
     parent: Option<usize>,
@@ -25,12 +41,21 @@ struct MctsNode {
     visits: u32,
     /// Número de victorias obtenidas por `who_just_moved` desde aquí
     wins: f32,
+    /// RAVE/AMAF: simulaciones (que pasan por el padre) en las que `who_just_moved`
+    /// jugó este mismo movimiento en algún momento, no necesariamente el primero.
+    amaf_visits: u32,
+    /// RAVE/AMAF: victorias de `who_just_moved` en esas simulaciones.
+    amaf_wins: f32,
+    /// Probabilidad de victoria de `who_just_moved` según la evaluación
+    /// estática, si el bot usa prior (ver [`MctsCompletoBot::with_prior`]).
+    prior: f32,
 }
 
 impl MctsNode {
     fn new(parent: Option<usize>, move_idx: Option<u32>, who_just_moved: Option<PlayerId>, board: &GameY) -> Self {
-        // Obtenemos los movimientos legales desde el tablero en este estado
-        let unexpanded_moves = board.available_cells().clone();
+        // Movimientos legales en este estado, sin las casillas muertas o
+        // capturadas (no cambian el resultado, ver `GameY::inferior_cells`)
+        let unexpanded_moves = board.candidate_moves();
         Self {
             parent,
             move_idx,
@@ -39,70 +64,165 @@ impl MctsNode {
             unexpanded_moves,
             visits: 0,
             wins: 0.0,
+            amaf_visits: 0,
+            amaf_wins: 0.0,
+            prior: 0.5,
         }
     }
 }
 
+/// Parámetro de equivalencia RAVE por defecto: número de visitas a partir del
+/// cual las estadísticas reales pesan lo mismo que las AMAF.
+pub const DEFAULT_RAVE_EQUIVALENCE: f32 = 1000.0;
+
+/// Peso por defecto del prior de la evaluación estática, en visitas virtuales.
+pub const DEFAULT_PRIOR_WEIGHT: f32 = 10.0;
+
+/// Constante de exploración de UCT. Reducida a 0.3 para juegos de conexión:
+/// el árbol profundiza mucho más rápido en lugar de hacer Breadth-First.
+const UCT_EXPLORATION: f32 = 0.3;
+
+/// Número máximo de nodos por defecto (sumando todos los árboles de un bot o
+/// sesión). Al alcanzarlo se deja de expandir, pero se siguen haciendo iteraciones.
+pub const DEFAULT_MAX_TREE_NODES: usize = 250_000;
+
+/// Al "pensar en el turno del rival" (ponder) una sesión hace como mucho este
+/// múltiplo de las iteraciones de una jugada normal.
+const PONDER_ITERATION_FACTOR: u32 = 4;
+
+/// Iteraciones que se hacen en cada árbol antes de pasar al siguiente al
+/// pensar en el turno del rival (se usa un único hilo).
+const PONDER_CHUNK: u32 = 256;
+
+/// Cada cuántas iteraciones informa cada árbol del progreso de la búsqueda.
+const PROGRESS_INTERVAL: u32 = 256;
+
+#[derive(Clone)]
 pub struct MctsCompletoBot {
     /// Nombre del bot en la interfaz/CLI.
-    name: &'static str,
+    name: String,
     /// Presupuesto total de iteraciones (nodos expandidos) para todo el árbol en cada turno.
     iterations: u32,
+    /// Número de árboles independientes que se construyen en paralelo (`0` = todos los núcleos).
+    threads: usize,
+    /// Tiempo máximo por jugada. Si se agota antes que las iteraciones, se corta la búsqueda.
+    time_budget: Option<Duration>,
+    /// Parámetro de equivalencia RAVE. `None` desactiva RAVE (UCT puro).
+    rave_equivalence: Option<f32>,
+    /// Límite de memoria: número máximo de nodos entre todos los árboles.
+    max_nodes: usize,
+    /// Política con la que se eligen las jugadas de las simulaciones.
+    playout: Arc<dyn PlayoutPolicy>,
+    /// Cuándo abandona o intercambia en lugar de colocar (por defecto, nunca).
+    actions: ActionPolicy,
+    /// Visitas virtuales con las que la evaluación estática inicializa cada
+    /// hijo nuevo. `None` desactiva el prior.
+    prior_weight: Option<f32>,
 }
 
 impl MctsCompletoBot {
-    pub fn new(name: &'static str, iterations: u32) -> Self {
-        Self { name, iterations }
+    pub fn new(name: impl Into<String>, iterations: u32) -> Self {
+        Self {
+            name: name.into(),
+            iterations,
+            threads: 1,
+            time_budget: None,
+            rave_equivalence: None,
+            max_nodes: DEFAULT_MAX_TREE_NODES,
+            playout: Arc::new(ProximityPlayout),
+            actions: ActionPolicy::default(),
+            prior_weight: None,
+        }
+    }
+
+    /// Usa la evaluación estática ([`crate::win_probability`]) como prior: cada
+    /// hijo nuevo empieza con `weight` visitas virtuales cuyo win rate es el
+    /// de la evaluación, y las simulaciones reales lo van corrigiendo.
+    pub fn with_prior(mut self, weight: f32) -> Self {
+        self.prior_weight = Some(weight.max(0.0));
+        self
+    }
+
+    /// Abandona cuando el win rate del hijo más visitado de la raíz baja de
+    /// `threshold` (ver [`crate::DEFAULT_RESIGN_THRESHOLD`]).
+    pub fn with_resign_threshold(mut self, threshold: f32) -> Self {
+        self.actions.resign_threshold = Some(threshold);
+        self
+    }
+
+    /// En la segunda jugada de la partida, intercambia si la primera ficha
+    /// parece favorecer a quien la puso.
+    pub fn with_swap(mut self) -> Self {
+        self.actions.swap = true;
+        self
+    }
+
+    /// Limita el número total de nodos de los árboles. Importa sobre todo en
+    /// las sesiones ([`YBot::new_session`]), donde el árbol crece de jugada en jugada.
+    pub fn with_max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = max_nodes.max(1);
+        self
+    }
+
+    /// Activa RAVE (all-moves-as-first): la selección mezcla el win rate real
+    /// de cada hijo con su win rate AMAF, dando más peso a AMAF cuanto menos
+    /// visitado está el hijo. `equivalence` es el número de visitas en el que
+    /// ambos pesan lo mismo (ver [`DEFAULT_RAVE_EQUIVALENCE`]).
+    pub fn with_rave(mut self, equivalence: f32) -> Self {
+        self.rave_equivalence = Some(equivalence.max(f32::EPSILON));
+        self
+    }
+
+    /// Cambia la política de las simulaciones (por defecto [`ProximityPlayout`]).
+    pub fn with_playout(mut self, playout: Arc<dyn PlayoutPolicy>) -> Self {
+        self.playout = playout;
+        self
+    }
+
+    /// Paralelismo en la raíz: cada hilo construye su propio árbol con una
+    /// parte de las iteraciones y al final se suman las estadísticas de los
+    /// hijos de la raíz. `0` usa todos los núcleos disponibles. Las búsquedas
+    /// con semilla usan siempre un solo árbol, para ser reproducibles.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Limita el tiempo de cada jugada. Las iteraciones siguen siendo el máximo,
+    /// así que para jugar solo por tiempo se puede usar `u32::MAX`.
+    pub fn with_time_budget(mut self, budget: Duration) -> Self {
+        self.time_budget = Some(budget);
+        self
     }
 
     /// FASE 3: SIMULACIÓN (Playout rápido)
-    /// Este método es idéntico a tu MCTS básico: juega al azar hasta terminar la partida
-    /// y devuelve quién ganó.
-    fn simulate(&self, mut virtual_board: GameY) -> Option<PlayerId> {
-        let mut rng = rand::rng();
-        let mut last_move: Option<Coordinates> = None;
+    /// Juega hasta terminar la partida eligiendo cada jugada con la política de
+    /// playout configurada y devuelve quién ganó.
+    ///
+    /// Si se pasa `played_by`, se anota en él (por índice de celda) qué jugador
+    /// ocupó cada casilla durante el playout, para las estadísticas RAVE.
+    fn simulate(
+        &self,
+        mut virtual_board: GameY,
+        mut played_by: Option<&mut [Option<PlayerId>]>,
+        rng: &mut dyn RngCore,
+    ) -> Option<PlayerId> {
+        // La última jugada del árbol: la simulación puede responder a ella.
+        let mut last_move = virtual_board.last_placement();
         let size = virtual_board.board_size();
         
         loop {
             match virtual_board.status() {
                 GameStatus::Finished { winner } => return (*winner).into(),
                 GameStatus::Ongoing { next_player } => {
-                    let available = virtual_board.available_cells();
-                    if available.is_empty() {
-                        return None;
-                    }
-
-                    let move_idx = if let Some(last_coord) = last_move {
-                        // Heavy Playout: 75% probabilidad de aplicar una táctica de proximidad
-                        if rng.random_bool(0.75) {
-                            let k = 3.min(available.len()); // Torneo de tamaño 3
-                            let mut best_idx = available[0];
-                            let mut min_dist = u32::MAX;
-
-                            for _ in 0..k {
-                                let candidate_idx = *available.choose(&mut rng).unwrap();
-                                let target_coord = Coordinates::from_index(candidate_idx, size);
-                                
-                                // Distancia topológica en Hex / Barycentric coords
-                                let dist = target_coord.x().abs_diff(last_coord.x())
-                                    .max(target_coord.y().abs_diff(last_coord.y()))
-                                    .max(target_coord.z().abs_diff(last_coord.z()));
-
-                                if dist < min_dist {
-                                    min_dist = dist;
-                                    best_idx = candidate_idx;
-                                }
-                            }
-                            best_idx
-                        } else {
-                            *available.choose(&mut rng).unwrap()
-                        }
-                    } else {
-                        *available.choose(&mut rng).unwrap()
-                    };
+                    // Sin casillas libres la simulación acaba en empate técnico.
+                    let move_idx = self.playout.choose(&virtual_board, last_move, rng)?;
 
                     let coords = Coordinates::from_index(move_idx, size);
                     last_move = Some(coords);
+                    if let Some(played_by) = played_by.as_deref_mut() {
+                        played_by[move_idx as usize] = Some(*next_player);
+                    }
                     
                     let _ = virtual_board.add_move(Movement::Placement {
                         player: *next_player,
@@ -114,27 +234,105 @@ impl MctsCompletoBot {
     }
 }
 
-impl YBot for MctsCompletoBot {
-    fn name(&self) -> &str {
-        self.name
+impl MctsCompletoBot {
+    /// Ejecuta la búsqueda desde cero y devuelve los árboles resultantes (uno
+    /// por hilo). La raíz de cada árbol es `arena[0]`.
+    ///
+    /// Con `seed` se construye un único árbol sea cual sea `threads`: así la
+    /// jugada no depende del número de núcleos de la máquina.
+    fn search(&self, board: &GameY, seed: Option<u64>, control: &SearchControl) -> Vec<Vec<MctsNode>> {
+        let mut trees = match seed {
+            Some(_) => vec![vec![MctsNode::new(None, None, None, board)]],
+            None => self.new_forest(board),
+        };
+        self.grow_forest(&mut trees, board, seed, control);
+        trees
     }
 
-    fn choose_action(&self, board: &GameY) -> Option<BotDecision> {
-        if board.available_cells().is_empty() {
-            return None;
+    /// Crea un árbol vacío (solo la raíz) por cada hilo de búsqueda.
+    fn new_forest(&self, board: &GameY) -> Vec<Vec<MctsNode>> {
+        (0..effective_threads(self.threads))
+            .map(|_| vec![MctsNode::new(None, None, None, board)])
+            .collect()
+    }
+
+    /// Hace crecer los árboles con el presupuesto del bot. Con varios árboles,
+    /// cada hilo trabaja sobre uno con una parte de las iteraciones.
+    ///
+    /// Con `seed`, el árbol `i` usa un generador derivado de `(seed, i)`: el
+    /// resultado es reproducible para el mismo número de árboles. `control`
+    /// puede cortar la búsqueda antes (fecha límite o cancelación).
+    fn grow_forest(&self, trees: &mut [Vec<MctsNode>], board: &GameY, seed: Option<u64>, control: &SearchControl) {
+        let deadline = control.deadline_within(self.time_budget);
+        let stop = Some(control.stop_flag());
+        let node_cap = (self.max_nodes / trees.len().max(1)).max(1);
+        let tree_seed = |i: usize| seed.map(|seed| derive_seed(seed, i as u64));
+
+        if let [tree] = trees {
+            let mut rng = seeded_rng(tree_seed(0));
+            self.grow_tree(tree, board, self.iterations, deadline, node_cap, (stop, Some((control, 0))), &mut rng);
+            return;
         }
 
+        let budgets = split_iterations(self.iterations, trees.len());
+        thread::scope(|scope| {
+            for (i, (tree, iterations)) in trees.iter_mut().zip(budgets).enumerate() {
+                let mut rng = seeded_rng(tree_seed(i));
+                scope.spawn(move || {
+                    self.grow_tree(tree, board, iterations, deadline, node_cap, (stop, Some((control, i))), &mut rng)
+                });
+            }
+        });
+    }
+
+    /// Hace crecer un árbol (selección, expansión, simulación y retropropagación)
+    /// con como mucho `iterations` iteraciones o hasta `deadline`. La raíz
+    /// (`arena[0]`) debe corresponder a `board`. No se expanden nodos nuevos
+    /// una vez que el árbol tiene `node_cap` nodos. Si se pasa `stop`, la
+    /// búsqueda se corta en cuanto se active; si se pasa `progress` (control y
+    /// número de árbol), se le informa cada [`PROGRESS_INTERVAL`] iteraciones.
+    /// Todo el azar sale de `rng`.
+    #[allow(clippy::too_many_arguments)]
+    fn grow_tree(
+        &self,
+        arena: &mut Vec<MctsNode>,
+        board: &GameY,
+        iterations: u32,
+        deadline: Option<Instant>,
+        node_cap: usize,
+        (stop, progress): (Option<&AtomicBool>, Option<(&SearchControl, usize)>),
+        rng: &mut StdRng,
+    ) {
         let size = board.board_size();
-        let mut rng = rand::rng();
 
         // Reservar memoria masiva para el árbol. Evitamos redimensionamientos en caliente.
         // Rust llenará esto rapidísimo.
-        let mut arena: Vec<MctsNode> = Vec::with_capacity((self.iterations as usize).min(200_000));
-        
-        // Inicializamos la Raíz
-        arena.push(MctsNode::new(None, None, None, board));
+        let room = node_cap.saturating_sub(arena.len());
+        arena.reserve((iterations as usize).min(room).min(200_000));
+
+        // Con RAVE, quién ocupó cada casilla en la iteración actual (camino + playout).
+        let mut played_by: Vec<Option<PlayerId>> = if self.rave_equivalence.is_some() {
+            vec![None; board.total_cells() as usize]
+        } else {
+            Vec::new()
+        };
+
+        let mut unreported = 0;
+        for iteration in 0..iterations {
+            // Al menos una iteración, para tener siempre una jugada que devolver.
+            let stopped = deadline_reached(deadline) || stop.is_some_and(|stop| stop.load(Ordering::Relaxed));
+            if iteration > 0 && stopped {
+                break;
+            }
+            if let Some(progress) = progress
+                && unreported == PROGRESS_INTERVAL
+            {
+                report_progress(progress, arena, size, unreported);
+                unreported = 0;
+            }
+            unreported += 1;
+            played_by.fill(None);
 
-        for _ in 0..self.iterations {
             let mut current_node_idx = 0; // Apuntamos a la raíz en cada iteración
             let mut current_board = board.clone();
 
@@ -154,21 +352,7 @@ impl YBot for MctsCompletoBot {
                 let log_parent_visits = parent_visits.ln();
 
                 for &child_idx in &arena[current_node_idx].children {
-                    let child = &arena[child_idx];
-                    let child_visits = child.visits as f32;
-                    let uct_score;
-
-                    if child_visits == 0.0 {
-                        // Si por algún motivo el hijo no fue visitado, tiene prioridad infinita (exploration)
-                        uct_score = f32::MAX;
-                    } else {
-                        // Explotación: win rate de quién tomó la decisión de llegar aquí
-                        let exploitation = child.wins / child_visits;
-                        // Exploración: reducimos el factor C a 0.3 para juegos de conexión
-                        // Esto hace que el árbol profundice mucho más rápido en lugar de hacer Breadth-First.
-                        let exploration = 0.3 * (log_parent_visits / child_visits).sqrt();
-                        uct_score = exploitation + exploration;
-                    }
+                    let uct_score = self.selection_score(&arena[child_idx], log_parent_visits);
 
                     if uct_score > best_uct {
                         best_uct = uct_score;
@@ -180,6 +364,9 @@ impl YBot for MctsCompletoBot {
                 
                 // Actualizamos el tablero virtual para reflejar el camino que tomamos
                 let move_val = arena[current_node_idx].move_idx.unwrap();
+                if !played_by.is_empty() {
+                    played_by[move_val as usize] = arena[current_node_idx].who_just_moved;
+                }
                 apply_placement_from_idx(&mut current_board, move_val, size);
             }
 
@@ -189,6 +376,7 @@ impl YBot for MctsCompletoBot {
             // Si llegamos a un nodo que tiene movimientos por descubrir, descubrimos UNO.
             if !current_board.check_game_over() 
                && !arena[current_node_idx].unexpanded_moves.is_empty() 
+               && arena.len() < node_cap
             {
                 let unexpanded = &mut arena[current_node_idx].unexpanded_moves;
                 
@@ -198,12 +386,18 @@ impl YBot for MctsCompletoBot {
                 let move_idx = unexpanded.swap_remove(pick_idx);
 
                 let mover = current_board.next_player().unwrap();
+                if !played_by.is_empty() {
+                    played_by[move_idx as usize] = Some(mover);
+                }
                 
                 // Lo aplicamos en nuestro mini tablero simulado
                 apply_placement_from_idx(&mut current_board, move_idx, size);
 
                 // Lo añadimos al árbol de verdad
-                let new_node = MctsNode::new(Some(current_node_idx), Some(move_idx), Some(mover), &current_board);
+                let mut new_node = MctsNode::new(Some(current_node_idx), Some(move_idx), Some(mover), &current_board);
+                if self.prior_weight.is_some() {
+                    new_node.prior = win_probability(&current_board, mover);
+                }
                 let new_node_idx = arena.len();
                 arena.push(new_node);
                 arena[current_node_idx].children.push(new_node_idx);
@@ -214,7 +408,11 @@ impl YBot for MctsCompletoBot {
             // -------------------------------------------------------------
             // FASE 3: SIMULACIÓN
             // -------------------------------------------------------------
-            let winner = self.simulate(current_board);
+            let winner = if played_by.is_empty() {
+                self.simulate(current_board, None, rng)
+            } else {
+                self.simulate(current_board, Some(&mut played_by), rng)
+            };
 
             // -------------------------------------------------------------
             // FASE 4: BACKPROPAGATION (Retropropagación)
@@ -233,29 +431,470 @@ impl YBot for MctsCompletoBot {
                 }
                 
                 backprop_idx = node.parent; // Subimos al padre
+
+                // RAVE: cada casilla se ocupa una sola vez por partida, así que basta
+                // con mirar quién la ocupó para acreditar a los hermanos AMAF.
+                if !played_by.is_empty() {
+                    update_amaf(arena, idx, &played_by, winner);
+                }
+            }
+        }
+
+        if let Some(progress) = progress {
+            report_progress(progress, arena, size, unreported);
+        }
+    }
+}
+
+impl MctsCompletoBot {
+    /// Puntuación de selección de un hijo: UCT y, si RAVE está activo, mezcla
+    /// del win rate real con el AMAF usando `beta = sqrt(k / (3n + k))`.
+    fn selection_score(&self, child: &MctsNode, log_parent_visits: f32) -> f32 {
+        let child_visits = child.visits as f32;
+        if child_visits == 0.0 {
+            // Si por algún motivo el hijo no fue visitado, tiene prioridad infinita (exploration)
+            return f32::MAX;
+        }
+
+        // Explotación: win rate de quién tomó la decisión de llegar aquí,
+        // con el prior como visitas virtuales si está activo.
+        let mut exploitation = match self.prior_weight {
+            Some(weight) => (child.wins + weight * child.prior) / (child_visits + weight),
+            None => child.wins / child_visits,
+        };
+        if let Some(k) = self.rave_equivalence
+            && child.amaf_visits > 0
+        {
+            let beta = (k / (3.0 * child_visits + k)).sqrt();
+            let amaf = child.amaf_wins / child.amaf_visits as f32;
+            exploitation = (1.0 - beta) * exploitation + beta * amaf;
+        }
+
+        let exploration = UCT_EXPLORATION * (log_parent_visits / child_visits).sqrt();
+        exploitation + exploration
+    }
+}
+
+/// Actualiza las estadísticas AMAF de los hijos de `node_idx`: cuentan todas las
+/// simulaciones en las que el jugador del hijo ocupó esa casilla.
+fn update_amaf(arena: &mut [MctsNode], node_idx: usize, played_by: &[Option<PlayerId>], winner: Option<PlayerId>) {
+    for i in 0..arena[node_idx].children.len() {
+        let child_idx = arena[node_idx].children[i];
+        let child = &mut arena[child_idx];
+        let (Some(move_idx), Some(owner)) = (child.move_idx, child.who_just_moved) else { continue };
+
+        if played_by[move_idx as usize] == Some(owner) {
+            child.amaf_visits += 1;
+            if winner == Some(owner) {
+                child.amaf_wins += 1.0;
             }
         }
+    }
+}
+
+/// Combina los árboles de la búsqueda paralela en la raíz.
+///
+/// Se toma el primer árbol como base (su subárbol se usa para la variante
+/// principal) y se le suman las visitas y victorias de los hijos de la raíz
+/// del resto. Los movimientos que la base no llegó a expandir se añaden como hojas.
+/// Con un único árbol no se copia nada.
+fn merge_root_statistics(trees: &[Vec<MctsNode>]) -> Cow<'_, [MctsNode]> {
+    let [first, rest @ ..] = trees else { return Cow::Borrowed(&[]) };
+    if rest.is_empty() {
+        return Cow::Borrowed(first);
+    }
+    let mut merged = first.clone();
+
+    for tree in rest {
+        merged[0].visits += tree[0].visits;
+
+        for &child_idx in &tree[0].children {
+            let child = &tree[child_idx];
+            let existing = merged[0]
+                .children
+                .iter()
+                .copied()
+                .find(|&idx| merged[idx].move_idx == child.move_idx);
+
+            match existing {
+                Some(idx) => {
+                    merged[idx].visits += child.visits;
+                    merged[idx].wins += child.wins;
+                    merged[idx].amaf_visits += child.amaf_visits;
+                    merged[idx].amaf_wins += child.amaf_wins;
+                }
+                None => {
+                    let new_idx = merged.len();
+                    merged.push(MctsNode {
+                        parent: Some(0),
+                        move_idx: child.move_idx,
+                        who_just_moved: child.who_just_moved,
+                        children: Vec::new(),
+                        unexpanded_moves: Vec::new(),
+                        visits: child.visits,
+                        wins: child.wins,
+                        amaf_visits: child.amaf_visits,
+                        amaf_wins: child.amaf_wins,
+                        prior: child.prior,
+                    });
+                    merged[0].children.push(new_idx);
+                }
+            }
+        }
+    }
+
+    Cow::Owned(merged)
+}
+
+/// Re-enraíza el árbol en el nodo al que se llega jugando `path` desde la
+/// raíz. Devuelve el subárbol compactado (raíz en el índice 0) o `None` si
+/// alguna de las jugadas no llegó a expandirse.
+fn reroot(mut arena: Vec<MctsNode>, path: &[u32]) -> Option<Vec<MctsNode>> {
+    if path.is_empty() {
+        return Some(arena);
+    }
+
+    let mut node_idx = 0;
+    for &move_idx in path {
+        node_idx = arena[node_idx]
+            .children
+            .iter()
+            .copied()
+            .find(|&child_idx| arena[child_idx].move_idx == Some(move_idx))?;
+    }
+
+    // Copia en anchura del subárbol, reasignando índices.
+    let mut subtree = Vec::new();
+    let mut queue = VecDeque::from([(node_idx, None)]);
+    while let Some((old_idx, parent)) = queue.pop_front() {
+        let new_idx = subtree.len();
+        let mut node = std::mem::take(&mut arena[old_idx]);
+        node.parent = parent;
+        for child_idx in std::mem::take(&mut node.children) {
+            queue.push_back((child_idx, Some(new_idx)));
+        }
+        subtree.push(node);
+        if let Some(parent) = parent {
+            subtree[parent].children.push(new_idx);
+        }
+    }
+
+    Some(subtree)
+}
+
+/// Escoge la jugada final: el hijo de la raíz más visitado, junto con su win rate.
+fn best_move(arena: &[MctsNode], size: u32) -> Option<(Coordinates, f32)> {
+    let most_visited_idx = most_visited_child(arena, 0)?;
+    let child = &arena[most_visited_idx];
+    let best_move_index = child.move_idx?;
+    let win_rate = if child.visits > 0 { child.wins / child.visits as f32 } else { 0.5 };
+    Some((Coordinates::from_index(best_move_index, size), win_rate))
+}
+
+/// Informa a `control` de `iterations` iteraciones más y de la jugada que
+/// prefiere ahora el árbol `tree`: el hijo más visitado, puntuado por su
+/// fracción de las visitas de la raíz.
+fn report_progress((control, tree): (&SearchControl, usize), arena: &[MctsNode], size: u32, iterations: u32) {
+    control.add_iterations(iterations as u64);
+    if let Some(child_idx) = most_visited_child(arena, 0)
+        && let Some(move_idx) = arena[child_idx].move_idx
+    {
+        let share = arena[child_idx].visits as f32 / arena[0].visits.max(1) as f32;
+        control.propose_best_move(tree, Coordinates::from_index(move_idx, size), share);
+    }
+}
+
+/// Devuelve el hijo más visitado de `node_idx`, si tiene alguno.
+///
+/// El movimiento más robusto según el algoritmo MCTS no es el de mayor win-rate,
+/// sino el hiperparámetro de robustez: "el hijo de la raíz más VISITADO".
+fn most_visited_child(arena: &[MctsNode], node_idx: usize) -> Option<usize> {
+    let mut best: Option<usize> = None;
+    for &child_idx in &arena[node_idx].children {
+        if best.is_none_or(|b| arena[child_idx].visits > arena[b].visits) {
+            best = Some(child_idx);
+        }
+    }
+    best
+}
+
+/// Construye el análisis (estadísticas por jugada y variante principal) a
+/// partir del árbol de búsqueda.
+fn analysis_from_arena(arena: &[MctsNode], size: u32) -> BotAnalysis {
+    let root = &arena[0];
+
+    let mut moves: Vec<MoveAnalysis> = root
+        .children
+        .iter()
+        .filter_map(|&child_idx| {
+            let child = &arena[child_idx];
+            let cell_id = child.move_idx?;
+            Some(MoveAnalysis {
+                cell_id,
+                coords: Coordinates::from_index(cell_id, size),
+                visits: child.visits,
+                win_rate: if child.visits > 0 { child.wins / child.visits as f32 } else { 0.0 },
+            })
+        })
+        .collect();
+    moves.sort_by(|a, b| b.visits.cmp(&a.visits).then(a.cell_id.cmp(&b.cell_id)));
+
+    // La evaluación de la posición es la del mejor movimiento (el más visitado).
+    let evaluation = moves.first().map(|m| m.win_rate).unwrap_or(0.5);
+
+    let mut principal_variation = Vec::new();
+    let mut node_idx = 0;
+    while let Some(child_idx) = most_visited_child(arena, node_idx) {
+        let child = &arena[child_idx];
+        let Some(cell_id) = child.move_idx else { break };
+        if child.visits == 0 {
+            break;
+        }
+        principal_variation.push(Coordinates::from_index(cell_id, size));
+        node_idx = child_idx;
+    }
+
+    BotAnalysis {
+        total_visits: root.visits,
+        evaluation,
+        moves,
+        principal_variation,
+    }
+}
+
+impl YBot for MctsCompletoBot {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// La dificultad sale del presupuesto de iteraciones (solo tiempo cuenta
+    /// como el máximo).
+    fn metadata(&self) -> BotMetadata {
+        let mut description = String::from("Monte Carlo tree search");
+        if self.rave_equivalence.is_some() {
+            description.push_str(" with RAVE");
+        }
+        if self.prior_weight.is_some() {
+            description.push_str(" guided by the static evaluation");
+        }
+        let difficulty = match self.iterations {
+            0..5_000 => 2,
+            5_000..20_000 => 3,
+            _ => 4,
+        };
+        BotMetadata::new(&self.name)
+            .with_description(format!(
+                "{description}, {} playouts, {}",
+                self.playout.name(),
+                describe_budget(self.iterations, self.time_budget)
+            ))
+            .with_difficulty(difficulty)
+            .with_rule_sets(self.actions.rule_sets())
+            .with_analysis()
+            .with_think_time(self.time_budget)
+    }
+
+    fn choose_action(&self, board: &GameY) -> Option<BotDecision> {
+        self.decide(board, None, &SearchControl::new())
+    }
+
+    fn choose_action_seeded(&self, board: &GameY, seed: u64) -> Option<BotDecision> {
+        self.decide(board, Some(seed), &SearchControl::new())
+    }
+
+    /// Siempre coloca, aunque con [`ActionPolicy`] fuese a abandonar o intercambiar.
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        self.choose_move_controlled(board, None, &SearchControl::new())
+    }
+
+    fn choose_move_seeded(&self, board: &GameY, seed: u64) -> Option<Coordinates> {
+        self.choose_move_controlled(board, Some(seed), &SearchControl::new())
+    }
+
+    fn choose_action_controlled(
+        &self,
+        board: &GameY,
+        seed: Option<u64>,
+        control: &SearchControl,
+    ) -> Option<BotDecision> {
+        self.decide(board, seed, control)
+    }
+
+    fn choose_move_controlled(&self, board: &GameY, seed: Option<u64>, control: &SearchControl) -> Option<Coordinates> {
+        self.search_best_move(board, seed, control).map(|(coords, _)| coords)
+    }
+
+    fn analyze(&self, board: &GameY) -> Option<BotAnalysis> {
+        self.analyze_controlled(board, &SearchControl::new())
+    }
+
+    fn analyze_controlled(&self, board: &GameY, control: &SearchControl) -> Option<BotAnalysis> {
+        if board.check_game_over() || board.available_cells().is_empty() {
+            return None;
+        }
+
+        let trees = self.search(board, None, control);
+        Some(analysis_from_arena(&merge_root_statistics(&trees), board.board_size()))
+    }
+
+    fn new_session(&self) -> Option<Box<dyn BotSession>> {
+        Some(Box::new(MctsCompletoSession::new(self.clone())))
+    }
+}
+
+impl MctsCompletoBot {
+    fn decide(&self, board: &GameY, seed: Option<u64>, control: &SearchControl) -> Option<BotDecision> {
+        let (best_move, win_rate) = self.search_best_move(board, seed, control)?;
+        Some(self.actions.decide(board, best_move, win_rate))
+    }
+
+    fn search_best_move(
+        &self,
+        board: &GameY,
+        seed: Option<u64>,
+        control: &SearchControl,
+    ) -> Option<(Coordinates, f32)> {
+        if board.available_cells().is_empty() {
+            return None;
+        }
+
+        let trees = self.search(board, seed, control);
 
         // -------------------------------------------------------------
         // FIN DEL TURNO: Escoger la mejor jugada
         // -------------------------------------------------------------
-        // El movimiento más robusto según el algoritmo MCTS no es el de mayor win-rate,
-        // sino el hiperparámetro de robustez: "el hijo de la raíz más VISITADO".
-        let mut most_visited_idx = 0;
-        let mut max_visits = -1;
+        best_move(&merge_root_statistics(&trees), board.board_size())
+    }
+}
 
-        let root = &arena[0];
-        for &child_idx in &root.children {
-            let child = &arena[child_idx];
-            if (child.visits as i32) > max_visits {
-                max_visits = child.visits as i32;
-                most_visited_idx = child_idx;
+/// Sesión de [`MctsCompletoBot`] para una partida: conserva los árboles entre
+/// jugadas y, en cada llamada, los re-enraíza en la posición actual para que
+/// la búsqueda parta de las visitas ya invertidas en esa rama.
+pub struct MctsCompletoSession {
+    bot: MctsCompletoBot,
+    /// Jugadas (índices de celda) desde el inicio de la partida hasta la raíz de `trees`.
+    root_moves: Vec<u32>,
+    /// Un árbol por hilo de búsqueda. Vacío si todavía no se ha buscado.
+    trees: Vec<Vec<MctsNode>>,
+    /// Estimaciones seguidas por debajo del umbral de abandono.
+    low_estimates: u32,
+}
+
+impl MctsCompletoSession {
+    pub fn new(bot: MctsCompletoBot) -> Self {
+        Self { bot, root_moves: Vec::new(), trees: Vec::new(), low_estimates: 0 }
+    }
+
+    /// Número total de nodos conservados entre todos los árboles.
+    pub fn tree_size(&self) -> usize {
+        self.trees.iter().map(Vec::len).sum()
+    }
+
+    /// Visitas ya acumuladas en la raíz (sumando todos los árboles).
+    pub fn root_visits(&self) -> u32 {
+        self.trees.iter().filter_map(|tree| tree.first()).map(|root| root.visits).sum()
+    }
+
+    /// Amplía los árboles de `board` y devuelve la mejor jugada con su win rate.
+    fn search_best_move(&mut self, board: &GameY, control: &SearchControl) -> Option<(Coordinates, f32)> {
+        if board.available_cells().is_empty() {
+            return None;
+        }
+
+        self.advance_to(board);
+        self.bot.grow_forest(&mut self.trees, board, None, control);
+        best_move(&merge_root_statistics(&self.trees), board.board_size())
+    }
+
+    /// Deja los árboles enraizados en `board`, reutilizando lo posible.
+    fn advance_to(&mut self, board: &GameY) {
+        // Con acciones (swap) en el historial el árbol ya no describe la partida.
+        let moves = placement_history(board);
+        let path = moves
+            .as_ref()
+            .filter(|moves| !self.trees.is_empty() && moves.starts_with(&self.root_moves))
+            .map(|moves| &moves[self.root_moves.len()..]);
+
+        self.trees = match path {
+            Some(path) => std::mem::take(&mut self.trees)
+                .into_iter()
+                .map(|tree| {
+                    reroot(tree, path).unwrap_or_else(|| vec![MctsNode::new(None, None, None, board)])
+                })
+                .collect(),
+            None => self.bot.new_forest(board),
+        };
+        self.root_moves = moves.unwrap_or_default();
+    }
+}
+
+impl BotSession for MctsCompletoSession {
+    /// Amplía el árbol de la posición en la que le toca al rival, en un solo
+    /// hilo y en bloques de [`PONDER_CHUNK`] iteraciones por árbol, hasta que
+    /// se active `stop`, pase `max_time`, se hagan [`PONDER_ITERATION_FACTOR`]
+    /// veces las iteraciones de una jugada o se llene el árbol.
+    fn ponder(&mut self, board: &GameY, stop: &AtomicBool, max_time: Duration) {
+        if board.check_game_over() || board.available_cells().is_empty() {
+            return;
+        }
+
+        self.advance_to(board);
+        let deadline = Some(Instant::now() + max_time);
+        let node_cap = (self.bot.max_nodes / self.trees.len().max(1)).max(1);
+        let mut remaining = self.bot.iterations.saturating_mul(PONDER_ITERATION_FACTOR);
+        let mut rng = seeded_rng(None);
+
+        while remaining > 0 && !stop.load(Ordering::Relaxed) && !deadline_reached(deadline) {
+            if self.trees.iter().all(|tree| tree.len() >= node_cap) {
+                break;
+            }
+            for tree in &mut self.trees {
+                let chunk = remaining.min(PONDER_CHUNK);
+                self.bot.grow_tree(tree, board, chunk, deadline, node_cap, (Some(stop), None), &mut rng);
+                remaining -= chunk;
             }
         }
+    }
+
+    /// Solo abandona tras [`crate::RESIGN_CONFIRMATIONS`] estimaciones seguidas por
+    /// debajo del umbral (ver [`ActionPolicy::decide_in_session`]).
+    fn choose_action(&mut self, board: &GameY) -> Option<BotDecision> {
+        let (best_move, win_rate) = self.search_best_move(board, &SearchControl::new())?;
+        Some(self.bot.actions.decide_in_session(board, best_move, win_rate, &mut self.low_estimates))
+    }
 
-        let best_move_index = arena[most_visited_idx].move_idx?;
-        Some(BotDecision::Move(Coordinates::from_index(best_move_index, size)))
+    /// Siempre coloca, aunque con [`ActionPolicy`] fuese a abandonar o intercambiar.
+    fn choose_move(&mut self, board: &GameY) -> Option<Coordinates> {
+        self.choose_move_controlled(board, &SearchControl::new())
     }
+
+    fn choose_move_controlled(&mut self, board: &GameY, control: &SearchControl) -> Option<Coordinates> {
+        self.search_best_move(board, control).map(|(coords, _)| coords)
+    }
+
+    fn analyze(&mut self, board: &GameY) -> Option<BotAnalysis> {
+        if board.check_game_over() || board.available_cells().is_empty() {
+            return None;
+        }
+
+        self.advance_to(board);
+        self.bot.grow_forest(&mut self.trees, board, None, &SearchControl::new());
+        Some(analysis_from_arena(&merge_root_statistics(&self.trees), board.board_size()))
+    }
+}
+
+/// Índices de celda de las colocaciones de la partida, en orden. `None` si el
+/// historial contiene alguna acción.
+fn placement_history(board: &GameY) -> Option<Vec<u32>> {
+    let size = board.board_size();
+    board
+        .history()
+        .iter()
+        .map(|movement| match movement {
+            Movement::Placement { coords, .. } => Some(coords.to_index(size)),
+            Movement::Action { .. } => None,
+        })
+        .collect()
 }
 
 /// Helper function to reduce duplication: applies a move based solely on index.
@@ -269,7 +908,7 @@ fn apply_placement_from_idx(board: &mut GameY, move_idx: u32, size: u32) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{GameY, Movement, PlayerId};
+    use crate::{BridgePlayout, GameY, Movement, PlayerId};
 
     #[test]
     fn test_mcts_completo_bot_name() {
@@ -311,6 +950,305 @@ mod tests {
         assert!(chosen_move.is_none());
     }
 
+    #[test]
+    fn test_mcts_completo_bot_analysis_is_consistent() {
+        let bot = MctsCompletoBot::new("mcts_hard", 500);
+        let game = GameY::new(4);
+
+        let analysis = bot.analyze(&game).unwrap();
+
+        assert_eq!(analysis.total_visits, 500);
+        assert!(!analysis.moves.is_empty());
+        assert!(analysis.moves.windows(2).all(|w| w[0].visits >= w[1].visits));
+        assert!(analysis.moves.iter().all(|m| (0.0..=1.0).contains(&m.win_rate)));
+        assert!((0.0..=1.0).contains(&analysis.evaluation));
+        assert_eq!(analysis.principal_variation.first(), Some(&analysis.moves[0].coords));
+    }
+
+    #[test]
+    fn test_mcts_completo_bot_analysis_none_when_finished() {
+        let bot = MctsCompletoBot::new("mcts_hard", 100);
+        let mut game = GameY::new(1);
+        game.add_move(Movement::Placement { player: PlayerId::new(0), coords: Coordinates::new(0, 0, 0) }).unwrap();
+        assert!(bot.analyze(&game).is_none());
+    }
+
+    #[test]
+    fn test_mcts_completo_bot_parallel_merges_root_visits() {
+        let bot = MctsCompletoBot::new("mcts_paralelo", 600).with_threads(3);
+        let game = GameY::new(4);
+
+        let analysis = bot.analyze(&game).unwrap();
+
+        assert_eq!(analysis.total_visits, 600);
+        assert_eq!(analysis.moves.iter().map(|m| m.visits).sum::<u32>(), 600);
+        let mut cells: Vec<u32> = analysis.moves.iter().map(|m| m.cell_id).collect();
+        cells.sort();
+        cells.dedup();
+        assert_eq!(cells.len(), analysis.moves.len());
+    }
+
+    #[test]
+    fn test_mcts_completo_bot_time_budget_stops_early() {
+        let bot = MctsCompletoBot::new("mcts_tiempo", u32::MAX)
+            .with_threads(2)
+            .with_time_budget(Duration::from_millis(20));
+        let game = GameY::new(5);
+
+        let start = Instant::now();
+        assert!(bot.choose_move(&game).is_some());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_mcts_completo_bot_bridge_playout() {
+        let bot = MctsCompletoBot::new("mcts_puentes", 300).with_playout(Arc::new(BridgePlayout));
+        let mut game = GameY::new(5);
+        let _ = game.add_move(Movement::Placement { player: PlayerId::new(0), coords: Coordinates::new(2, 1, 1) });
+
+        let analysis = bot.analyze(&game).unwrap();
+        assert_eq!(analysis.total_visits, 300);
+        let coords = bot.choose_move(&game).unwrap();
+        assert!(game.available_cells().contains(&coords.to_index(5)));
+    }
+
+    #[test]
+    fn test_mcts_completo_bot_seeded_decisions_are_reproducible() {
+        let bot = MctsCompletoBot::new("mcts_semilla", 400).with_threads(2).with_rave(DEFAULT_RAVE_EQUIVALENCE);
+        let mut game = GameY::new(5);
+        let _ = game.add_move(Movement::Placement { player: PlayerId::new(0), coords: Coordinates::new(2, 1, 1) });
+
+        let first = bot.choose_move_seeded(&game, 11);
+        for _ in 0..3 {
+            assert_eq!(bot.choose_move_seeded(&game, 11), first);
+        }
+        let trees = |seed| {
+            bot.search(&game, Some(seed), &SearchControl::new())
+                .iter()
+                .map(|tree| tree.iter().map(|node| (node.move_idx, node.visits, node.wins)).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(trees(5), trees(5));
+        assert_ne!(trees(5), trees(6));
+    }
+
+    #[test]
+    fn test_mcts_completo_bot_seeded_search_does_not_depend_on_threads() {
+        let single = MctsCompletoBot::new("mcts_semilla", 400).with_threads(1);
+        let parallel = MctsCompletoBot::new("mcts_semilla", 400).with_threads(4);
+        let mut game = GameY::new(5);
+        let _ = game.add_move(Movement::Placement { player: PlayerId::new(0), coords: Coordinates::new(2, 1, 1) });
+
+        let stats = |bot: &MctsCompletoBot| {
+            bot.search(&game, Some(13), &SearchControl::new())
+                .iter()
+                .map(|tree| tree.iter().map(|node| (node.move_idx, node.visits, node.wins)).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(stats(&single), stats(&parallel));
+        assert_eq!(single.choose_move_seeded(&game, 13), parallel.choose_move_seeded(&game, 13));
+        assert_eq!(single.choose_action_seeded(&game, 13), parallel.choose_action_seeded(&game, 13));
+    }
+
+    #[test]
+    fn test_mcts_completo_bot_swaps_strong_opening_stone() {
+        // En un tablero pequeño la ficha central es muy fuerte: conviene quedársela.
+        let bot = MctsCompletoBot::new("mcts_intercambio", 2000).with_swap();
+        let mut game = GameY::new(4);
+        play(&mut game, Coordinates::new(1, 1, 1).to_index(4));
+
+        assert_eq!(
+            bot.choose_action_seeded(&game, 11),
+            Some(BotDecision::Action(crate::GameAction::Swap))
+        );
+        assert!(bot.choose_move_seeded(&game, 11).is_some());
+
+        // Pasada la segunda jugada ya no se plantea el intercambio.
+        play(&mut game, 0);
+        assert!(matches!(bot.choose_action_seeded(&game, 11), Some(BotDecision::Move(_))));
+    }
+
+    #[test]
+    fn test_mcts_completo_session_resigns_lost_positions() {
+        let bot = MctsCompletoBot::new("mcts_abandona", 200).with_resign_threshold(1.1);
+        let mut session = bot.new_session().unwrap();
+        let game = GameY::new(4);
+
+        // La primera estimación baja no basta para abandonar.
+        assert!(matches!(session.choose_action(&game), Some(BotDecision::Move(_))));
+        assert_eq!(session.choose_action(&game), Some(BotDecision::Action(crate::GameAction::Resign)));
+        assert!(session.choose_move(&game).is_some());
+    }
+
+    #[test]
+    fn test_prior_blends_static_evaluation_into_selection_score() {
+        let plain = MctsCompletoBot::new("mcts", 10);
+        let with_prior = MctsCompletoBot::new("mcts_prior", 10).with_prior(10.0);
+        let child = MctsNode { visits: 10, wins: 0.0, prior: 1.0, ..MctsNode::default() };
+
+        // 0 de 10 victorias reales, pero 10 visitas virtuales ganadas: 0.5.
+        let exploration = UCT_EXPLORATION * (1.0f32 / 10.0).sqrt();
+        assert!((plain.selection_score(&child, 1.0) - exploration).abs() < 1e-6);
+        assert!((with_prior.selection_score(&child, 1.0) - (0.5 + exploration)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_mcts_completo_bot_with_prior_plays_and_sets_priors() {
+        let bot = MctsCompletoBot::new("mcts_prior", 300).with_prior(DEFAULT_PRIOR_WEIGHT);
+        let game = GameY::new(5);
+        assert!(bot.choose_move(&game).is_some());
+
+        let trees = bot.search(&game, Some(5), &SearchControl::new());
+        let root = &trees[0][0];
+        assert!(root.children.iter().any(|&child| trees[0][child].prior != 0.5));
+    }
+
+    #[test]
+    fn test_mcts_rave_bot_collects_amaf_statistics() {
+        let bot = MctsCompletoBot::new("mcts_rave", 400).with_rave(DEFAULT_RAVE_EQUIVALENCE);
+        let game = GameY::new(4);
+
+        let trees = bot.search(&game, None, &SearchControl::new());
+        let arena = &trees[0];
+
+        assert_eq!(arena[0].visits, 400);
+        for &child_idx in &arena[0].children {
+            let child = &arena[child_idx];
+            // Toda visita real a un hijo cuenta también como visita AMAF.
+            assert!(child.amaf_visits >= child.visits);
+            assert!(child.amaf_wins <= child.amaf_visits as f32);
+        }
+        assert!(bot.choose_move(&game).is_some());
+    }
+
+    #[test]
+    fn test_rave_blends_amaf_into_selection_score() {
+        let uct = MctsCompletoBot::new("uct", 10);
+        let rave = MctsCompletoBot::new("rave", 10).with_rave(100.0);
+        let mut child = MctsNode::new(Some(0), Some(0), Some(PlayerId::new(0)), &GameY::new(2));
+        child.visits = 10;
+        child.wins = 2.0;
+        child.amaf_visits = 50;
+        child.amaf_wins = 45.0;
+
+        let log_parent = 100f32.ln();
+        assert!(rave.selection_score(&child, log_parent) > uct.selection_score(&child, log_parent));
+    }
+
+    fn play(game: &mut GameY, cell: u32) {
+        let player = game.next_player().unwrap();
+        let coords = Coordinates::from_index(cell, game.board_size());
+        game.add_move(Movement::Placement { player, coords }).unwrap();
+    }
+
+    #[test]
+    fn test_session_reuses_subtree_after_moves() {
+        let bot = MctsCompletoBot::new("mcts_sesion", 2000);
+        let mut session = MctsCompletoSession::new(bot);
+        let mut game = GameY::new(5);
+
+        let first = session.choose_move(&game).unwrap();
+        assert_eq!(session.root_visits(), 2000);
+
+        // Jugamos la jugada del bot y una respuesta que el árbol ya conoce.
+        play(&mut game, first.to_index(5));
+        let reply = session.trees[0][most_visited_child(&session.trees[0], 0).unwrap()].children
+            .first()
+            .and_then(|&idx| session.trees[0][idx].move_idx)
+            .unwrap_or_else(|| game.available_cells()[0]);
+        play(&mut game, reply);
+
+        session.choose_move(&game).unwrap();
+        assert!(session.root_visits() > 2000);
+    }
+
+    #[test]
+    fn test_session_starts_over_for_unrelated_position() {
+        let bot = MctsCompletoBot::new("mcts_sesion", 500);
+        let mut session = MctsCompletoSession::new(bot);
+
+        let mut game = GameY::new(4);
+        play(&mut game, 0);
+        session.choose_move(&game).unwrap();
+
+        let mut other = GameY::new(4);
+        play(&mut other, 9);
+        session.choose_move(&other).unwrap();
+        assert_eq!(session.root_visits(), 500);
+        assert_eq!(session.root_moves, vec![9]);
+    }
+
+    #[test]
+    fn test_session_respects_node_cap() {
+        let bot = MctsCompletoBot::new("mcts_sesion", 3000).with_max_nodes(100);
+        let mut session = MctsCompletoSession::new(bot);
+        let game = GameY::new(6);
+
+        assert!(session.choose_move(&game).is_some());
+        assert!(session.tree_size() <= 100);
+        assert_eq!(session.root_visits(), 3000);
+    }
+
+    #[test]
+    fn test_reroot_compacts_subtree() {
+        let bot = MctsCompletoBot::new("mcts_hard", 300);
+        let game = GameY::new(3);
+        let tree = bot.search(&game, None, &SearchControl::new()).remove(0);
+        let child_idx = most_visited_child(&tree, 0).unwrap();
+        let child_visits = tree[child_idx].visits;
+        let move_idx = tree[child_idx].move_idx.unwrap();
+
+        let subtree = reroot(tree, &[move_idx]).unwrap();
+        assert_eq!(subtree[0].parent, None);
+        assert_eq!(subtree[0].visits, child_visits);
+        assert!(subtree[0].children.iter().all(|&idx| subtree[idx].parent == Some(0)));
+    }
+
+    #[test]
+    fn test_ponder_grows_tree_for_opponent_turn() {
+        let bot = MctsCompletoBot::new("mcts_ponder", 500);
+        let mut session = MctsCompletoSession::new(bot);
+        let mut game = GameY::new(5);
+        play(&mut game, 0);
+
+        session.ponder(&game, &AtomicBool::new(false), Duration::from_secs(5));
+        assert_eq!(session.root_visits(), 500 * PONDER_ITERATION_FACTOR);
+
+        // Tras la respuesta del rival, la búsqueda parte de lo ya pensado.
+        play(&mut game, 14);
+        session.choose_move(&game).unwrap();
+        assert!(session.root_visits() > 500);
+    }
+
+    #[test]
+    fn test_ponder_stops_immediately_when_flag_is_set() {
+        let bot = MctsCompletoBot::new("mcts_ponder", 500);
+        let mut session = MctsCompletoSession::new(bot);
+
+        session.ponder(&GameY::new(5), &AtomicBool::new(true), Duration::from_secs(5));
+        assert_eq!(session.root_visits(), 0);
+    }
+
+    #[test]
+    fn test_new_session_is_supported() {
+        let bot = MctsCompletoBot::new("mcts_hard", 100);
+        let mut session = bot.new_session().unwrap();
+        assert!(session.choose_move(&GameY::new(3)).is_some());
+    }
+
+    #[test]
+    fn test_node_skips_dead_cells() {
+        // Tamaño 4: la esquina superior (índice 0) queda muerta tras 1 y 2 de P0.
+        let mut board = GameY::new(4);
+        apply_placement_from_idx(&mut board, 1, 4);
+        apply_placement_from_idx(&mut board, 6, 4);
+        apply_placement_from_idx(&mut board, 2, 4);
+
+        let node = MctsNode::new(None, None, None, &board);
+        assert!(!node.unexpanded_moves.contains(&0));
+        assert_eq!(node.unexpanded_moves.len(), board.available_cells().len() - 1);
+    }
+
     #[test]
     fn test_apply_placement_helper() {
         let mut board = GameY::new(3);
@@ -318,4 +1256,39 @@ mod tests {
         assert_eq!(board.available_cells().len(), 5); // size 3 has 6 cells, 1 assigned
         assert_eq!(board.next_player(), Some(PlayerId::new(1)));
     }
+
+    #[test]
+    fn test_cancelled_control_stops_the_search() {
+        let bot = MctsCompletoBot::new("mcts_cancelado", 100_000_000).with_threads(2);
+        let control = SearchControl::new();
+        control.cancel();
+
+        let start = Instant::now();
+        let coords = bot.choose_move_controlled(&GameY::new(7), None, &control);
+        assert!(coords.is_some());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_cancelled_control_stops_the_analysis() {
+        let bot = MctsCompletoBot::new("mcts_analisis_cancelado", 100_000_000).with_threads(2);
+        let control = SearchControl::new();
+        control.cancel();
+
+        let start = Instant::now();
+        let analysis = bot.analyze_controlled(&GameY::new(7), &control).unwrap();
+        assert!(analysis.total_visits < 100_000_000);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_search_reports_progress() {
+        let bot = MctsCompletoBot::new("mcts_progreso", 1000).with_threads(1);
+        let control = SearchControl::new();
+
+        let coords = bot.choose_move_controlled(&GameY::new(5), Some(3), &control);
+        let progress = control.progress();
+        assert_eq!(progress.iterations, 1000);
+        assert_eq!(progress.best_move, coords);
+    }
 }
@@ -5,20 +5,48 @@
 //!
 //! - [`YBot`] - A trait that defines the interface for all bots
 //! - [`YBotRegistry`] - A registry for managing multiple bot implementations
+//! - [`BotRegistryConfig`] - Declarative (JSON) description of a registry
 //! - [`RandomBot`] - A simple bot that makes random valid moves
-//! - [`GreedyBot`] - A heuristic bot
+//! - [`GreedyBot`] - Plays the move with the best static evaluation
 //! - [`MctsBot`] - A Monte Carlo Tree Search bot
+//! - [`BookBot`] - Plays opening book moves before delegating to another bot
+//! - [`ExternalBot`] - Gets its moves from an external engine process
+//! - [`RemoteBot`] - Gets its moves from another server's `/play` endpoint
+//! - [`SeededBot`] - Makes the decisions of any bot reproducible from a seed
+//! - [`SolverBot`] - An exact solver for small boards with an MCTS fallback
+//! - [`PlayoutPolicy`] - Move selection used by MCTS simulations
+//! - [`evaluate`] - Connectivity-based static evaluation (two-distance to each side)
+//! - [`ActionPolicy`] - When MCTS bots resign or swap instead of placing a stone
 
+pub mod actions;
+pub mod bot_config;
+pub mod book;
+pub mod evaluation;
+pub mod external;
 pub mod greedy;
 pub mod random;
+pub mod remote;
+pub mod seeded;
 pub mod ybot;
 pub mod ybot_registry;
 pub mod mcts;
 pub mod mctsCompleto;
+mod parallel;
+pub mod playout;
+pub mod solver;
 
+pub use actions::*;
+pub use bot_config::*;
+pub use book::*;
+pub use evaluation::*;
+pub use external::*;
 pub use greedy::*;
 pub use random::*;
+pub use remote::*;
+pub use seeded::*;
 pub use ybot::*;
 pub use ybot_registry::*;
 pub use mcts::*;
 pub use mctsCompleto::*;
+pub use playout::*;
+pub use solver::*;
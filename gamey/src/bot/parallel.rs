@@ -0,0 +1,74 @@
+//! Utilidades compartidas por los bots MCTS para repartir la búsqueda entre
+//! varios hilos (paralelismo en la raíz) y respetar un presupuesto de tiempo.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Traduce el número de hilos pedido a uno efectivo.
+///
+/// `0` significa "todos los núcleos disponibles"; cualquier otro valor se usa tal cual.
+pub(crate) fn effective_threads(requested: usize) -> usize {
+    if requested == 0 {
+        thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+    } else {
+        requested
+    }
+}
+
+/// Reparte `total` iteraciones entre `parts` hilos. El resto se asigna a los
+/// primeros hilos, de modo que la suma es exactamente `total`.
+pub(crate) fn split_iterations(total: u32, parts: usize) -> Vec<u32> {
+    let parts = parts.max(1) as u32;
+    let base = total / parts;
+    let remainder = total % parts;
+    (0..parts)
+        .map(|i| base + u32::from(i < remainder))
+        .collect()
+}
+
+/// Indica si ya se ha alcanzado la fecha límite (si la hay).
+pub(crate) fn deadline_reached(deadline: Option<Instant>) -> bool {
+    deadline.is_some_and(|deadline| Instant::now() >= deadline)
+}
+
+/// Describe el presupuesto de búsqueda para [`crate::BotMetadata`]. Con
+/// `u32::MAX` iteraciones se considera que solo limita el tiempo.
+pub(crate) fn describe_budget(iterations: u32, time_budget: Option<Duration>) -> String {
+    match (iterations, time_budget) {
+        (u32::MAX, Some(budget)) => format!("{} ms per move", budget.as_millis()),
+        (iterations, Some(budget)) => format!("{iterations} iterations or {} ms per move", budget.as_millis()),
+        (iterations, None) => format!("{iterations} iterations per move"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effective_threads() {
+        assert_eq!(effective_threads(3), 3);
+        assert!(effective_threads(0) >= 1);
+    }
+
+    #[test]
+    fn test_split_iterations_sums_to_total() {
+        assert_eq!(split_iterations(10, 3), vec![4, 3, 3]);
+        assert_eq!(split_iterations(2, 4), vec![1, 1, 0, 0]);
+        assert_eq!(split_iterations(7, 0), vec![7]);
+    }
+
+    #[test]
+    fn test_deadline_reached() {
+        assert!(!deadline_reached(None));
+        assert!(deadline_reached(Some(Instant::now())));
+        assert!(!deadline_reached(Some(Instant::now() + Duration::from_secs(60))));
+    }
+
+    #[test]
+    fn test_describe_budget() {
+        assert_eq!(describe_budget(500, None), "500 iterations per move");
+        assert_eq!(describe_budget(500, Some(Duration::from_millis(20))), "500 iterations or 20 ms per move");
+        assert_eq!(describe_budget(u32::MAX, Some(Duration::from_secs(1))), "1000 ms per move");
+    }
+}
@@ -0,0 +1,203 @@
+//! Políticas de playout para los bots MCTS.
+//!
+//! En la fase de simulación el MCTS juega la partida hasta el final eligiendo
+//! cada jugada con una [`PlayoutPolicy`]. Hay tres:
+//!
+//! - [`RandomPlayout`]: casilla libre al azar.
+//! - [`ProximityPlayout`]: torneo "más cerca de la última jugada" (la heurística
+//!   original de `MctsCompletoBot`).
+//! - [`BridgePlayout`]: si el rival acaba de meterse en un puente (dos piedras
+//!   propias con dos casillas comunes libres) o en una plantilla de borde (piedra
+//!   a distancia 1 del borde), se responde en la otra casilla. Si no, proximidad.
+
+use std::sync::Arc;
+
+use rand::seq::IndexedRandom;
+use rand::{Rng, RngCore};
+
+use crate::{Coordinates, GameY};
+
+/// Elige la siguiente jugada de una simulación.
+pub trait PlayoutPolicy: Send + Sync {
+    /// Nombre de la política (para configuración y logs).
+    fn name(&self) -> &'static str;
+
+    /// Devuelve el índice de la casilla que juega `board.next_player()`.
+    /// `last_move` es la última jugada de la simulación (la del rival), si la hay.
+    /// `None` si no quedan casillas libres.
+    fn choose(&self, board: &GameY, last_move: Option<Coordinates>, rng: &mut dyn RngCore) -> Option<u32>;
+}
+
+/// Construye una política a partir de su nombre: `random`, `proximity` o `bridge`.
+pub fn playout_from_name(name: &str) -> Option<Arc<dyn PlayoutPolicy>> {
+    match name {
+        "random" => Some(Arc::new(RandomPlayout)),
+        "proximity" => Some(Arc::new(ProximityPlayout)),
+        "bridge" => Some(Arc::new(BridgePlayout)),
+        _ => None,
+    }
+}
+
+/// Playout puramente aleatorio.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomPlayout;
+
+impl PlayoutPolicy for RandomPlayout {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn choose(&self, board: &GameY, _last_move: Option<Coordinates>, rng: &mut dyn RngCore) -> Option<u32> {
+        board.available_cells().choose(rng).copied()
+    }
+}
+
+/// Heavy playout: 75% de las veces un torneo de tamaño 3 entre casillas libres
+/// gana la más cercana a la última jugada; el resto, al azar.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProximityPlayout;
+
+impl PlayoutPolicy for ProximityPlayout {
+    fn name(&self) -> &'static str {
+        "proximity"
+    }
+
+    fn choose(&self, board: &GameY, last_move: Option<Coordinates>, rng: &mut dyn RngCore) -> Option<u32> {
+        let available = board.available_cells();
+        let first = *available.choose(rng)?;
+        let Some(last_coord) = last_move else { return Some(first) };
+        if !rng.random_bool(0.75) {
+            return Some(first);
+        }
+
+        let size = board.board_size();
+        let k = 3.min(available.len()); // Torneo de tamaño 3
+        let mut best_idx = available[0];
+        let mut min_dist = u32::MAX;
+
+        for _ in 0..k {
+            let candidate_idx = *available.choose(rng)?;
+            let target_coord = Coordinates::from_index(candidate_idx, size);
+
+            // Distancia topológica en Hex / Barycentric coords
+            let dist = target_coord.x().abs_diff(last_coord.x())
+                .max(target_coord.y().abs_diff(last_coord.y()))
+                .max(target_coord.z().abs_diff(last_coord.z()));
+
+            if dist < min_dist {
+                min_dist = dist;
+                best_idx = candidate_idx;
+            }
+        }
+        Some(best_idx)
+    }
+}
+
+/// Salva puentes y plantillas de borde atacados (las respuestas de
+/// [`GameY::threat_responses`] a la última jugada); si no hay ninguno, proximidad.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BridgePlayout;
+
+impl PlayoutPolicy for BridgePlayout {
+    fn name(&self) -> &'static str {
+        "bridge"
+    }
+
+    fn choose(&self, board: &GameY, last_move: Option<Coordinates>, rng: &mut dyn RngCore) -> Option<u32> {
+        if let (Some(intrusion), Some(me)) = (last_move, board.next_player()) {
+            let responses = board.threat_responses(intrusion, me);
+            if let Some(coords) = responses.choose(rng) {
+                return Some(coords.to_index(board.board_size()));
+            }
+        }
+        ProximityPlayout.choose(board, last_move, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Movement, PlayerId};
+
+    fn place(board: &mut GameY, player: u32, coords: Coordinates) {
+        board
+            .add_move(Movement::Placement {
+                player: PlayerId::new(player),
+                coords,
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_playout_from_name() {
+        for name in ["random", "proximity", "bridge"] {
+            assert_eq!(playout_from_name(name).unwrap().name(), name);
+        }
+        assert!(playout_from_name("unknown").is_none());
+    }
+
+    #[test]
+    fn test_policies_return_available_cell() {
+        let mut board = GameY::new(5);
+        place(&mut board, 0, Coordinates::new(2, 1, 1));
+        let last = Some(Coordinates::new(2, 1, 1));
+        let mut rng = rand::rng();
+
+        for name in ["random", "proximity", "bridge"] {
+            let policy = playout_from_name(name).unwrap();
+            for _ in 0..20 {
+                let idx = policy.choose(&board, last, &mut rng).unwrap();
+                assert!(board.available_cells().contains(&idx));
+            }
+        }
+    }
+
+    #[test]
+    fn test_bridge_intrusion_is_answered() {
+        // Puente de P0 entre (2,2,2) y (0,3,3): casillas comunes (1,3,2) y (1,2,3).
+        let mut board = GameY::new(7);
+        place(&mut board, 0, Coordinates::new(2, 2, 2));
+        place(&mut board, 1, Coordinates::new(6, 0, 0));
+        place(&mut board, 0, Coordinates::new(0, 3, 3));
+        place(&mut board, 1, Coordinates::new(1, 3, 2));
+
+        let intrusion = Some(Coordinates::new(1, 3, 2));
+        let mut rng = rand::rng();
+        for _ in 0..20 {
+            let idx = BridgePlayout.choose(&board, intrusion, &mut rng).unwrap();
+            assert_eq!(Coordinates::from_index(idx, 7), Coordinates::new(1, 2, 3));
+        }
+    }
+
+    #[test]
+    fn test_edge_template_intrusion_is_answered() {
+        // P0 en (1,2,1) está a distancia 1 del borde x = 0: (0,3,1) y (0,2,2).
+        let mut board = GameY::new(5);
+        place(&mut board, 0, Coordinates::new(1, 2, 1));
+        place(&mut board, 1, Coordinates::new(0, 3, 1));
+
+        let responses = board.threat_responses(Coordinates::new(0, 3, 1), PlayerId::new(0));
+        assert_eq!(responses, vec![Coordinates::new(0, 2, 2)]);
+    }
+
+    #[test]
+    fn test_no_response_when_bridge_already_broken() {
+        // Las dos casillas del puente ocupadas por el rival: no hay nada que salvar.
+        let mut board = GameY::new(7);
+        place(&mut board, 0, Coordinates::new(2, 2, 2));
+        place(&mut board, 1, Coordinates::new(1, 2, 3));
+        place(&mut board, 0, Coordinates::new(0, 3, 3));
+        place(&mut board, 1, Coordinates::new(1, 3, 2));
+
+        assert!(board.threat_responses(Coordinates::new(1, 3, 2), PlayerId::new(0)).is_empty());
+    }
+
+    #[test]
+    fn test_opponent_stones_do_not_form_bridges() {
+        let mut board = GameY::new(7);
+        place(&mut board, 0, Coordinates::new(2, 2, 2));
+        place(&mut board, 1, Coordinates::new(1, 3, 2));
+
+        assert!(board.threat_responses(Coordinates::new(1, 3, 2), PlayerId::new(1)).is_empty());
+    }
+}
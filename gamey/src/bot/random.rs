@@ -3,9 +3,12 @@
 //! This module provides [`RandomBot`], a bot that makes random valid moves.
 //! It is useful for testing and as a baseline opponent.
 
-use crate::{BotDecision, Coordinates, GameY, YBot};
+use crate::{BotDecision, BotMetadata, Coordinates, GameY, YBot};
+use rand::RngCore;
 use rand::prelude::IndexedRandom;
 
+use super::seeded::seeded_rng;
+
 /// A bot that chooses moves randomly from the available cells.
 ///
 /// This is the simplest possible bot implementation - it simply picks
@@ -31,14 +34,28 @@ impl YBot for RandomBot {
         "random_bot"
     }
 
+    fn metadata(&self) -> BotMetadata {
+        BotMetadata::new("Random")
+            .with_description("Plays a random free cell")
+            .with_difficulty(1)
+    }
+
     fn choose_action(&self, board: &GameY) -> Option<BotDecision> {
-        let available_cells = board.available_cells();
-        let cell = available_cells.choose(&mut rand::rng())?;
-        let coordinates = Coordinates::from_index(*cell, board.board_size());
-        Some(BotDecision::Move(coordinates))
+        random_move(board, &mut rand::rng())
+    }
+
+    fn choose_action_seeded(&self, board: &GameY, seed: u64) -> Option<BotDecision> {
+        random_move(board, &mut seeded_rng(Some(seed)))
     }
 }
 
+fn random_move(board: &GameY, rng: &mut dyn RngCore) -> Option<BotDecision> {
+    let available_cells = board.available_cells();
+    let cell = available_cells.choose(rng)?;
+    let coordinates = Coordinates::from_index(*cell, board.board_size());
+    Some(BotDecision::Move(coordinates))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -50,6 +67,13 @@ mod tests {
         assert_eq!(bot.name(), "random_bot");
     }
 
+    #[test]
+    fn test_random_bot_metadata() {
+        let metadata = RandomBot.metadata();
+        assert_eq!(metadata.difficulty, Some(1));
+        assert!(!metadata.supports_analysis);
+    }
+
     #[test]
     fn test_random_bot_returns_move_on_empty_board() {
         let bot = RandomBot;
@@ -0,0 +1,182 @@
+//! Bot backed by a remote `/play` server.
+//!
+//! [`RemoteBot`] sends each position as YEN to another server that implements
+//! the same `GET /play` API as [`crate::game_server::play`] and turns its
+//! [`PlayResponse`] back into a [`BotDecision`], so bots of other teams can
+//! be registered and benchmarked like local ones.
+//!
+//! Requests time out after [`DEFAULT_REMOTE_TIMEOUT`], or earlier if the
+//! [`SearchControl`] deadline comes first; the remaining time is forwarded as
+//! `timeout_ms` so the remote search ends in time. Connection errors,
+//! timeouts and `5xx`/`429` replies are retried up to
+//! [`DEFAULT_REMOTE_RETRIES`] times, unless the control asks to stop; any
+//! other error reply is final.
+
+use std::time::{Duration, Instant};
+
+use crate::game_server::API_V1;
+use crate::game_server::play::{PlayAction, PlayResponse};
+use crate::{BotDecision, BotMetadata, Coordinates, GameAction, GameY, SearchControl, YEN};
+use super::ybot::YBot;
+
+/// Default time limit for one request, including reading the reply.
+pub const DEFAULT_REMOTE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default number of retries after a failed request.
+pub const DEFAULT_REMOTE_RETRIES: u32 = 2;
+
+/// Pause before the first retry; it doubles on each further retry.
+const RETRY_BACKOFF: Duration = Duration::from_millis(100);
+
+/// A bot whose decisions come from a remote `/play` endpoint.
+pub struct RemoteBot {
+    name: String,
+    base_url: String,
+    bot_id: String,
+    retries: u32,
+    timeout: Duration,
+    agent: ureq::Agent,
+}
+
+impl RemoteBot {
+    /// Creates a bot that asks `bot_id` on the server at `base_url`
+    /// (for example `http://localhost:4000`).
+    pub fn new(name: impl Into<String>, base_url: impl Into<String>, bot_id: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            bot_id: bot_id.into(),
+            retries: DEFAULT_REMOTE_RETRIES,
+            timeout: DEFAULT_REMOTE_TIMEOUT,
+            agent: ureq::Agent::new(),
+        }
+    }
+
+    /// Sets the time limit for each request.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets how many times a failed request is retried.
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    fn decide(&self, board: &GameY, seed: Option<u64>, control: &SearchControl) -> Result<BotDecision, String> {
+        if board.check_game_over() {
+            return Err("the game is over".to_string());
+        }
+        let position = serde_json::to_string(&YEN::from(board)).map_err(|e| e.to_string())?;
+
+        let mut attempt = 0;
+        let response = loop {
+            let limit = control
+                .deadline_within(Some(self.timeout))
+                .map_or(self.timeout, |deadline| deadline.saturating_duration_since(Instant::now()));
+            if limit.is_zero() {
+                return Err("the deadline was reached".to_string());
+            }
+            let mut request = self
+                .agent
+                .get(&format!("{}/play", self.base_url))
+                .timeout(limit)
+                .query("position", &position)
+                .query("bot_id", &self.bot_id)
+                .query("api_version", API_V1)
+                .query("timeout_ms", &limit.as_millis().to_string());
+            if let Some(seed) = seed {
+                request = request.query("seed", &seed.to_string());
+            }
+
+            match request.call() {
+                Ok(response) => break response,
+                Err(e) if attempt < self.retries && is_transient(&e) && !control.should_stop() => {
+                    tracing::debug!(bot = %self.name, attempt, "remote bot request failed, retrying: {e}");
+                    std::thread::sleep(RETRY_BACKOFF * 2u32.pow(attempt));
+                    attempt += 1;
+                }
+                Err(e) => return Err(e.to_string()),
+            }
+        };
+
+        let body = response.into_string().map_err(|e| e.to_string())?;
+        let reply: PlayResponse = serde_json::from_str(&body).map_err(|e| format!("invalid reply: {e}"))?;
+        let decision = match reply {
+            PlayResponse::Move { coords } => BotDecision::Move(coords),
+            PlayResponse::Action { action: PlayAction::Swap } => BotDecision::Action(GameAction::Swap),
+            PlayResponse::Action { action: PlayAction::Resign } => BotDecision::Action(GameAction::Resign),
+        };
+        if let BotDecision::Move(coords) = decision {
+            let size = board.board_size();
+            let on_board = coords.x() as u64 + coords.y() as u64 + coords.z() as u64 == size as u64 - 1;
+            if !on_board || !board.available_cells().contains(&coords.to_index(size)) {
+                return Err(format!("remote bot played an illegal move: {coords}"));
+            }
+        }
+        Ok(decision)
+    }
+
+    fn choose(&self, board: &GameY, seed: Option<u64>, control: &SearchControl) -> Option<BotDecision> {
+        match self.decide(board, seed, control) {
+            Ok(decision) => Some(decision),
+            Err(e) => {
+                tracing::warn!(bot = %self.name, url = %self.base_url, "remote bot failed: {e}");
+                None
+            }
+        }
+    }
+}
+
+/// Whether a failed request is worth retrying.
+fn is_transient(error: &ureq::Error) -> bool {
+    match error {
+        ureq::Error::Status(status, _) => *status == 429 || *status >= 500,
+        ureq::Error::Transport(_) => true,
+    }
+}
+
+impl YBot for RemoteBot {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The remote bot's strength is unknown, so it is unrated.
+    fn metadata(&self) -> BotMetadata {
+        BotMetadata::new(&self.name)
+            .with_description(format!("Bot `{}` of the server at {}", self.bot_id, self.base_url))
+    }
+
+    fn choose_action(&self, board: &GameY) -> Option<BotDecision> {
+        self.choose(board, None, &SearchControl::new())
+    }
+
+    /// The seed is forwarded as the `seed` query parameter.
+    fn choose_action_seeded(&self, board: &GameY, seed: u64) -> Option<BotDecision> {
+        self.choose(board, Some(seed), &SearchControl::new())
+    }
+
+    /// Each request ends by the control's deadline and no retry starts once
+    /// it has passed or the control was cancelled.
+    fn choose_action_controlled(
+        &self,
+        board: &GameY,
+        seed: Option<u64>,
+        control: &SearchControl,
+    ) -> Option<BotDecision> {
+        self.choose(board, seed, control)
+    }
+
+    fn choose_move_controlled(
+        &self,
+        board: &GameY,
+        seed: Option<u64>,
+        control: &SearchControl,
+    ) -> Option<Coordinates> {
+        match self.choose(board, seed, control) {
+            Some(BotDecision::Move(coords)) => Some(coords),
+            _ => None,
+        }
+    }
+}
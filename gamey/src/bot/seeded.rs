@@ -0,0 +1,152 @@
+//! Reproducible bot decisions.
+//!
+//! Every [`YBot`] can be asked for a decision driven by a seed through
+//! [`YBot::choose_action_seeded`]. This module provides the helpers the bots
+//! use to turn a seed into random number generators, and [`SeededBot`], a
+//! wrapper that fixes the seed of any bot for a whole game.
+
+use std::sync::Arc;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use crate::{BotAnalysis, BotDecision, BotMetadata, Coordinates, GameY, SearchControl, YBot};
+
+/// Mixes `seed` with a stream number (SplitMix64), so that related seeds
+/// (`seed`, `seed + 1`, ...) give unrelated sequences.
+pub fn derive_seed(seed: u64, stream: u64) -> u64 {
+    let mut z = seed ^ stream.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Seed for the decision in `board` of a game played with `seed`: each move
+/// of the game gets a different one.
+pub fn move_seed(seed: u64, board: &GameY) -> u64 {
+    derive_seed(seed, board.history().len() as u64)
+}
+
+/// Random number generator for a search: seeded if `seed` is given, from
+/// system entropy otherwise.
+pub(crate) fn seeded_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_rng(&mut rand::rng()),
+    }
+}
+
+/// Wraps a bot so that all its decisions are derived from a fixed seed.
+///
+/// The seed of each decision also depends on the number of moves played (see
+/// [`move_seed`]), so the same game replayed with the same seed gets the same
+/// moves. The wrapper keeps the name of the wrapped bot and does not create
+/// sessions, since reusing search state between moves is not reproducible.
+pub struct SeededBot {
+    inner: Arc<dyn YBot>,
+    seed: u64,
+}
+
+impl SeededBot {
+    pub fn new(inner: Arc<dyn YBot>, seed: u64) -> Self {
+        Self { inner, seed }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl YBot for SeededBot {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn metadata(&self) -> BotMetadata {
+        self.inner.metadata()
+    }
+
+    fn choose_action(&self, board: &GameY) -> Option<BotDecision> {
+        self.inner.choose_action_seeded(board, move_seed(self.seed, board))
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        self.inner.choose_move_seeded(board, move_seed(self.seed, board))
+    }
+
+    fn choose_action_controlled(
+        &self,
+        board: &GameY,
+        _seed: Option<u64>,
+        control: &SearchControl,
+    ) -> Option<BotDecision> {
+        self.inner
+            .choose_action_controlled(board, Some(move_seed(self.seed, board)), control)
+    }
+
+    fn choose_move_controlled(
+        &self,
+        board: &GameY,
+        _seed: Option<u64>,
+        control: &SearchControl,
+    ) -> Option<Coordinates> {
+        self.inner
+            .choose_move_controlled(board, Some(move_seed(self.seed, board)), control)
+    }
+
+    fn analyze(&self, board: &GameY) -> Option<BotAnalysis> {
+        self.inner.analyze(board)
+    }
+
+    fn analyze_controlled(&self, board: &GameY, control: &SearchControl) -> Option<BotAnalysis> {
+        self.inner.analyze_controlled(board, control)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Movement, PlayerId, RandomBot};
+    use rand::Rng;
+
+    #[test]
+    fn test_derive_seed_separates_streams() {
+        assert_eq!(derive_seed(7, 1), derive_seed(7, 1));
+        assert_ne!(derive_seed(7, 1), derive_seed(7, 2));
+        assert_ne!(derive_seed(7, 1), derive_seed(8, 1));
+    }
+
+    #[test]
+    fn test_seeded_rng_is_reproducible() {
+        let a: Vec<u32> = (0..8).map({ let mut rng = seeded_rng(Some(3)); move |_| rng.random() }).collect();
+        let b: Vec<u32> = (0..8).map({ let mut rng = seeded_rng(Some(3)); move |_| rng.random() }).collect();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_move_seed_changes_with_each_move() {
+        let mut board = GameY::new(5);
+        let first = move_seed(42, &board);
+        board
+            .add_move(Movement::Placement { player: PlayerId::new(0), coords: Coordinates::new(2, 1, 1) })
+            .unwrap();
+        assert_ne!(first, move_seed(42, &board));
+    }
+
+    #[test]
+    fn test_seeded_bot_replays_the_same_game() {
+        let play = |seed: u64| {
+            let bot = SeededBot::new(Arc::new(RandomBot), seed);
+            let mut board = GameY::new(6);
+            while let Some(player) = board.next_player() {
+                let coords = bot.choose_move(&board).unwrap();
+                board.add_move(Movement::Placement { player, coords }).unwrap();
+            }
+            board.history().iter().map(|m| format!("{m:?}")).collect::<Vec<_>>()
+        };
+
+        assert_eq!(play(1), play(1));
+        assert_ne!(play(1), play(2));
+        assert_eq!(SeededBot::new(Arc::new(RandomBot), 1).name(), "random_bot");
+    }
+}
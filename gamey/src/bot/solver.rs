@@ -0,0 +1,526 @@
+//! Solver exacto para tableros pequeños.
+//!
+//! En Y no hay empates: con el tablero lleno siempre gana alguien, así que cada
+//! posición es ganada o perdida para el jugador que mueve. [`Solver`] lo
+//! demuestra con una búsqueda en profundidad tipo negamax (booleana, equivalente
+//! a alfa-beta con ventana mínima) sobre bitboards, con:
+//!
+//! - tabla de transposiciones (posición → ganada/perdida)
+//! - victorias inmediatas: si puedo conectar los tres lados en una jugada, gano
+//! - jugadas forzadas: si el rival amenaza ganar en una casilla hay que taparla,
+//!   y si amenaza en dos la posición está perdida
+//! - orden de jugadas del centro hacia los bordes
+//! - relleno de casillas inferiores en la raíz: las casillas muertas o
+//!   capturadas ([`GameY::inferior_cells`]) se dan a su dueño antes de buscar,
+//!   lo que no cambia el resultado y reduce el número de jugadas
+//!
+//! La búsqueda se corta al superar el presupuesto de nodos o cuando lo pide el
+//! [`SearchControl`] (fecha límite o cancelación). [`SolverBot`] usa el solver
+//! y, si no consigue demostrar una victoria, juega con un MCTS.
+//!
+//! Solo se consideran colocaciones: el solver no tiene en cuenta la regla del
+//! intercambio ni el abandono.
+
+use std::collections::HashMap;
+
+use crate::{
+    BotAnalysis, BotDecision, BotMetadata, Cell, Coordinates, GameStatus, GameY, MctsCompletoBot, SearchControl,
+};
+use super::ybot::{MAX_DIFFICULTY, YBot};
+
+/// Presupuesto de nodos por defecto de [`Solver`].
+pub const DEFAULT_SOLVER_NODE_BUDGET: u64 = 2_000_000;
+
+/// Cada cuántos nodos se consulta el [`SearchControl`].
+const STOP_CHECK_INTERVAL: u64 = 4096;
+
+/// Tamaño máximo de tablero que cabe en los bitboards (55 casillas ≤ 64 bits).
+pub const MAX_SOLVER_BOARD_SIZE: u32 = 10;
+
+/// Resultado demostrado para el jugador que mueve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveOutcome {
+    /// Victoria forzada empezando por `best_move`.
+    Win { best_move: Coordinates },
+    /// Cualquier jugada pierde contra un rival perfecto.
+    Loss,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Solution {
+    pub outcome: SolveOutcome,
+    /// Nodos visitados para llegar a la demostración.
+    pub nodes: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Solver {
+    node_budget: u64,
+}
+
+impl Solver {
+    pub fn new(node_budget: u64) -> Self {
+        Self { node_budget }
+    }
+
+    pub fn node_budget(&self) -> u64 {
+        self.node_budget
+    }
+
+    /// Intenta resolver la posición. `None` si la partida ya terminó, el tablero
+    /// es mayor que [`MAX_SOLVER_BOARD_SIZE`] o se agotó el presupuesto de nodos.
+    pub fn solve(&self, board: &GameY) -> Option<Solution> {
+        self.solve_controlled(board, &SearchControl::new())
+    }
+
+    /// Como [`Solver::solve`], pero devuelve `None` también si `control` pide
+    /// parar (fecha límite o cancelación) antes de terminar la demostración.
+    pub fn solve_controlled(&self, board: &GameY, control: &SearchControl) -> Option<Solution> {
+        let size = board.board_size();
+        if size > MAX_SOLVER_BOARD_SIZE {
+            return None;
+        }
+        let GameStatus::Ongoing { next_player } = board.status() else {
+            return None;
+        };
+
+        let layout = Layout::new(size);
+        let mut stones = [0u64; 2];
+        for idx in 0..board.total_cells() {
+            if let Cell::Occupied(player) = board.cell(&Coordinates::from_index(idx, size)) {
+                stones[(player.id() % 2) as usize] |= 1 << idx;
+            }
+        }
+
+        // Relleno: las casillas inferiores pasan a su dueño. Si con eso alguien
+        // ya conecta, el resultado está decidido sin buscar; el ganador puede
+        // empezar por cualquiera de sus casillas rellenadas.
+        let mut filled = [Vec::new(), Vec::new()];
+        for cell in board.inferior_cells() {
+            let owner = (cell.owner.id() % 2) as usize;
+            let idx = cell.coords.to_index(size);
+            stones[owner] |= 1 << idx;
+            filled[owner].push(idx);
+        }
+        let me = (next_player.id() % 2) as usize;
+        // Antes del relleno nadie conectaba, así que un grupo ganador tiene que
+        // contener alguna casilla rellenada de su dueño.
+        let connected = |p: usize| filled[p].iter().any(|&idx| layout.connects(stones[p], idx));
+        if connected(me) {
+            let best_move = Coordinates::from_index(filled[me][0], size);
+            return Some(Solution { outcome: SolveOutcome::Win { best_move }, nodes: 0 });
+        }
+        if connected(1 - me) {
+            return Some(Solution { outcome: SolveOutcome::Loss, nodes: 0 });
+        }
+
+        let mut search = Search {
+            layout: &layout,
+            table: HashMap::new(),
+            nodes: 0,
+            budget: self.node_budget,
+            control,
+        };
+        let (wins, best) = search.solve(stones, me)?;
+        let outcome = match best {
+            Some(idx) if wins => SolveOutcome::Win { best_move: Coordinates::from_index(idx, size) },
+            _ => SolveOutcome::Loss,
+        };
+        Some(Solution { outcome, nodes: search.nodes })
+    }
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Self::new(DEFAULT_SOLVER_NODE_BUDGET)
+    }
+}
+
+/// Vecinos y lados de cada casilla, precalculados como máscaras de bits.
+struct Layout {
+    full: u64,
+    neighbors: Vec<u64>,
+    sides: [u64; 3],
+    /// Casillas ordenadas del centro hacia los bordes.
+    order: Vec<u32>,
+}
+
+impl Layout {
+    fn new(size: u32) -> Self {
+        let total = size * (size + 1) / 2;
+        let board = GameY::new(size);
+        let mut neighbors = Vec::with_capacity(total as usize);
+        let mut sides = [0u64; 3];
+
+        for idx in 0..total {
+            let coords = Coordinates::from_index(idx, size);
+            let mask = board
+                .get_neighbors(&coords)
+                .iter()
+                .fold(0u64, |acc, n| acc | 1 << n.to_index(size));
+            neighbors.push(mask);
+            for (side, value) in [coords.x(), coords.y(), coords.z()].into_iter().enumerate() {
+                if value == 0 {
+                    sides[side] |= 1 << idx;
+                }
+            }
+        }
+
+        let mut order: Vec<u32> = (0..total).collect();
+        order.sort_by_key(|&idx| {
+            let c = Coordinates::from_index(idx, size);
+            std::cmp::Reverse(c.x().min(c.y()).min(c.z()))
+        });
+
+        Self {
+            full: (1u64 << total) - 1,
+            neighbors,
+            sides,
+            order,
+        }
+    }
+
+    /// ¿El grupo de `own` que contiene `from` toca los tres lados?
+    fn connects(&self, own: u64, from: u32) -> bool {
+        let mut group = 1u64 << from;
+        let mut frontier = group;
+        while frontier != 0 {
+            let mut next = 0u64;
+            let mut bits = frontier;
+            while bits != 0 {
+                let cell = bits.trailing_zeros();
+                bits &= bits - 1;
+                next |= self.neighbors[cell as usize];
+            }
+            frontier = next & own & !group;
+            group |= frontier;
+        }
+        self.sides.iter().all(|&side| group & side != 0)
+    }
+}
+
+struct Search<'a> {
+    layout: &'a Layout,
+    /// Clave: piedras de cada jugador, con el jugador que mueve en el bit 63.
+    table: HashMap<(u64, u64), bool>,
+    nodes: u64,
+    budget: u64,
+    control: &'a SearchControl,
+}
+
+impl Search<'_> {
+    /// Devuelve si gana `me` (el que mueve) y, si gana, con qué casilla.
+    /// `None` si se agota el presupuesto o el control pide parar.
+    fn solve(&mut self, stones: [u64; 2], me: usize) -> Option<(bool, Option<u32>)> {
+        self.nodes += 1;
+        if self.nodes > self.budget {
+            return None;
+        }
+        if self.nodes.is_multiple_of(STOP_CHECK_INTERVAL) && self.control.should_stop() {
+            return None;
+        }
+
+        let key = (stones[0] | (me as u64) << 63, stones[1]);
+        if let Some(&wins) = self.table.get(&key) {
+            // La jugada ganadora no se guarda: solo hace falta en la raíz, que no se repite.
+            return Some((wins, None));
+        }
+
+        let opponent = 1 - me;
+        let empty = self.layout.full & !(stones[0] | stones[1]);
+        let mut threat: Option<u32> = None;
+        let mut threats = 0;
+
+        for &cell in &self.layout.order {
+            let bit = 1u64 << cell;
+            if empty & bit == 0 {
+                continue;
+            }
+            if self.layout.connects(stones[me] | bit, cell) {
+                self.table.insert(key, true);
+                return Some((true, Some(cell)));
+            }
+            if self.layout.connects(stones[opponent] | bit, cell) {
+                threats += 1;
+                threat = Some(cell);
+            }
+        }
+
+        // Dos amenazas del rival: no se pueden tapar las dos.
+        let result = if threats >= 2 {
+            (false, None)
+        } else {
+            let forced = threat.map(|cell| vec![cell]);
+            let candidates = forced.as_deref().unwrap_or(&self.layout.order);
+            let mut result = (false, None);
+            for &cell in candidates {
+                let bit = 1u64 << cell;
+                if empty & bit == 0 {
+                    continue;
+                }
+                let mut child = stones;
+                child[me] |= bit;
+                let (opponent_wins, _) = self.solve(child, opponent)?;
+                if !opponent_wins {
+                    result = (true, Some(cell));
+                    break;
+                }
+            }
+            result
+        };
+
+        self.table.insert(key, result.0);
+        Some(result)
+    }
+}
+
+/// Bot que juega la victoria demostrada por el [`Solver`] si la encuentra y,
+/// si no (posición perdida o demasiado grande), delega en un MCTS.
+pub struct SolverBot {
+    name: String,
+    solver: Solver,
+    fallback: MctsCompletoBot,
+}
+
+impl SolverBot {
+    pub fn new(name: impl Into<String>, node_budget: u64, fallback: MctsCompletoBot) -> Self {
+        Self {
+            name: name.into(),
+            solver: Solver::new(node_budget),
+            fallback,
+        }
+    }
+
+    pub fn solver(&self) -> &Solver {
+        &self.solver
+    }
+}
+
+impl YBot for SolverBot {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Juega perfecto cuando la posición se resuelve; si no, como su MCTS.
+    fn metadata(&self) -> BotMetadata {
+        let fallback = self.fallback.metadata();
+        BotMetadata {
+            display_name: self.name.clone(),
+            description: format!(
+                "Exact solver (boards up to size {MAX_SOLVER_BOARD_SIZE}, {} nodes) backed by {}",
+                self.solver.node_budget(),
+                fallback.description
+            ),
+            difficulty: Some(MAX_DIFFICULTY),
+            ..fallback
+        }
+    }
+
+    fn choose_action(&self, board: &GameY) -> Option<BotDecision> {
+        if let Some(Solution { outcome: SolveOutcome::Win { best_move }, .. }) = self.solver.solve(board) {
+            return Some(BotDecision::Move(best_move));
+        }
+        self.fallback.choose_action(board)
+    }
+
+    fn choose_action_seeded(&self, board: &GameY, seed: u64) -> Option<BotDecision> {
+        if let Some(Solution { outcome: SolveOutcome::Win { best_move }, .. }) = self.solver.solve(board) {
+            return Some(BotDecision::Move(best_move));
+        }
+        self.fallback.choose_action_seeded(board, seed)
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        match self.solver.solve(board) {
+            Some(Solution { outcome: SolveOutcome::Win { best_move }, .. }) => Some(best_move),
+            _ => self.fallback.choose_move(board),
+        }
+    }
+
+    fn choose_move_seeded(&self, board: &GameY, seed: u64) -> Option<Coordinates> {
+        match self.solver.solve(board) {
+            Some(Solution { outcome: SolveOutcome::Win { best_move }, .. }) => Some(best_move),
+            _ => self.fallback.choose_move_seeded(board, seed),
+        }
+    }
+
+    /// El solver se corta con `control` y el MCTS recibe el mismo control, así
+    /// que solo dispone del tiempo que quede hasta la fecha límite.
+    fn choose_action_controlled(
+        &self,
+        board: &GameY,
+        seed: Option<u64>,
+        control: &SearchControl,
+    ) -> Option<BotDecision> {
+        let solution = self.solver.solve_controlled(board, control);
+        if let Some(Solution { outcome: SolveOutcome::Win { best_move }, .. }) = solution {
+            return Some(BotDecision::Move(best_move));
+        }
+        self.fallback.choose_action_controlled(board, seed, control)
+    }
+
+    fn choose_move_controlled(
+        &self,
+        board: &GameY,
+        seed: Option<u64>,
+        control: &SearchControl,
+    ) -> Option<Coordinates> {
+        match self.solver.solve_controlled(board, control) {
+            Some(Solution { outcome: SolveOutcome::Win { best_move }, .. }) => Some(best_move),
+            _ => self.fallback.choose_move_controlled(board, seed, control),
+        }
+    }
+
+    fn analyze(&self, board: &GameY) -> Option<BotAnalysis> {
+        self.fallback.analyze(board)
+    }
+
+    fn analyze_controlled(&self, board: &GameY, control: &SearchControl) -> Option<BotAnalysis> {
+        self.fallback.analyze_controlled(board, control)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Movement, PlayerId};
+
+    fn place(board: &mut GameY, player: u32, idx: u32) {
+        let coords = Coordinates::from_index(idx, board.board_size());
+        board
+            .add_move(Movement::Placement { player: PlayerId::new(player), coords })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_layout_connects_all_sides() {
+        let layout = Layout::new(3);
+        // Fila inferior completa de un tablero de tamaño 3: toca los tres lados.
+        let bottom = [3u32, 4, 5]
+            .iter()
+            .fold(0u64, |acc, &i| acc | 1 << i);
+        assert!(layout.connects(bottom, 3));
+        assert!(!layout.connects(1 << 0, 0));
+    }
+
+    #[test]
+    fn test_first_player_wins_small_boards() {
+        // En Y el primer jugador gana siempre con juego perfecto.
+        for size in 1..=5 {
+            let solution = Solver::default().solve(&GameY::new(size)).unwrap();
+            assert!(
+                matches!(solution.outcome, SolveOutcome::Win { .. }),
+                "size {size} not solved as a win"
+            );
+        }
+    }
+
+    #[test]
+    fn test_solver_finds_immediate_win() {
+        // Tamaño 3: P0 tiene 3 y 4; jugando 5 completa la fila inferior.
+        let mut board = GameY::new(3);
+        place(&mut board, 0, 3);
+        place(&mut board, 1, 0);
+        place(&mut board, 0, 4);
+        place(&mut board, 1, 1);
+
+        let solution = Solver::default().solve(&board).unwrap();
+        let SolveOutcome::Win { best_move } = solution.outcome else {
+            panic!("expected a win");
+        };
+        let mut after = board.clone();
+        after
+            .add_move(Movement::Placement { player: PlayerId::new(0), coords: best_move })
+            .unwrap();
+        assert!(after.check_game_over());
+    }
+
+    #[test]
+    fn test_solver_detects_loss() {
+        // Tamaño 2: tras la primera piedra de P0, P1 pierde haga lo que haga.
+        let mut board = GameY::new(2);
+        place(&mut board, 0, 1);
+
+        let solution = Solver::default().solve(&board).unwrap();
+        assert_eq!(solution.outcome, SolveOutcome::Loss);
+    }
+
+    #[test]
+    fn test_solver_decides_by_filling_in_captured_cells() {
+        // Tamaño 2: las dos casillas libres están capturadas por P0; al
+        // rellenarlas P0 ya conecta, así que no hace falta buscar.
+        let mut board = GameY::new(2);
+        place(&mut board, 0, 0);
+
+        let solution = Solver::default().solve(&board).unwrap();
+        assert_eq!(solution.outcome, SolveOutcome::Loss);
+        assert_eq!(solution.nodes, 0);
+    }
+
+    #[test]
+    fn test_solver_never_plays_a_dead_cell() {
+        // Tamaño 4: la esquina superior (índice 0) está muerta, rodeada por P0.
+        let mut board = GameY::new(4);
+        place(&mut board, 0, 1);
+        place(&mut board, 1, 6);
+        place(&mut board, 0, 2);
+        place(&mut board, 1, 9);
+        assert_eq!(board.inferior_cells().len(), 1);
+
+        let solution = Solver::default().solve(&board).unwrap();
+        if let SolveOutcome::Win { best_move } = solution.outcome {
+            assert_ne!(best_move.to_index(4), 0);
+        }
+    }
+
+    #[test]
+    fn test_solver_respects_node_budget() {
+        assert!(Solver::new(10).solve(&GameY::new(7)).is_none());
+    }
+
+    #[test]
+    fn test_solver_stops_when_control_asks() {
+        let control = SearchControl::new();
+        control.cancel();
+        assert!(Solver::default().solve_controlled(&GameY::new(9), &control).is_none());
+
+        let expired = SearchControl::with_deadline(std::time::Instant::now());
+        assert!(Solver::default().solve_controlled(&GameY::new(9), &expired).is_none());
+    }
+
+    #[test]
+    fn test_solver_bot_respects_deadline() {
+        let bot = SolverBot::new("solver", DEFAULT_SOLVER_NODE_BUDGET, MctsCompletoBot::new("fallback", 100_000_000));
+        let control = SearchControl::with_deadline(std::time::Instant::now() + std::time::Duration::from_millis(200));
+        let start = std::time::Instant::now();
+
+        assert!(bot.choose_move_controlled(&GameY::new(9), None, &control).is_some());
+        assert!(start.elapsed() < std::time::Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_solver_gives_up_on_large_boards() {
+        assert!(Solver::default().solve(&GameY::new(MAX_SOLVER_BOARD_SIZE + 1)).is_none());
+    }
+
+    #[test]
+    fn test_solver_bot_plays_proven_win() {
+        let bot = SolverBot::new("solver", DEFAULT_SOLVER_NODE_BUDGET, MctsCompletoBot::new("fallback", 50));
+        let board = GameY::new(4);
+
+        let coords = bot.choose_move(&board).unwrap();
+        let mut after = board.clone();
+        after
+            .add_move(Movement::Placement { player: PlayerId::new(0), coords })
+            .unwrap();
+        let follow_up = Solver::default().solve(&after).unwrap();
+        assert_eq!(follow_up.outcome, SolveOutcome::Loss);
+    }
+
+    #[test]
+    fn test_solver_bot_falls_back_when_unsolved() {
+        let bot = SolverBot::new("solver", 10, MctsCompletoBot::new("fallback", 50));
+        assert!(bot.choose_move(&GameY::new(7)).is_some());
+        assert!(bot.analyze(&GameY::new(7)).is_some());
+    }
+}
@@ -1,3 +1,9 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
 use crate::{Coordinates, GameAction, GameY};
 
 /// Decision that a bot can return for the external competition API.
@@ -7,6 +13,238 @@ pub enum BotDecision {
     Action(GameAction),
 }
 
+/// Search statistics for a single candidate move.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MoveAnalysis {
+    /// Cell index of the move.
+    pub cell_id: u32,
+    /// Coordinates of the move.
+    pub coords: Coordinates,
+    /// Number of playouts that went through this move.
+    pub visits: u32,
+    /// Fraction of those playouts won by the player to move, in `[0, 1]`.
+    pub win_rate: f32,
+}
+
+/// Result of analysing a position: what the bot thinks of every move.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BotAnalysis {
+    /// Total number of playouts behind the statistics.
+    pub total_visits: u32,
+    /// Estimated probability that the player to move wins, in `[0, 1]`.
+    pub evaluation: f32,
+    /// Candidate moves, most visited first.
+    pub moves: Vec<MoveAnalysis>,
+    /// Best line of play found, starting with the move for the player to move.
+    pub principal_variation: Vec<Coordinates>,
+}
+
+impl BotAnalysis {
+    /// Returns the share of visits of each cell, indexed by cell index and
+    /// normalized so that the most visited move has value `1.0`.
+    ///
+    /// The result can be passed directly as [`crate::SvgOptions::heatmap`].
+    pub fn heatmap(&self, total_cells: u32) -> Vec<f32> {
+        let mut values = vec![0.0; total_cells as usize];
+        let max_visits = self.moves.iter().map(|m| m.visits).max().unwrap_or(0);
+        if max_visits == 0 {
+            return values;
+        }
+        for m in &self.moves {
+            if let Some(value) = values.get_mut(m.cell_id as usize) {
+                *value = m.visits as f32 / max_visits as f32;
+            }
+        }
+        values
+    }
+}
+
+/// Rule variants a bot knows how to play.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleSet {
+    /// Players alternate placing stones.
+    Standard,
+    /// The second player may swap instead of placing (pie rule). Bots listing
+    /// it decide when to swap; the rest never do.
+    Swap,
+}
+
+/// Highest difficulty rating a bot can have.
+pub const MAX_DIFFICULTY: u8 = 5;
+
+/// Description of a bot for user interfaces: what it is, how strong it is
+/// and what it can do.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BotMetadata {
+    /// Human-readable name.
+    pub display_name: String,
+    /// Short explanation of how the bot plays.
+    pub description: String,
+    /// Rating from 1 (beginner) to [`MAX_DIFFICULTY`], or `None` if unknown.
+    pub difficulty: Option<u8>,
+    /// Rule variants the bot supports.
+    pub rule_sets: Vec<RuleSet>,
+    /// Smallest supported board size.
+    pub min_board_size: u32,
+    /// Largest supported board size, or `None` for no limit.
+    pub max_board_size: Option<u32>,
+    /// Whether [`YBot::analyze`] returns statistics.
+    pub supports_analysis: bool,
+    /// Upper bound on the time spent per move, if the bot has one.
+    pub think_time_ms: Option<u64>,
+}
+
+impl BotMetadata {
+    /// Metadata with only a display name: unrated, standard rules, any board
+    /// size and no analysis.
+    pub fn new(display_name: impl Into<String>) -> Self {
+        Self {
+            display_name: display_name.into(),
+            description: String::new(),
+            difficulty: None,
+            rule_sets: vec![RuleSet::Standard],
+            min_board_size: 1,
+            max_board_size: None,
+            supports_analysis: false,
+            think_time_ms: None,
+        }
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    /// Sets the rating, clamped to `1..=MAX_DIFFICULTY`.
+    pub fn with_difficulty(mut self, difficulty: u8) -> Self {
+        self.difficulty = Some(difficulty.clamp(1, MAX_DIFFICULTY));
+        self
+    }
+
+    pub fn with_rule_sets(mut self, rule_sets: Vec<RuleSet>) -> Self {
+        self.rule_sets = rule_sets;
+        self
+    }
+
+    pub fn with_max_board_size(mut self, max_board_size: u32) -> Self {
+        self.max_board_size = Some(max_board_size);
+        self
+    }
+
+    pub fn with_analysis(mut self) -> Self {
+        self.supports_analysis = true;
+        self
+    }
+
+    pub fn with_think_time(mut self, think_time: Option<Duration>) -> Self {
+        self.think_time_ms = think_time.map(|t| t.as_millis() as u64);
+        self
+    }
+
+    /// Whether the bot can play on a board of side `size`.
+    pub fn supports_board_size(&self, size: u32) -> bool {
+        size >= self.min_board_size && self.max_board_size.is_none_or(|max| size <= max)
+    }
+}
+
+/// How far a controlled search has got (see [`SearchControl::progress`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SearchProgress {
+    /// Playouts completed so far.
+    pub iterations: u64,
+    /// The move the search currently prefers, once it has one.
+    pub best_move: Option<Coordinates>,
+}
+
+/// External control over a single decision: a deadline and a cancellation
+/// flag, shared between the caller and the thread running the search.
+///
+/// Searching bots stop when either is reached and answer with the best
+/// decision found so far; bots that decide quickly ignore it. Searching bots
+/// also report their progress here, so the caller can show it while waiting.
+#[derive(Debug, Default)]
+pub struct SearchControl {
+    stop: AtomicBool,
+    deadline: Option<Instant>,
+    iterations: AtomicU64,
+    /// Favourite move and its score, per search thread.
+    best: Mutex<Vec<Option<(Coordinates, f32)>>>,
+}
+
+impl SearchControl {
+    /// A control without deadline that is never cancelled unless asked to.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_deadline(deadline: Instant) -> Self {
+        Self { deadline: Some(deadline), ..Self::default() }
+    }
+
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Asks the search to stop as soon as possible.
+    pub fn cancel(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+
+    /// The cancellation flag, for searches that poll an [`AtomicBool`].
+    pub fn stop_flag(&self) -> &AtomicBool {
+        &self.stop
+    }
+
+    /// The earlier of this control's deadline and `budget` from now.
+    pub fn deadline_within(&self, budget: Option<Duration>) -> Option<Instant> {
+        let budget = budget.map(|budget| Instant::now() + budget);
+        match (self.deadline, budget) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Whether the search should stop now: cancelled or past the deadline.
+    pub fn should_stop(&self) -> bool {
+        self.is_cancelled() || self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Records `count` more completed playouts.
+    pub fn add_iterations(&self, count: u64) {
+        self.iterations.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Records `coords` as the move that search thread `worker` currently
+    /// prefers, with a confidence `score` (higher is better). Each thread's
+    /// proposal replaces its previous one; [`SearchControl::progress`]
+    /// reports the highest-scored among them.
+    pub fn propose_best_move(&self, worker: usize, coords: Coordinates, score: f32) {
+        let mut best = self.best.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if best.len() <= worker {
+            best.resize(worker + 1, None);
+        }
+        best[worker] = Some((coords, score));
+    }
+
+    /// Snapshot of the progress reported so far.
+    pub fn progress(&self) -> SearchProgress {
+        let best = self.best.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        SearchProgress {
+            iterations: self.iterations.load(Ordering::Relaxed),
+            best_move: best
+                .iter()
+                .flatten()
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|&(coords, _)| coords),
+        }
+    }
+}
+
 /// Trait representing a Y game bot (YBot)
 /// A YBot is an AI that can choose moves in the game of Y.
 /// Implementors of this trait must provide a name and a method to choose the
@@ -26,10 +264,239 @@ pub trait YBot: Send + Sync {
     ///
     /// This helper keeps the rest of the project compatible with the original
     /// trait while allowing the external competition API to support actions.
+    /// Bots that may resign or swap override it to return the placement they
+    /// would have played, so callers that only place stones keep working.
     fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
         match self.choose_action(board) {
             Some(BotDecision::Move(coords)) => Some(coords),
             _ => None,
         }
     }
+
+    /// Chooses the next action drawing all randomness from `seed`, so that the
+    /// same position and seed always give the same decision.
+    ///
+    /// Bots without randomness can rely on the default, which ignores the seed.
+    /// Searches limited by time are only reproducible if they finish their
+    /// iterations before the deadline.
+    fn choose_action_seeded(&self, board: &GameY, _seed: u64) -> Option<BotDecision> {
+        self.choose_action(board)
+    }
+
+    /// Seeded version of [`YBot::choose_move`].
+    fn choose_move_seeded(&self, board: &GameY, seed: u64) -> Option<Coordinates> {
+        match self.choose_action_seeded(board, seed) {
+            Some(BotDecision::Move(coords)) => Some(coords),
+            _ => None,
+        }
+    }
+
+    /// Chooses the next action (seeded if `seed` is given) under `control`:
+    /// searching bots stop at its deadline or when it is cancelled and answer
+    /// with the best decision found so far.
+    ///
+    /// The default ignores `control`, which suits bots that decide quickly.
+    fn choose_action_controlled(
+        &self,
+        board: &GameY,
+        seed: Option<u64>,
+        _control: &SearchControl,
+    ) -> Option<BotDecision> {
+        match seed {
+            Some(seed) => self.choose_action_seeded(board, seed),
+            None => self.choose_action(board),
+        }
+    }
+
+    /// Placement-only version of [`YBot::choose_action_controlled`].
+    fn choose_move_controlled(
+        &self,
+        board: &GameY,
+        seed: Option<u64>,
+        _control: &SearchControl,
+    ) -> Option<Coordinates> {
+        match seed {
+            Some(seed) => self.choose_move_seeded(board, seed),
+            None => self.choose_move(board),
+        }
+    }
+
+    /// Analyses the position and returns per-move statistics.
+    ///
+    /// Bots that do not gather statistics return `None` (the default).
+    fn analyze(&self, _board: &GameY) -> Option<BotAnalysis> {
+        None
+    }
+
+    /// Analyses the position under `control`: searching bots stop at its
+    /// deadline or when it is cancelled and report the statistics gathered
+    /// so far.
+    ///
+    /// The default ignores `control` and calls [`YBot::analyze`].
+    fn analyze_controlled(&self, board: &GameY, _control: &SearchControl) -> Option<BotAnalysis> {
+        self.analyze(board)
+    }
+
+    /// Describes the bot for user interfaces.
+    ///
+    /// The default only knows the name; bots override it to report their
+    /// difficulty and capabilities.
+    fn metadata(&self) -> BotMetadata {
+        BotMetadata::new(self.name())
+    }
+
+    /// Creates a stateful instance of this bot bound to a single game.
+    ///
+    /// Bots that can reuse work between consecutive moves (such as a search
+    /// tree) return a [`BotSession`]; the rest return `None` (the default)
+    /// and are called through [`YBot::choose_action`] every time.
+    fn new_session(&self) -> Option<Box<dyn BotSession>> {
+        None
+    }
+}
+
+/// A stateful bot instance that plays a single game.
+///
+/// A session may keep search state between calls, so it must only be given
+/// positions of the game it was created for, in the order they occur.
+/// Positions that do not follow from the previous one are still handled, but
+/// any kept state is discarded.
+pub trait BotSession: Send {
+    /// Chooses the next action for the given position of the game.
+    fn choose_action(&mut self, board: &GameY) -> Option<BotDecision>;
+
+    /// Chooses a placement move for the given position of the game.
+    fn choose_move(&mut self, board: &GameY) -> Option<Coordinates> {
+        match self.choose_action(board) {
+            Some(BotDecision::Move(coords)) => Some(coords),
+            _ => None,
+        }
+    }
+
+    /// Chooses a placement move under `control` (see
+    /// [`YBot::choose_action_controlled`]). The default ignores `control`.
+    fn choose_move_controlled(&mut self, board: &GameY, _control: &SearchControl) -> Option<Coordinates> {
+        self.choose_move(board)
+    }
+
+    /// Analyses the position, reusing and extending the kept search state.
+    fn analyze(&mut self, _board: &GameY) -> Option<BotAnalysis> {
+        None
+    }
+
+    /// Thinks ahead while the opponent is to move in `board`, so that the
+    /// next call to [`BotSession::choose_action`] starts with more work done.
+    ///
+    /// Implementations must return promptly once `stop` is set and must not
+    /// run for longer than `max_time`. The default does nothing.
+    fn ponder(&mut self, _board: &GameY, _stop: &AtomicBool, _max_time: Duration) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn move_analysis(cell_id: u32, visits: u32) -> MoveAnalysis {
+        MoveAnalysis {
+            cell_id,
+            coords: Coordinates::from_index(cell_id, 3),
+            visits,
+            win_rate: 0.5,
+        }
+    }
+
+    #[test]
+    fn test_heatmap_is_normalized_by_most_visited() {
+        let analysis = BotAnalysis {
+            total_visits: 30,
+            evaluation: 0.5,
+            moves: vec![move_analysis(1, 20), move_analysis(4, 10)],
+            principal_variation: vec![],
+        };
+
+        let heatmap = analysis.heatmap(6);
+        assert_eq!(heatmap, vec![0.0, 1.0, 0.0, 0.0, 0.5, 0.0]);
+    }
+
+    #[test]
+    fn test_heatmap_without_visits_is_all_zero() {
+        let analysis = BotAnalysis {
+            total_visits: 0,
+            evaluation: 0.5,
+            moves: vec![move_analysis(1, 0)],
+            principal_variation: vec![],
+        };
+
+        assert_eq!(analysis.heatmap(3), vec![0.0; 3]);
+    }
+
+    #[test]
+    fn test_default_analyze_returns_none() {
+        struct PlainBot;
+        impl YBot for PlainBot {
+            fn name(&self) -> &str {
+                "plain"
+            }
+            fn choose_action(&self, _board: &GameY) -> Option<BotDecision> {
+                None
+            }
+        }
+
+        assert!(PlainBot.analyze(&GameY::new(3)).is_none());
+        assert!(PlainBot.new_session().is_none());
+
+        let metadata = PlainBot.metadata();
+        assert_eq!(metadata.display_name, "plain");
+        assert_eq!(metadata.difficulty, None);
+        assert_eq!(metadata.rule_sets, vec![RuleSet::Standard]);
+        assert!(!metadata.supports_analysis);
+    }
+
+    #[test]
+    fn test_search_control_stops_on_cancel_or_deadline() {
+        let control = SearchControl::new();
+        assert!(!control.should_stop());
+        control.cancel();
+        assert!(control.should_stop());
+
+        let expired = SearchControl::with_deadline(Instant::now());
+        assert!(expired.should_stop());
+        assert!(!expired.is_cancelled());
+
+        let later = Instant::now() + Duration::from_secs(60);
+        let control = SearchControl::with_deadline(later);
+        assert!(control.deadline_within(Some(Duration::from_millis(10))).unwrap() < later);
+        assert_eq!(control.deadline_within(None), Some(later));
+        assert_eq!(SearchControl::new().deadline_within(None), None);
+    }
+
+    #[test]
+    fn test_search_control_reports_the_best_proposal() {
+        let control = SearchControl::new();
+        assert_eq!(control.progress(), SearchProgress::default());
+
+        let (a, b) = (Coordinates::new(2, 1, 1), Coordinates::new(1, 2, 1));
+        control.add_iterations(10);
+        control.propose_best_move(0, a, 0.6);
+        control.propose_best_move(1, b, 0.4);
+        assert_eq!(control.progress(), SearchProgress { iterations: 10, best_move: Some(a) });
+
+        control.propose_best_move(0, a, 0.3);
+        control.add_iterations(5);
+        assert_eq!(control.progress(), SearchProgress { iterations: 15, best_move: Some(b) });
+    }
+
+    #[test]
+    fn test_metadata_builders() {
+        let metadata = BotMetadata::new("Solver")
+            .with_difficulty(9)
+            .with_max_board_size(10)
+            .with_think_time(Some(Duration::from_millis(1500)));
+
+        assert_eq!(metadata.difficulty, Some(MAX_DIFFICULTY));
+        assert_eq!(metadata.think_time_ms, Some(1500));
+        assert!(metadata.supports_board_size(10));
+        assert!(!metadata.supports_board_size(11));
+        assert!(!metadata.supports_board_size(0));
+    }
 }
@@ -1,16 +1,21 @@
 //! Registry for managing YBot implementations.
 //!
 //! The [`YBotRegistry`] provides a centralized way to register and retrieve
-//! bot implementations by name.
+//! bot implementations by name. [`SharedRegistry`] lets servers swap the
+//! whole registry at runtime, for example after reloading its configuration.
 
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
 
-use crate::YBot;
+use crate::{BookBot, OpeningBook, YBot};
 
 /// A registry that stores and manages [`YBot`] implementations.
 ///
 /// The registry allows bots to be registered and retrieved by their name,
-/// making it easy to dynamically select bots at runtime.
+/// making it easy to dynamically select bots at runtime. Bots are listed in
+/// the order they were first registered.
 ///
 /// # Example
 ///
@@ -24,8 +29,11 @@ use crate::YBot;
 /// let bot = registry.find("random_bot");
 /// assert!(bot.is_some());
 /// ```
+#[derive(Clone)]
 pub struct YBotRegistry {
     bots: HashMap<String, Arc<dyn YBot>>,
+    /// Bot names in registration order.
+    order: Vec<String>,
 }
 
 impl YBotRegistry {
@@ -33,14 +41,19 @@ impl YBotRegistry {
     pub fn new() -> Self {
         YBotRegistry {
             bots: HashMap::new(),
+            order: Vec::new(),
         }
     }
 
     /// Adds a bot to the registry and returns the registry for chaining.
     ///
     /// The bot is registered under its name (as returned by [`YBot::name`]).
+    /// A bot with the same name as an earlier one replaces it in its place.
     pub fn with_bot(mut self, bot: Arc<dyn YBot>) -> Self {
-        self.bots.insert(bot.name().to_string(), bot);
+        let name = bot.name().to_string();
+        if self.bots.insert(name.clone(), bot).is_none() {
+            self.order.push(name);
+        }
         self
     }
 
@@ -51,9 +64,30 @@ impl YBotRegistry {
         self.bots.get(name).cloned()
     }
 
-    /// Returns a list of all registered bot names.
+    /// Wraps every registered bot in a [`BookBot`], so that positions found
+    /// in `book` are answered from it before the bot starts searching.
+    ///
+    /// Bots keep their names; bots added afterwards are not wrapped.
+    pub fn with_opening_book(mut self, book: Arc<OpeningBook>) -> Self {
+        self.bots = self
+            .bots
+            .into_iter()
+            .map(|(name, bot)| {
+                let wrapped: Arc<dyn YBot> = Arc::new(BookBot::new(book.clone(), bot));
+                (name, wrapped)
+            })
+            .collect();
+        self
+    }
+
+    /// Returns a list of all registered bot names, in registration order.
     pub fn names(&self) -> Vec<String> {
-        self.bots.keys().cloned().collect()
+        self.order.clone()
+    }
+
+    /// Iterates over the registered bots in registration order.
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<dyn YBot>> {
+        self.order.iter().filter_map(|name| self.bots.get(name))
     }
 }
 
@@ -63,6 +97,47 @@ impl Default for YBotRegistry {
     }
 }
 
+/// A [`YBotRegistry`] shared between threads that can be replaced as a whole.
+///
+/// Lookups see either the old or the new registry, never a mix; bots already
+/// handed out keep working after a replacement.
+#[derive(Clone, Default)]
+pub struct SharedRegistry {
+    inner: Arc<RwLock<Arc<YBotRegistry>>>,
+}
+
+impl SharedRegistry {
+    pub fn new(registry: YBotRegistry) -> Self {
+        Self { inner: Arc::new(RwLock::new(Arc::new(registry))) }
+    }
+
+    /// The current registry.
+    pub fn snapshot(&self) -> Arc<YBotRegistry> {
+        self.inner.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replaces the registry for every clone of this handle.
+    pub fn replace(&self, registry: YBotRegistry) {
+        *self.inner.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(registry);
+    }
+
+    /// Finds a bot by name in the current registry.
+    pub fn find(&self, name: &str) -> Option<Arc<dyn YBot>> {
+        self.snapshot().find(name)
+    }
+
+    /// Names of the bots in the current registry.
+    pub fn names(&self) -> Vec<String> {
+        self.snapshot().names()
+    }
+}
+
+impl From<YBotRegistry> for SharedRegistry {
+    fn from(registry: YBotRegistry) -> Self {
+        Self::new(registry)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -135,6 +210,32 @@ mod tests {
         assert!(registry.find("random_bot").is_some());
     }
 
+    #[test]
+    fn test_with_opening_book_wraps_registered_bots() {
+        let mut book = OpeningBook::new();
+        book.insert(&GameY::new(5), crate::Coordinates::new(2, 1, 1), 1);
+
+        let registry = YBotRegistry::new()
+            .with_bot(Arc::new(MockBot::new("mock")))
+            .with_opening_book(Arc::new(book));
+
+        let bot = registry.find("mock").unwrap();
+        assert_eq!(bot.name(), "mock");
+        assert_eq!(bot.choose_move(&GameY::new(5)), Some(crate::Coordinates::new(2, 1, 1)));
+        assert_eq!(bot.choose_move(&GameY::new(4)), None);
+    }
+
+    #[test]
+    fn test_names_keep_registration_order() {
+        let registry = ["zeta", "alpha", "mid", "alpha"]
+            .into_iter()
+            .fold(YBotRegistry::new(), |registry, name| registry.with_bot(Arc::new(MockBot::new(name))));
+
+        assert_eq!(registry.names(), vec!["zeta", "alpha", "mid"]);
+        let listed: Vec<&str> = registry.iter().map(|bot| bot.name()).collect();
+        assert_eq!(listed, vec!["zeta", "alpha", "mid"]);
+    }
+
     #[test]
     fn test_duplicate_name_overwrites() {
         let bot1 = Arc::new(MockBot::new("same_name"));
@@ -144,4 +245,17 @@ mod tests {
 
         assert_eq!(registry.names().len(), 1);
     }
+
+    #[test]
+    fn test_shared_registry_replace_is_seen_by_clones() {
+        let shared = SharedRegistry::new(YBotRegistry::new().with_bot(Arc::new(MockBot::new("old"))));
+        let clone = shared.clone();
+        let held = shared.find("old").unwrap();
+
+        clone.replace(YBotRegistry::new().with_bot(Arc::new(MockBot::new("new"))));
+
+        assert!(shared.find("old").is_none());
+        assert!(shared.find("new").is_some());
+        assert_eq!(held.name(), "old");
+    }
 }
@@ -2,7 +2,7 @@ use crate::{check_api_version, Coordinates, GameY, YEN};
 use super::{error::ErrorResponse, state::AppState};
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
 };
 use serde::{Deserialize, Serialize};
 
@@ -15,6 +15,14 @@ pub struct ChooseParams {
     bot_id: String,
 }
 
+/// Optional query parameters of the choose endpoint.
+#[derive(Deserialize, Default)]
+pub struct ChooseQuery {
+    /// Seed for the bot's randomness: the same position and seed always
+    /// produce the same move.
+    seed: Option<u64>,
+}
+
 /// Response returned by the choose endpoint on success.
 ///
 /// Contains the bot's chosen move coordinates along with context
@@ -35,7 +43,7 @@ pub struct MoveResponse {
 /// coordinates of the bot's chosen move.
 ///
 /// # Route
-/// `POST /{api_version}/ybot/choose/{bot_id}`
+/// `POST /{api_version}/ybot/choose/{bot_id}[?seed=<u64>]`
 ///
 /// # Request Body
 /// A JSON object in YEN format representing the current game state.
@@ -47,6 +55,7 @@ pub struct MoveResponse {
 pub async fn choose(
     State(state): State<AppState>,
     Path(params): Path<ChooseParams>,
+    Query(query): Query<ChooseQuery>,
     Json(yen): Json<YEN>,
 ) -> Result<Json<MoveResponse>, Json<ErrorResponse>> {
     check_api_version(&params.api_version)?;
@@ -74,7 +83,11 @@ pub async fn choose(
             )));
         }
     };
-    let coords = match bot.choose_move(&game_y) {
+    let chosen = match query.seed {
+        Some(seed) => bot.choose_move_seeded(&game_y, seed),
+        None => bot.choose_move(&game_y),
+    };
+    let coords = match chosen {
         Some(coords) => coords,
         None => {
             // Handle the case where the bot has no valid moves
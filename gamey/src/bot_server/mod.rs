@@ -24,12 +24,12 @@ pub mod error;
 pub mod state;
 pub mod version;
 use axum::response::IntoResponse;
-use std::sync::Arc;
+use std::path::Path;
 pub use choose::MoveResponse;
 pub use error::ErrorResponse;
 pub use version::*;
 
-use crate::{GameYError, RandomBot, YBotRegistry};
+use crate::{BotRegistryConfig, GameYError};
 use self::state::AppState;
 
 /// Creates the Axum router with the given state.
@@ -47,12 +47,23 @@ pub fn create_router(state: AppState) -> axum::Router {
 
 /// Creates the default application state with the standard bot registry.
 ///
-/// The default state includes the `RandomBot` which selects moves randomly.
+/// The bots are the ones declared in [`DEFAULT_BOT_CONFIG`](crate::DEFAULT_BOT_CONFIG), the same the
+/// game server registers by default.
 pub fn create_default_state() -> AppState {
-    let bots = YBotRegistry::new().with_bot(Arc::new(RandomBot));
+    let bots = BotRegistryConfig::default_config()
+        .build()
+        .expect("config/bots.json is valid");
     AppState::new(bots)
 }
 
+/// Creates an application state with the bots declared in a configuration file.
+///
+/// # Errors
+/// Returns an error if the file cannot be read or declares an invalid bot.
+pub fn create_state_from_config<P: AsRef<Path>>(path: P) -> Result<AppState, GameYError> {
+    Ok(AppState::new(BotRegistryConfig::load(path)?.build()?))
+}
+
 /// Starts the bot server on the specified port.
 ///
 /// This function blocks until the server is shut down.
@@ -1,4 +1,4 @@
-use crate::YBotRegistry;
+use crate::{SharedRegistry, YBotRegistry};
 use std::sync::Arc;
 
 /// Shared application state for the bot server.
@@ -8,21 +8,26 @@ use std::sync::Arc;
 /// for concurrent request handling.
 #[derive(Clone)]
 pub struct AppState {
-    /// The registry of available bots, wrapped in Arc for thread-safe sharing.
-    bots: Arc<YBotRegistry>,
+    /// The registry of available bots, replaceable at runtime.
+    bots: SharedRegistry,
 }
 
 impl AppState {
     /// Creates a new application state with the given bot registry.
     pub fn new(bots: YBotRegistry) -> Self {
         Self {
-            bots: Arc::new(bots),
+            bots: SharedRegistry::new(bots),
         }
     }
 
-    /// Returns a clone of the Arc-wrapped bot registry.
+    /// Returns the current bot registry.
     pub fn bots(&self) -> Arc<YBotRegistry> {
-        Arc::clone(&self.bots)
+        self.bots.snapshot()
+    }
+
+    /// Replaces the bot registry for every clone of this state.
+    pub fn replace_bots(&self, bots: YBotRegistry) {
+        self.bots.replace(bots);
     }
 }
 
@@ -63,4 +68,12 @@ mod tests {
         // Both Arcs should point to the same registry
         assert_eq!(bots1.names(), bots2.names());
     }
+
+    #[test]
+    fn test_replace_bots_is_seen_by_clones() {
+        let state = AppState::new(YBotRegistry::new());
+        let cloned = state.clone();
+        state.replace_bots(YBotRegistry::new().with_bot(Arc::new(RandomBot)));
+        assert!(cloned.bots().find("random_bot").is_some());
+    }
 }
@@ -3,7 +3,8 @@
 //! This module provides the CLI application for playing Y games interactively.
 
 use crate::{
-    Coordinates, GameAction, MctsBot, MctsCompletoBot, RandomBot, Movement, RenderOptions, YBot, YBotRegistry, game
+    Coordinates, GameAction, MctsBot, MctsCompletoBot, OpeningBook, RandomBot, Movement, RenderOptions,
+    SolveOutcome, Solver, YBot, YBotRegistry, game
 };
 use crate::{GameStatus, GameY, PlayerId};
 use anyhow::Result;
@@ -22,11 +23,12 @@ pub struct CliArgs {
     #[arg(short, long, default_value_t = 7)]
     pub size: u32,
 
-    /// Game mode: human (2-player), computer (vs bot), or server (HTTP API).
+    /// Game mode: human (2-player), computer (vs bot), server (HTTP API) or
+    /// engine (text protocol on stdin/stdout for --bot).
     #[arg(short, long, default_value_t = Mode::Human)]
     pub mode: Mode,
 
-    /// The bot to use (only used with --mode=computer).
+    /// The bot to use (only used with --mode=computer and --mode=engine).
     #[arg(short, long, default_value = "random_bot")]
     pub bot: String,
 
@@ -37,6 +39,50 @@ pub struct CliArgs {
     /// Port to run the server on (only used with --mode=server).
     #[arg(short, long, default_value_t = 3000)]
     pub port: u16,
+
+    /// Opening book (JSON file) that every bot consults before searching.
+    #[arg(long)]
+    pub book: Option<String>,
+
+    /// Bot registry configuration (JSON file) for --mode=server and
+    /// --mode=engine; defaults to the bundled config/bots.json.
+    #[arg(long)]
+    pub bots: Option<String>,
+
+    /// Token that enables the admin endpoints (only used with --mode=server).
+    #[arg(long)]
+    pub admin_token: Option<String>,
+
+    /// File where the server keeps its games so they survive restarts (only
+    /// used with --mode=server); games are kept in memory only if omitted.
+    #[arg(long)]
+    pub sessions: Option<String>,
+
+    /// Seconds a finished game is kept after its last move (only used with
+    /// --mode=server).
+    #[arg(long, value_name = "SECS")]
+    pub finished_ttl: Option<u64>,
+
+    /// Seconds an unfinished game is kept without moves (only used with
+    /// --mode=server).
+    #[arg(long, value_name = "SECS")]
+    pub idle_ttl: Option<u64>,
+
+    /// Maximum ongoing games per client, 0 for no limit (only used with
+    /// --mode=server).
+    #[arg(long)]
+    pub max_games: Option<usize>,
+
+    /// Seconds a bot move requested with `Prefer: respond-async` may search
+    /// (only used with --mode=server).
+    #[arg(long, value_name = "SECS")]
+    pub async_bot_deadline: Option<u64>,
+
+    /// Maximum bot sessions (search trees kept between moves) held at once;
+    /// the least recently used one is dropped first (only used with
+    /// --mode=server).
+    #[arg(long)]
+    pub max_bot_sessions: Option<usize>,
 }
 
 /// The game mode determining how the game is played.
@@ -45,6 +91,7 @@ pub enum Mode {
     Computer,
     Human,
     Server,
+    Engine,
 }
 
 impl Display for Mode {
@@ -53,6 +100,7 @@ impl Display for Mode {
             Mode::Computer => "computer",
             Mode::Human => "human",
             Mode::Server => "server",
+            Mode::Engine => "engine",
         };
         write!(f, "{}", s)
     }
@@ -65,10 +113,13 @@ pub fn run_cli_game() -> Result<()> {
     let mut rl = DefaultEditor::new()?;
     
     // Registro de bots disponibles
-    let bots_registry = YBotRegistry::new()
+    let mut bots_registry = YBotRegistry::new()
         .with_bot(Arc::new(RandomBot))
         .with_bot(Arc::new(MctsBot::new("mcts_test", 15000))) // Nivel de dificultad alto
         .with_bot(Arc::new(MctsCompletoBot::new("mcts_completo", 15000)));
+    if let Some(path) = &args.book {
+        bots_registry = bots_registry.with_opening_book(Arc::new(OpeningBook::load(path)?));
+    }
 
     let bot: Arc<dyn YBot> = match bots_registry.find(&args.bot) {
         Some(b) => b,
@@ -162,6 +213,12 @@ fn process_input(
         Command::Show3DCoords => render_options.show_3d_coords = !render_options.show_3d_coords,
         Command::ShowIdx => render_options.show_idx = !render_options.show_idx,
         Command::ShowColors => render_options.show_colors = !render_options.show_colors,
+        Command::Analyze => print_analysis(game, bot),
+        Command::Solve { filename: None } => print_solution(game),
+        Command::Solve { filename: Some(filename) } => {
+            let path = std::path::Path::new(&filename);
+            print_solution(&GameY::load_from_file(path)?);
+        }
         Command::Help => print_help(),
         Command::Exit => {
             println!("Exiting the game.");
@@ -189,6 +246,8 @@ pub fn parse_command(input: &str, bound: u32) -> Command {
         "load" => parse_file_command(&parts, |f| Command::Load { filename: f }),
         "resign" => Command::Resign,
         "help" => Command::Help,
+        "analyze" => Command::Analyze,
+        "solve" => Command::Solve { filename: parts.get(1).map(|f| f.to_string()) },
         "exit" => Command::Exit,
         "show_colors" => Command::ShowColors,
         "show_coords" => Command::Show3DCoords,
@@ -214,7 +273,55 @@ where
 }
 
 fn print_help() {
-    println!("Commands: <number> (place), resign, show_coords, show_idx, show_colors, save/load <file>, exit, help");
+    println!("Commands: <number> (place), resign, analyze, solve [file], show_coords, show_idx, show_colors, save/load <file>, exit, help");
+}
+
+/// Number of candidate moves printed by the `analyze` command.
+const ANALYSIS_TOP_MOVES: usize = 5;
+
+fn print_analysis(game: &GameY, bot: &dyn YBot) {
+    let Some(analysis) = bot.analyze(game) else {
+        println!("Bot {} does not support analysis.", bot.name());
+        return;
+    };
+
+    println!(
+        "Evaluation: {:.1}% for the side to move ({} visits)",
+        analysis.evaluation * 100.0,
+        analysis.total_visits
+    );
+    for mv in analysis.moves.iter().take(ANALYSIS_TOP_MOVES) {
+        println!(
+            "  {:>4} {}  visits: {:>7}  win rate: {:.1}%",
+            mv.cell_id,
+            mv.coords,
+            mv.visits,
+            mv.win_rate * 100.0
+        );
+    }
+    let pv: Vec<String> = analysis
+        .principal_variation
+        .iter()
+        .map(|coords| coords.to_index(game.board_size()).to_string())
+        .collect();
+    println!("Principal variation: {}", pv.join(" "));
+}
+
+/// Solves the given position (the current game, or a YEN file for `solve <file>`)
+/// with the default node budget.
+fn print_solution(game: &GameY) {
+    match Solver::default().solve(game) {
+        Some(solution) => match solution.outcome {
+            SolveOutcome::Win { best_move } => println!(
+                "Proven win for the side to move: play {} {} ({} nodes)",
+                best_move.to_index(game.board_size()),
+                best_move,
+                solution.nodes
+            ),
+            SolveOutcome::Loss => println!("Proven loss for the side to move ({} nodes)", solution.nodes),
+        },
+        None => println!("Could not solve the position (game over, board too large or node budget exhausted)."),
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -228,6 +335,8 @@ pub enum Command {
     Show3DCoords,
     ShowColors,
     ShowIdx,
+    Analyze,
+    Solve { filename: Option<String> },
     Exit,
     Help,
 }
@@ -586,6 +695,48 @@ mod tests {
         assert_eq!(args.mode, Mode::Server);
     }
 
+    #[test]
+    fn test_cli_args_mode_engine() {
+        let args = CliArgs::try_parse_from(["gamey", "--mode", "engine", "--bot", "greedy_bot"]).unwrap();
+        assert_eq!(args.mode, Mode::Engine);
+        assert_eq!(format!("{}", args.mode), "engine");
+    }
+
+    #[test]
+    fn test_cli_args_bots_config_and_admin_token() {
+        let args = CliArgs::try_parse_from([
+            "gamey", "--mode", "server", "--bots", "bots.json", "--admin-token", "secret",
+        ])
+        .unwrap();
+        assert_eq!(args.bots.as_deref(), Some("bots.json"));
+        assert_eq!(args.admin_token.as_deref(), Some("secret"));
+        assert_eq!(args.sessions, None);
+    }
+
+    #[test]
+    fn test_cli_args_sessions_file() {
+        let args =
+            CliArgs::try_parse_from(["gamey", "--mode", "server", "--sessions", "data/sessions.jsonl"]).unwrap();
+        assert_eq!(args.sessions.as_deref(), Some("data/sessions.jsonl"));
+    }
+
+    #[test]
+    fn test_cli_args_session_expiry() {
+        let args = CliArgs::try_parse_from([
+            "gamey", "--mode", "server", "--finished-ttl", "600", "--idle-ttl", "3600", "--max-games", "0",
+        ])
+        .unwrap();
+        assert_eq!(args.finished_ttl, Some(600));
+        assert_eq!(args.idle_ttl, Some(3600));
+        assert_eq!(args.max_games, Some(0));
+    }
+
+    #[test]
+    fn test_cli_args_max_bot_sessions() {
+        let args = CliArgs::try_parse_from(["gamey", "--mode", "server", "--max-bot-sessions", "8"]).unwrap();
+        assert_eq!(args.max_bot_sessions, Some(8));
+    }
+
     #[test]
     fn test_cli_args_mode_short() {
         let args = CliArgs::try_parse_from(["gamey", "-m", "computer"]).unwrap();
@@ -693,6 +844,9 @@ mod tests {
         // Comandos simples
         assert_eq!(parse_command("resign", b), Command::Resign);
         assert_eq!(parse_command("help", b), Command::Help);
+        assert_eq!(parse_command("analyze", b), Command::Analyze);
+        assert_eq!(parse_command("solve", b), Command::Solve { filename: None });
+        assert_eq!(parse_command("solve pos.json", b), Command::Solve { filename: Some("pos.json".into()) });
         assert_eq!(parse_command("exit", b), Command::Exit);
         assert_eq!(parse_command("   ", b), Command::None);
 
@@ -732,10 +886,29 @@ mod tests {
 
         // Ramas de error y ayuda
         let _ = process_input("help", &mut game, &player, &mut opts, Mode::Human, &bot);
+        let _ = process_input("analyze", &mut game, &player, &mut opts, Mode::Human, &bot); // RandomBot: sin análisis
         let _ = process_input("", &mut game, &player, &mut opts, Mode::Human, &bot); // None branch
         let _ = process_input("invalid_cmd_test", &mut game, &player, &mut opts, Mode::Human, &bot); // Error branch
     }
 
+    #[test]
+    fn test_process_input_solve() {
+        let mut game = GameY::new(7);
+        let player = PlayerId::new(0);
+        let mut opts = RenderOptions::default();
+        let bot = RandomBot;
+
+        // Posición pequeña guardada en YEN: se resuelve sin tocar la partida actual.
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("solve.json");
+        GameY::new(3).save_to_file(&path).unwrap();
+        let command = format!("solve {}", path.display());
+        assert!(process_input(&command, &mut game, &player, &mut opts, Mode::Human, &bot).is_ok());
+        assert_eq!(game.board_size(), 7);
+
+        assert!(process_input("solve missing.json", &mut game, &player, &mut opts, Mode::Human, &bot).is_err());
+    }
+
     #[test]
     fn test_apply_move_failure_branch() {
         let mut game = GameY::new(7);
@@ -0,0 +1,468 @@
+//! Virtual connection analysis.
+//!
+//! The engine itself only knows direct adjacency. This module looks at the
+//! patterns players actually reason with:
+//!
+//! - a [`Bridge`]: two non-adjacent stones of the same player that share two
+//!   empty neighbors (the *carrier*); if the opponent takes one carrier cell,
+//!   the other one still connects them
+//! - an [`EdgeTemplate`]: a stone one row away from a side whose two
+//!   neighbors on that side are empty, so it reaches the side whatever the
+//!   opponent does
+//! - a [`Threat`]: a bridge or edge template with one carrier cell taken by
+//!   the opponent and the other still empty; the owner must answer there
+//!
+//! [`GameY::threat_responses`] finds only the threats created by one stone,
+//! cheaply enough to be used inside playouts.
+//!
+//! Stones joined by adjacency or intact bridges form a [`VirtualGroup`], which
+//! also reaches the sides its stones touch or hold an edge template to. A
+//! group that reaches all three sides is virtually won. Carriers of different
+//! patterns may overlap, so that verdict is optimistic: a single intrusion
+//! into a shared carrier threatens two connections at once.
+
+use serde::Serialize;
+
+use crate::{Cell, Coordinates, GameY, PlayerId};
+
+/// A side of the triangular board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    /// Cells with `x == 0`.
+    A,
+    /// Cells with `y == 0`.
+    B,
+    /// Cells with `z == 0`.
+    C,
+}
+
+impl Side {
+    /// All sides, in order.
+    pub const ALL: [Side; 3] = [Side::A, Side::B, Side::C];
+
+    /// Distance from `coords` to this side, in rows.
+    pub fn distance(self, coords: &Coordinates) -> u32 {
+        match self {
+            Side::A => coords.x(),
+            Side::B => coords.y(),
+            Side::C => coords.z(),
+        }
+    }
+}
+
+/// Two stones of `player` connected through two empty carrier cells.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Bridge {
+    pub player: PlayerId,
+    pub stones: [Coordinates; 2],
+    pub carrier: [Coordinates; 2],
+}
+
+/// A stone of `player` one row away from `side`, with both cells of that
+/// side next to it empty.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EdgeTemplate {
+    pub player: PlayerId,
+    pub stone: Coordinates,
+    pub side: Side,
+    pub carrier: [Coordinates; 2],
+}
+
+/// The pattern a [`Threat`] attacks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum ThreatKind {
+    /// A bridge between two stones.
+    Bridge { stones: [Coordinates; 2] },
+    /// An edge template of a stone.
+    Edge { stone: Coordinates, side: Side },
+}
+
+/// A connection of `player` that the opponent has cut into: `player` keeps it
+/// by playing at `response`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Threat {
+    pub player: PlayerId,
+    #[serde(flatten)]
+    pub kind: ThreatKind,
+    /// The opponent stone inside the carrier.
+    pub intrusion: Coordinates,
+    /// The carrier cell that is still empty.
+    pub response: Coordinates,
+}
+
+/// Stones of `player` joined by adjacency or intact bridges.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VirtualGroup {
+    pub player: PlayerId,
+    /// Stones of the group, sorted by cell index.
+    pub stones: Vec<Coordinates>,
+    /// Sides the group touches or reaches through an edge template.
+    pub sides: Vec<Side>,
+}
+
+impl VirtualGroup {
+    /// Returns true if the group reaches the three sides.
+    pub fn connects_all_sides(&self) -> bool {
+        self.sides.len() == Side::ALL.len()
+    }
+}
+
+/// Every bridge, edge template, threat and virtual group on the board, for
+/// both players.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ConnectionAnalysis {
+    pub bridges: Vec<Bridge>,
+    pub edge_templates: Vec<EdgeTemplate>,
+    pub threats: Vec<Threat>,
+    pub groups: Vec<VirtualGroup>,
+}
+
+impl ConnectionAnalysis {
+    /// Returns the first virtual group of `player` that reaches the three
+    /// sides, if any.
+    pub fn virtual_win(&self, player: PlayerId) -> Option<&VirtualGroup> {
+        self.groups
+            .iter()
+            .find(|group| group.player == player && group.connects_all_sides())
+    }
+
+    /// Cells where `player` must answer to keep a threatened connection,
+    /// without duplicates and sorted by cell coordinates.
+    pub fn urgent_replies(&self, player: PlayerId) -> Vec<Coordinates> {
+        let mut replies: Vec<Coordinates> = self
+            .threats
+            .iter()
+            .filter(|threat| threat.player == player)
+            .map(|threat| threat.response)
+            .collect();
+        replies.sort_by_key(|c| (c.x(), c.y(), c.z()));
+        replies.dedup();
+        replies
+    }
+}
+
+impl GameY {
+    /// Finds the bridges, edge templates, threats and virtual groups of both
+    /// players in the current position.
+    pub fn connections(&self) -> ConnectionAnalysis {
+        let size = self.board_size();
+        let stones: Vec<(Coordinates, PlayerId)> = (0..self.total_cells())
+            .map(|idx| Coordinates::from_index(idx, size))
+            .filter_map(|coords| match self.cell(&coords) {
+                Cell::Occupied(player) => Some((coords, player)),
+                Cell::Empty => None,
+            })
+            .collect();
+
+        let mut analysis = ConnectionAnalysis::default();
+        // Stone pairs (by position in `stones`) joined directly or by a bridge.
+        let mut links: Vec<(usize, usize)> = Vec::new();
+
+        for (i, &(a, player)) in stones.iter().enumerate() {
+            let a_neighbors = self.get_neighbors(&a);
+
+            for (j, &(b, other)) in stones.iter().enumerate().skip(i + 1) {
+                if other != player {
+                    continue;
+                }
+                if a_neighbors.contains(&b) {
+                    links.push((i, j));
+                    continue;
+                }
+                let Some(carrier) = bridge_carrier(&a_neighbors, &self.get_neighbors(&b)) else { continue };
+                match self.carrier_state(carrier, player) {
+                    CarrierState::Intact => {
+                        links.push((i, j));
+                        analysis.bridges.push(Bridge { player, stones: [a, b], carrier });
+                    }
+                    CarrierState::Attacked { intrusion, response } => analysis.threats.push(Threat {
+                        player,
+                        kind: ThreatKind::Bridge { stones: [a, b] },
+                        intrusion,
+                        response,
+                    }),
+                    CarrierState::Broken => {}
+                }
+            }
+
+            for side in Side::ALL {
+                let Some(carrier) = edge_carrier(&a, &a_neighbors, side) else { continue };
+                match self.carrier_state(carrier, player) {
+                    CarrierState::Intact => {
+                        analysis.edge_templates.push(EdgeTemplate { player, stone: a, side, carrier });
+                    }
+                    CarrierState::Attacked { intrusion, response } => analysis.threats.push(Threat {
+                        player,
+                        kind: ThreatKind::Edge { stone: a, side },
+                        intrusion,
+                        response,
+                    }),
+                    CarrierState::Broken => {}
+                }
+            }
+        }
+
+        analysis.groups = virtual_groups(&stones, &links, &analysis.edge_templates);
+        analysis
+    }
+
+    /// Cells where `player` must answer to keep the bridges and edge
+    /// templates that an opponent stone at `intrusion` cuts into, without
+    /// duplicates and sorted by cell coordinates.
+    ///
+    /// These are the responses of the [`ConnectionAnalysis::threats`] whose
+    /// intrusion is `intrusion`, but only the stones around it are examined.
+    pub fn threat_responses(&self, intrusion: Coordinates, player: PlayerId) -> Vec<Coordinates> {
+        let mine: Vec<(Coordinates, Vec<Coordinates>)> = self
+            .get_neighbors(&intrusion)
+            .into_iter()
+            .filter(|c| self.cell(c) == Cell::Occupied(player))
+            .map(|c| (c, self.get_neighbors(&c)))
+            .collect();
+
+        let mut carriers = Vec::new();
+        for (i, (a, a_neighbors)) in mine.iter().enumerate() {
+            for (b, b_neighbors) in &mine[i + 1..] {
+                if !a_neighbors.contains(b) {
+                    carriers.extend(bridge_carrier(a_neighbors, b_neighbors));
+                }
+            }
+            carriers.extend(Side::ALL.into_iter().filter_map(|side| edge_carrier(a, a_neighbors, side)));
+        }
+
+        let mut responses: Vec<Coordinates> = carriers
+            .into_iter()
+            .filter_map(|carrier| match self.carrier_state(carrier, player) {
+                CarrierState::Attacked { intrusion: cut, response } if cut == intrusion => Some(response),
+                _ => None,
+            })
+            .collect();
+        responses.sort_by_key(|c| (c.x(), c.y(), c.z()));
+        responses.dedup();
+        responses
+    }
+
+    /// Whether the carrier of a connection of `player` is still whole.
+    fn carrier_state(&self, [first, second]: [Coordinates; 2], player: PlayerId) -> CarrierState {
+        match (self.cell(&first), self.cell(&second)) {
+            (Cell::Empty, Cell::Empty) => CarrierState::Intact,
+            (Cell::Occupied(intruder), Cell::Empty) if intruder != player => {
+                CarrierState::Attacked { intrusion: first, response: second }
+            }
+            (Cell::Empty, Cell::Occupied(intruder)) if intruder != player => {
+                CarrierState::Attacked { intrusion: second, response: first }
+            }
+            _ => CarrierState::Broken,
+        }
+    }
+}
+
+/// State of the two carrier cells of a bridge or edge template.
+enum CarrierState {
+    /// Both cells are empty.
+    Intact,
+    /// The opponent holds `intrusion` and `response` is still empty.
+    Attacked { intrusion: Coordinates, response: Coordinates },
+    /// Any other case: filled by the owner or cut in both cells.
+    Broken,
+}
+
+/// The carrier of a bridge between two non-adjacent cells, given their
+/// neighbors: their common neighbors, if there are exactly two.
+fn bridge_carrier(a_neighbors: &[Coordinates], b_neighbors: &[Coordinates]) -> Option<[Coordinates; 2]> {
+    let common: Vec<Coordinates> = a_neighbors.iter().copied().filter(|c| b_neighbors.contains(c)).collect();
+    common.try_into().ok()
+}
+
+/// The carrier of an edge template of `stone` towards `side`: the two cells
+/// of `side` next to it, if `stone` is one row away from it.
+fn edge_carrier(stone: &Coordinates, neighbors: &[Coordinates], side: Side) -> Option<[Coordinates; 2]> {
+    if side.distance(stone) != 1 {
+        return None;
+    }
+    let edge: Vec<Coordinates> = neighbors.iter().copied().filter(|c| side.distance(c) == 0).collect();
+    edge.try_into().ok()
+}
+
+/// Joins `stones` along `links` (union-find) and collects the sides each
+/// group reaches.
+fn virtual_groups(
+    stones: &[(Coordinates, PlayerId)],
+    links: &[(usize, usize)],
+    edge_templates: &[EdgeTemplate],
+) -> Vec<VirtualGroup> {
+    let mut parent: Vec<usize> = (0..stones.len()).collect();
+    for &(a, b) in links {
+        let (root_a, root_b) = (find(&mut parent, a), find(&mut parent, b));
+        if root_a != root_b {
+            parent[root_a] = root_b;
+        }
+    }
+
+    let mut groups: Vec<(usize, VirtualGroup)> = Vec::new();
+    for (i, &(coords, player)) in stones.iter().enumerate() {
+        let root = find(&mut parent, i);
+        let position = match groups.iter().position(|(r, _)| *r == root) {
+            Some(position) => position,
+            None => {
+                groups.push((root, VirtualGroup { player, stones: Vec::new(), sides: Vec::new() }));
+                groups.len() - 1
+            }
+        };
+        let group = &mut groups[position].1;
+        group.stones.push(coords);
+
+        let templated = edge_templates.iter().filter(|t| t.stone == coords).map(|t| t.side);
+        let touched = Side::ALL.into_iter().filter(|side| side.distance(&coords) == 0);
+        group.sides.extend(touched.chain(templated));
+    }
+
+    groups
+        .into_iter()
+        .map(|(_, mut group)| {
+            group.sides.sort();
+            group.sides.dedup();
+            group
+        })
+        .collect()
+}
+
+fn find(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Movement;
+
+    fn place(game: &mut GameY, player: u32, x: u32, y: u32, z: u32) {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords: Coordinates::new(x, y, z),
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_empty_board_has_no_connections() {
+        assert_eq!(GameY::new(5).connections(), ConnectionAnalysis::default());
+    }
+
+    #[test]
+    fn test_finds_bridge_and_its_carrier() {
+        let mut game = GameY::new(6);
+        place(&mut game, 0, 3, 1, 1);
+        place(&mut game, 1, 5, 0, 0);
+        place(&mut game, 0, 1, 2, 2);
+
+        let analysis = game.connections();
+        assert_eq!(analysis.bridges.len(), 1);
+        let bridge = &analysis.bridges[0];
+        assert_eq!(bridge.player, PlayerId::new(0));
+        assert!(bridge.carrier.contains(&Coordinates::new(2, 2, 1)));
+        assert!(bridge.carrier.contains(&Coordinates::new(2, 1, 2)));
+
+        // Both ends of the bridge form a single virtual group.
+        let group = analysis.groups.iter().find(|g| g.player == PlayerId::new(0)).unwrap();
+        assert_eq!(group.stones.len(), 2);
+    }
+
+    #[test]
+    fn test_intrusion_turns_bridge_into_threat() {
+        let mut game = GameY::new(6);
+        place(&mut game, 0, 3, 1, 1);
+        place(&mut game, 1, 2, 2, 1);
+        place(&mut game, 0, 1, 2, 2);
+
+        let analysis = game.connections();
+        assert!(analysis.bridges.is_empty());
+        assert_eq!(
+            analysis.threats,
+            vec![Threat {
+                player: PlayerId::new(0),
+                kind: ThreatKind::Bridge { stones: [Coordinates::new(3, 1, 1), Coordinates::new(1, 2, 2)] },
+                intrusion: Coordinates::new(2, 2, 1),
+                response: Coordinates::new(2, 1, 2),
+            }]
+        );
+        assert_eq!(analysis.urgent_replies(PlayerId::new(0)), vec![Coordinates::new(2, 1, 2)]);
+        assert!(analysis.urgent_replies(PlayerId::new(1)).is_empty());
+    }
+
+    #[test]
+    fn test_threat_responses_match_the_full_analysis() {
+        let mut game = GameY::new(7);
+        let total = game.total_cells();
+        let mut answered = 0;
+        // Visit the cells in a scattered order (17 and 28 are coprime).
+        for step in 0..total {
+            let coords = Coordinates::from_index(step * 17 % total, 7);
+            let player = PlayerId::new(step % 2);
+            if game.add_move(Movement::Placement { player, coords }).is_err() {
+                break;
+            }
+            let opponent = PlayerId::new(1 - step % 2);
+            let mut expected: Vec<Coordinates> = game
+                .connections()
+                .threats
+                .into_iter()
+                .filter(|threat| threat.player == opponent && threat.intrusion == coords)
+                .map(|threat| threat.response)
+                .collect();
+            expected.sort_by_key(|c| (c.x(), c.y(), c.z()));
+            expected.dedup();
+            answered += usize::from(!expected.is_empty());
+            assert_eq!(game.threat_responses(coords, opponent), expected, "after {coords}");
+        }
+        assert!(answered > 0);
+    }
+
+    #[test]
+    fn test_edge_template_and_threat() {
+        let mut game = GameY::new(6);
+        place(&mut game, 0, 1, 2, 2);
+
+        let analysis = game.connections();
+        assert_eq!(analysis.edge_templates.len(), 1);
+        assert_eq!(analysis.edge_templates[0].side, Side::A);
+        assert_eq!(analysis.groups[0].sides, vec![Side::A]);
+
+        place(&mut game, 1, 0, 3, 2);
+        let analysis = game.connections();
+        assert!(analysis.edge_templates.is_empty());
+        assert_eq!(analysis.threats.len(), 1);
+        assert_eq!(analysis.threats[0].response, Coordinates::new(0, 2, 3));
+        assert_eq!(analysis.threats[0].kind, ThreatKind::Edge { stone: Coordinates::new(1, 2, 2), side: Side::A });
+    }
+
+    #[test]
+    fn test_virtual_win_through_bridges_and_templates() {
+        // On a size 4 board the center stone holds an edge template to every side.
+        let mut game = GameY::new(4);
+        place(&mut game, 0, 1, 1, 1);
+
+        let analysis = game.connections();
+        let group = analysis.virtual_win(PlayerId::new(0)).unwrap();
+        assert_eq!(group.sides, vec![Side::A, Side::B, Side::C]);
+        assert!(analysis.virtual_win(PlayerId::new(1)).is_none());
+    }
+
+    #[test]
+    fn test_serializes_sides_and_threat_kind() {
+        let mut game = GameY::new(6);
+        place(&mut game, 0, 1, 2, 2);
+        place(&mut game, 1, 0, 3, 2);
+
+        let json = serde_json::to_value(game.connections()).unwrap();
+        assert_eq!(json["threats"][0]["kind"], "edge");
+        assert_eq!(json["threats"][0]["side"], "a");
+        assert_eq!(json["threats"][0]["player"], 0);
+    }
+}
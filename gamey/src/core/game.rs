@@ -1,7 +1,7 @@
 use crate::core::SetIdx;
 use crate::core::player_set::PlayerSet;
 use crate::{Coordinates, GameAction, GameYError, Movement, PlayerId, RenderOptions, YEN};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 use std::path::Path;
 
@@ -80,6 +80,83 @@ impl GameY {
         (self.board_size * (self.board_size + 1)) / 2
     }
 
+    /// Returns the moves played so far, in order.
+    pub fn history(&self) -> &[Movement] {
+        &self.history
+    }
+
+    /// Returns the coordinates of the most recent placement, if any.
+    pub fn last_placement(&self) -> Option<Coordinates> {
+        self.history.iter().rev().find_map(|movement| match movement {
+            Movement::Placement { coords, .. } => Some(*coords),
+            Movement::Action { .. } => None,
+        })
+    }
+
+    /// Returns the state of the cell at the given coordinates.
+    pub fn cell(&self, coords: &Coordinates) -> Cell {
+        match self.board_map.get(coords) {
+            Some((_, player)) => Cell::Occupied(*player),
+            None => Cell::Empty,
+        }
+    }
+
+    /// Returns the stones of the chain that won the game.
+    ///
+    /// The chain is the connected group of the winner's stones that touches
+    /// all three sides, sorted by cell index. Returns `None` while the game is
+    /// ongoing, after a draw, or when the game ended without a connecting
+    /// chain (e.g. by resignation).
+    pub fn winning_chain(&self) -> Option<Vec<Coordinates>> {
+        let winner = match self.status {
+            GameStatus::Finished {
+                winner: Some(winner),
+            } => winner,
+            _ => return None,
+        };
+
+        let mut visited: HashSet<Coordinates> = HashSet::new();
+        for (start, (_, player)) in &self.board_map {
+            if *player != winner || visited.contains(start) {
+                continue;
+            }
+            let group = self.collect_group(*start, winner, &mut visited);
+            let touches_all_sides = group.iter().any(|c| c.touches_side_a())
+                && group.iter().any(|c| c.touches_side_b())
+                && group.iter().any(|c| c.touches_side_c());
+            if touches_all_sides {
+                let mut chain = group;
+                chain.sort_by_key(|c| c.to_index(self.board_size));
+                return Some(chain);
+            }
+        }
+        None
+    }
+
+    /// Flood-fills the group of `player` stones connected to `start`.
+    fn collect_group(
+        &self,
+        start: Coordinates,
+        player: PlayerId,
+        visited: &mut HashSet<Coordinates>,
+    ) -> Vec<Coordinates> {
+        let mut group = Vec::new();
+        let mut stack = vec![start];
+        visited.insert(start);
+        while let Some(current) = stack.pop() {
+            group.push(current);
+            for neighbor in self.get_neighbors(&current) {
+                if !visited.contains(&neighbor)
+                    && matches!(self.board_map.get(&neighbor), Some((_, p)) if *p == player)
+                {
+                    visited.insert(neighbor);
+                    stack.push(neighbor);
+                }
+            }
+        }
+        group
+    }
+
     /// Checks if the movement is made by the correct player.
     ///
     /// Returns an error if it's not the specified player's turn.
@@ -263,7 +340,7 @@ impl GameY {
     }
 
     /// Returns the neighboring coordinates for a given cell.
-    fn get_neighbors(&self, coords: &Coordinates) -> Vec<Coordinates> {
+    pub fn get_neighbors(&self, coords: &Coordinates) -> Vec<Coordinates> {
         let mut neighbors = Vec::new();
         let x = coords.x();
         let y = coords.y();
@@ -564,7 +641,6 @@ pub enum GameStatus {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashSet;
 
     #[test]
     fn test_other_player() {
@@ -713,6 +789,66 @@ mod tests {
         assert_eq!(yen.layout(), yen_loaded.layout());
     }
 
+    #[test]
+    fn test_last_placement_and_cell() {
+        let mut game = GameY::new(3);
+        assert_eq!(game.last_placement(), None);
+
+        let coords = Coordinates::new(1, 1, 0);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords,
+        })
+        .unwrap();
+
+        assert_eq!(game.history().len(), 1);
+        assert_eq!(game.last_placement(), Some(coords));
+        assert_eq!(game.cell(&coords), Cell::Occupied(PlayerId::new(0)));
+        assert_eq!(game.cell(&Coordinates::new(2, 0, 0)), Cell::Empty);
+    }
+
+    #[test]
+    fn test_winning_chain_only_contains_connecting_group() {
+        let mut game = GameY::new(3);
+        let moves = vec![
+            (0, Coordinates::new(0, 2, 0)),
+            (1, Coordinates::new(2, 0, 0)),
+            (0, Coordinates::new(0, 1, 1)),
+            (1, Coordinates::new(1, 1, 0)),
+            (0, Coordinates::new(0, 0, 2)),
+        ];
+        for (player, coords) in moves {
+            assert!(game.winning_chain().is_none());
+            game.add_move(Movement::Placement {
+                player: PlayerId::new(player),
+                coords,
+            })
+            .unwrap();
+        }
+
+        let chain = game.winning_chain().unwrap();
+        assert_eq!(
+            chain,
+            vec![
+                Coordinates::new(0, 0, 2),
+                Coordinates::new(0, 1, 1),
+                Coordinates::new(0, 2, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_winning_chain_is_none_after_resign() {
+        let mut game = GameY::new(3);
+        game.add_move(Movement::Action {
+            player: PlayerId::new(0),
+            action: GameAction::Resign,
+        })
+        .unwrap();
+        assert!(game.check_game_over());
+        assert!(game.winning_chain().is_none());
+    }
+
     // Test loading a YEN representation of a finished game
     #[test]
     fn test_load_yen_end2() {
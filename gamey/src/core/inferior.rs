@@ -0,0 +1,293 @@
+//! Dead and captured cells.
+//!
+//! Some empty cells cannot change the outcome of the game, and search can
+//! skip them:
+//!
+//! - a **dead** cell lies in an empty region whose neighbors are all stones
+//!   of a single group, and every side the region touches is already touched
+//!   by that group; a stone there adds nothing to either player
+//! - a **captured** pair is an empty region of two cells whose neighbors are
+//!   all stones of one player, where both cells are adjacent to the same
+//!   groups of that player and either cell alone reaches every side the pair
+//!   does; if the opponent plays one cell, the owner answers in the other and
+//!   gets exactly what owning both would give
+//!
+//! In both cases the region can be filled with the owner's stones without
+//! changing who wins. Only these local patterns are detected, so the result
+//! is a safe subset of all inferior cells, not all of them.
+
+use serde::Serialize;
+
+use crate::{Cell, Coordinates, GameY, PlayerId};
+
+/// Why an empty cell can be ignored by search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InferiorKind {
+    Dead,
+    Captured,
+}
+
+/// An empty cell that does not affect the outcome of the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct InferiorCell {
+    pub coords: Coordinates,
+    pub kind: InferiorKind,
+    /// The player whose stones enclose the cell; filling the cell with a
+    /// stone of this player does not change who wins.
+    pub owner: PlayerId,
+}
+
+impl GameY {
+    /// Finds the dead cells and captured pairs on the board, in index order.
+    pub fn inferior_cells(&self) -> Vec<InferiorCell> {
+        let size = self.board_size();
+        let total = self.total_cells() as usize;
+        let coords: Vec<Coordinates> =
+            (0..total).map(|idx| Coordinates::from_index(idx as u32, size)).collect();
+        let cells: Vec<Cell> = coords.iter().map(|c| self.cell(c)).collect();
+        let neighbors: Vec<Vec<usize>> = coords
+            .iter()
+            .map(|c| {
+                self.get_neighbors(c)
+                    .iter()
+                    .map(|n| n.to_index(size) as usize)
+                    .collect()
+            })
+            .collect();
+
+        // Stone groups and the sides each of them touches.
+        let mut group = vec![usize::MAX; total];
+        let mut group_sides: Vec<u8> = Vec::new();
+        for start in 0..total {
+            let Cell::Occupied(player) = cells[start] else { continue };
+            if group[start] != usize::MAX {
+                continue;
+            }
+            let id = group_sides.len();
+            let members = flood(start, &neighbors, &mut group, id, |n| {
+                cells[n] == Cell::Occupied(player)
+            });
+            group_sides.push(members.iter().fold(0, |acc, &i| acc | side_mask(&coords[i])));
+        }
+
+        let mut region_of = vec![usize::MAX; total];
+        let mut result = Vec::new();
+        for start in 0..total {
+            if cells[start] != Cell::Empty || region_of[start] != usize::MAX {
+                continue;
+            }
+            let region = flood(start, &neighbors, &mut region_of, start, |n| cells[n] == Cell::Empty);
+
+            let Some(owner) = enclosing_player(&region, &neighbors, &cells) else { continue };
+            let adjacent = |members: &[usize]| {
+                let mut groups: Vec<usize> = members
+                    .iter()
+                    .flat_map(|&i| neighbors[i].iter())
+                    .filter(|&&n| cells[n] != Cell::Empty)
+                    .map(|&n| group[n])
+                    .collect();
+                groups.sort_unstable();
+                groups.dedup();
+                groups
+            };
+            let groups = adjacent(&region);
+            let groups_sides = groups.iter().fold(0, |acc, &g| acc | group_sides[g]);
+            let region_sides = region.iter().fold(0, |acc, &i| acc | side_mask(&coords[i]));
+
+            let kind = if groups.len() == 1 && region_sides & !groups_sides == 0 {
+                InferiorKind::Dead
+            } else if region.len() == 2
+                && region.iter().all(|&cell| {
+                    adjacent(&[cell]) == groups
+                        && groups_sides | side_mask(&coords[cell]) == groups_sides | region_sides
+                })
+            {
+                InferiorKind::Captured
+            } else {
+                continue;
+            };
+            result.extend(region.into_iter().map(|i| InferiorCell { coords: coords[i], kind, owner }));
+        }
+
+        result.sort_by_key(|cell| cell.coords.to_index(size));
+        result
+    }
+
+    /// Empty cells (as indices) worth considering as moves: the available
+    /// cells minus the dead and captured ones.
+    ///
+    /// If every empty cell is inferior, all of them are returned, so that
+    /// there is always a move to play while the board is not full.
+    pub fn candidate_moves(&self) -> Vec<u32> {
+        let size = self.board_size();
+        let inferior: Vec<u32> = self
+            .inferior_cells()
+            .iter()
+            .map(|cell| cell.coords.to_index(size))
+            .collect();
+        let candidates: Vec<u32> = self
+            .available_cells()
+            .iter()
+            .copied()
+            .filter(|idx| !inferior.contains(idx))
+            .collect();
+        if candidates.is_empty() {
+            self.available_cells().clone()
+        } else {
+            candidates
+        }
+    }
+}
+
+/// Bit `i` is set when `coords` lies on side `i` (A, B, C).
+fn side_mask(coords: &Coordinates) -> u8 {
+    [coords.x(), coords.y(), coords.z()]
+        .iter()
+        .enumerate()
+        .filter(|(_, value)| **value == 0)
+        .fold(0, |acc, (side, _)| acc | 1 << side)
+}
+
+/// Labels with `id` every cell reachable from `start` through cells that
+/// satisfy `joins`, and returns them.
+fn flood(
+    start: usize,
+    neighbors: &[Vec<usize>],
+    labels: &mut [usize],
+    id: usize,
+    joins: impl Fn(usize) -> bool,
+) -> Vec<usize> {
+    labels[start] = id;
+    let mut members = vec![start];
+    let mut next = 0;
+    while next < members.len() {
+        for &n in &neighbors[members[next]] {
+            if labels[n] == usize::MAX && joins(n) {
+                labels[n] = id;
+                members.push(n);
+            }
+        }
+        next += 1;
+    }
+    members
+}
+
+/// The player owning every stone around `region`, if there is exactly one.
+fn enclosing_player(region: &[usize], neighbors: &[Vec<usize>], cells: &[Cell]) -> Option<PlayerId> {
+    let mut owner = None;
+    for &n in region.iter().flat_map(|&i| neighbors[i].iter()) {
+        match (cells[n], owner) {
+            (Cell::Empty, _) => {}
+            (Cell::Occupied(player), None) => owner = Some(player),
+            (Cell::Occupied(player), Some(current)) if player != current => return None,
+            _ => {}
+        }
+    }
+    owner
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Movement;
+
+    fn place(game: &mut GameY, player: u32, x: u32, y: u32, z: u32) {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords: Coordinates::new(x, y, z),
+        })
+        .unwrap();
+    }
+
+    fn inferior(coords: Coordinates, kind: InferiorKind, owner: u32) -> InferiorCell {
+        InferiorCell { coords, kind, owner: PlayerId::new(owner) }
+    }
+
+    #[test]
+    fn test_empty_board_has_no_inferior_cells() {
+        let game = GameY::new(5);
+        assert!(game.inferior_cells().is_empty());
+        assert_eq!(game.candidate_moves(), *game.available_cells());
+    }
+
+    #[test]
+    fn test_corner_enclosed_by_one_group_is_dead() {
+        // The corner touches sides B and C, and so does the group around it.
+        let mut game = GameY::new(5);
+        place(&mut game, 0, 3, 1, 0);
+        place(&mut game, 1, 0, 2, 2);
+        place(&mut game, 0, 3, 0, 1);
+
+        let corner = Coordinates::new(4, 0, 0);
+        assert_eq!(game.inferior_cells(), vec![inferior(corner, InferiorKind::Dead, 0)]);
+        assert!(!game.candidate_moves().contains(&corner.to_index(5)));
+        assert_eq!(game.candidate_moves().len(), game.available_cells().len() - 1);
+    }
+
+    #[test]
+    fn test_region_reaching_a_side_the_group_misses_is_not_dead() {
+        // Size 3: the two stones cut off the corner and the whole side A row.
+        // The corner is dead, but the row reaches side A, which the stones do not.
+        let mut game = GameY::new(3);
+        place(&mut game, 0, 1, 1, 0);
+        place(&mut game, 0, 1, 0, 1);
+
+        assert_eq!(
+            game.inferior_cells(),
+            vec![inferior(Coordinates::new(2, 0, 0), InferiorKind::Dead, 0)]
+        );
+    }
+
+    #[test]
+    fn test_region_next_to_both_players_is_not_inferior() {
+        let mut game = GameY::new(5);
+        place(&mut game, 0, 3, 1, 0);
+        place(&mut game, 1, 3, 0, 1);
+        assert!(game.inferior_cells().is_empty());
+    }
+
+    #[test]
+    fn test_pair_between_two_groups_is_captured() {
+        // Size 5: the corner stone and the third row are separate groups;
+        // each of the two cells between them joins both and reaches a side
+        // those groups already touch.
+        let mut game = GameY::new(5);
+        place(&mut game, 0, 4, 0, 0);
+        for (x, y, z) in [(2, 2, 0), (2, 1, 1), (2, 0, 2)] {
+            place(&mut game, 0, x, y, z);
+        }
+
+        assert_eq!(
+            game.inferior_cells(),
+            vec![
+                inferior(Coordinates::new(3, 0, 1), InferiorKind::Captured, 0),
+                inferior(Coordinates::new(3, 1, 0), InferiorKind::Captured, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pair_touching_an_opponent_stone_is_not_captured() {
+        // With an opponent stone at (2,0,2) the pair is no longer enclosed
+        // by a single player.
+        let mut game = GameY::new(5);
+        place(&mut game, 0, 4, 0, 0);
+        place(&mut game, 0, 2, 2, 0);
+        place(&mut game, 1, 2, 0, 2);
+        place(&mut game, 0, 2, 1, 1);
+
+        assert!(game.inferior_cells().is_empty());
+    }
+
+    #[test]
+    fn test_candidate_moves_fall_back_to_available_cells() {
+        // Size 2: the top stone has captured the rest of the board.
+        let mut game = GameY::new(2);
+        place(&mut game, 0, 1, 0, 0);
+
+        assert_eq!(game.inferior_cells().len(), 2);
+        assert!(game.inferior_cells().iter().all(|c| c.kind == InferiorKind::Captured));
+        assert_eq!(game.candidate_moves(), *game.available_cells());
+    }
+}
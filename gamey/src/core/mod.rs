@@ -8,20 +8,29 @@
 //! - [`Movement`]: A move (placement or action) in the game
 //! - [`GameAction`]: Special actions like swap or resign
 //! - [`RenderOptions`]: Configuration for board rendering
+//! - [`SvgOptions`]: Configuration for SVG board rendering
+//! - [`ConnectionAnalysis`]: Bridges, edge templates and virtual groups on the board
+//! - [`InferiorCell`]: Dead and captured cells that search can skip
 
 pub mod action;
+pub mod connections;
 pub mod coord;
 pub mod game;
+pub mod inferior;
 pub mod movement;
 pub mod player;
 mod player_set;
 pub mod render_options;
+pub mod svg;
 
 pub use action::*;
+pub use connections::*;
 pub use coord::*;
 pub use game::*;
+pub use inferior::*;
 pub use movement::*;
 pub use player::*;
 pub use render_options::*;
+pub use svg::*;
 
 type SetIdx = usize;
@@ -0,0 +1,442 @@
+//! SVG rendering of the game board.
+//!
+//! [`GameY::render_svg`] draws the triangular board as a grid of hexagonal
+//! cells, with stones, optional labels, the last move, the winning chain,
+//! heatmap overlays and the virtual connections found by [`GameY::connections`]. The output is a standalone `image/svg+xml` document.
+
+use std::fmt::Write;
+
+use crate::{Cell, ConnectionAnalysis, Coordinates, GameY};
+
+/// Fill color used for player 0 stones.
+const PLAYER_0_COLOR: &str = "#1e63d6";
+/// Fill color used for player 1 stones.
+const PLAYER_1_COLOR: &str = "#d62828";
+/// Fill color of an empty cell.
+const EMPTY_CELL_COLOR: &str = "#f4ecd8";
+/// Stroke color of the cell grid.
+const GRID_COLOR: &str = "#8a7b5c";
+/// Stroke color used to outline the winning chain.
+const WINNING_CHAIN_COLOR: &str = "#f2b705";
+/// Stroke color of the ring marking where a threatened connection is saved.
+const THREAT_COLOR: &str = "#ff8c00";
+/// Stroke color used to outline a group that virtually connects all sides.
+const VIRTUAL_WIN_COLOR: &str = "#2a9d3f";
+
+/// Which label, if any, is written inside each cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SvgLabels {
+    /// No labels.
+    #[default]
+    None,
+    /// The linear cell index.
+    Index,
+    /// The barycentric `x,y,z` coordinates.
+    Coordinates,
+}
+
+/// Configuration options for SVG rendering.
+#[derive(Debug, Clone)]
+pub struct SvgOptions {
+    /// Radius of each hexagonal cell, in SVG user units.
+    pub cell_radius: f32,
+    /// Label written inside each cell.
+    pub labels: SvgLabels,
+    /// If true, mark the most recent placement.
+    pub show_last_move: bool,
+    /// If true, outline the chain that won the game (if any).
+    pub highlight_winning_chain: bool,
+    /// Optional per-cell values in `[0, 1]`, indexed by cell index.
+    ///
+    /// Values are drawn as a translucent overlay on empty cells. Missing
+    /// entries and non-finite values are ignored; others are clamped.
+    pub heatmap: Option<Vec<f32>>,
+    /// If true, draw bridges, edge templates, threatened connections and
+    /// groups that virtually connect all sides (see [`GameY::connections`]).
+    pub show_connections: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions {
+            cell_radius: 24.0,
+            labels: SvgLabels::None,
+            show_last_move: true,
+            highlight_winning_chain: true,
+            heatmap: None,
+            show_connections: false,
+        }
+    }
+}
+
+impl GameY {
+    /// Renders the current state of the board as an SVG document.
+    pub fn render_svg(&self, options: &SvgOptions) -> String {
+        let layout = SvgLayout::new(self.board_size(), options.cell_radius);
+        let last_move = if options.show_last_move {
+            self.last_placement()
+        } else {
+            None
+        };
+        let winning_chain = if options.highlight_winning_chain {
+            self.winning_chain().unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w:.1}" height="{h:.1}" viewBox="0 0 {w:.1} {h:.1}">"#,
+            w = layout.width,
+            h = layout.height,
+        );
+        let _ = writeln!(
+            svg,
+            r#"<title>Game of Y (size {})</title>"#,
+            self.board_size()
+        );
+
+        for idx in 0..self.total_cells() {
+            let coords = Coordinates::from_index(idx, self.board_size());
+            let (cx, cy) = layout.center(&coords);
+            let cell = self.cell(&coords);
+
+            let _ = writeln!(
+                svg,
+                r#"<polygon class="cell" data-cell="{idx}" points="{}" fill="{EMPTY_CELL_COLOR}" stroke="{GRID_COLOR}" stroke-width="1"/>"#,
+                layout.hexagon_points(cx, cy, layout.radius),
+            );
+
+            if cell == Cell::Empty
+                && let Some(value) = heatmap_value(options.heatmap.as_deref(), idx)
+            {
+                let _ = writeln!(
+                    svg,
+                    r#"<polygon class="heat" points="{}" fill="{}" fill-opacity="{:.3}"/>"#,
+                    layout.hexagon_points(cx, cy, layout.radius),
+                    heat_color(value),
+                    0.15 + 0.6 * value,
+                );
+            }
+
+            if let Cell::Occupied(player) = cell {
+                let color = player_color(player.id());
+                let _ = writeln!(
+                    svg,
+                    r#"<circle class="stone player-{}" cx="{cx:.2}" cy="{cy:.2}" r="{:.2}" fill="{color}"/>"#,
+                    player.id(),
+                    layout.radius * 0.72,
+                );
+            }
+
+            if winning_chain.contains(&coords) {
+                let _ = writeln!(
+                    svg,
+                    r#"<polygon class="winning" points="{}" fill="none" stroke="{WINNING_CHAIN_COLOR}" stroke-width="{:.2}"/>"#,
+                    layout.hexagon_points(cx, cy, layout.radius * 0.9),
+                    layout.radius * 0.15,
+                );
+            }
+
+            if last_move == Some(coords) {
+                let _ = writeln!(
+                    svg,
+                    r##"<circle class="last-move" cx="{cx:.2}" cy="{cy:.2}" r="{:.2}" fill="#ffffff"/>"##,
+                    layout.radius * 0.18,
+                );
+            }
+
+            if let Some(label) = cell_label(options.labels, idx, &coords) {
+                let text_color = if cell == Cell::Empty { "#333333" } else { "#ffffff" };
+                let font_size = if options.labels == SvgLabels::Coordinates {
+                    layout.radius * 0.38
+                } else {
+                    layout.radius * 0.55
+                };
+                let _ = writeln!(
+                    svg,
+                    r#"<text class="label" x="{cx:.2}" y="{cy:.2}" font-family="sans-serif" font-size="{font_size:.2}" text-anchor="middle" dominant-baseline="central" fill="{text_color}">{label}</text>"#,
+                );
+            }
+        }
+
+        if options.show_connections {
+            write_connections(&mut svg, &layout, &self.connections());
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Draws the connection overlay on top of the board: dashed lines for
+/// bridges and edge templates, rings on the cells that save a threatened
+/// connection and outlines around groups that virtually connect all sides.
+fn write_connections(svg: &mut String, layout: &SvgLayout, analysis: &ConnectionAnalysis) {
+    let stroke_width = layout.radius * 0.12;
+
+    for bridge in &analysis.bridges {
+        let (x1, y1) = layout.center(&bridge.stones[0]);
+        let (x2, y2) = layout.center(&bridge.stones[1]);
+        let _ = writeln!(
+            svg,
+            r#"<line class="bridge player-{}" x1="{x1:.2}" y1="{y1:.2}" x2="{x2:.2}" y2="{y2:.2}" stroke="{}" stroke-width="{stroke_width:.2}" stroke-dasharray="{:.2}"/>"#,
+            bridge.player.id(),
+            player_color(bridge.player.id()),
+            layout.radius * 0.25,
+        );
+    }
+
+    for template in &analysis.edge_templates {
+        let (x1, y1) = layout.center(&template.stone);
+        let (ax, ay) = layout.center(&template.carrier[0]);
+        let (bx, by) = layout.center(&template.carrier[1]);
+        let (x2, y2) = ((ax + bx) / 2.0, (ay + by) / 2.0);
+        let _ = writeln!(
+            svg,
+            r#"<line class="edge-template player-{}" x1="{x1:.2}" y1="{y1:.2}" x2="{x2:.2}" y2="{y2:.2}" stroke="{}" stroke-width="{stroke_width:.2}" stroke-dasharray="{:.2}"/>"#,
+            template.player.id(),
+            player_color(template.player.id()),
+            layout.radius * 0.25,
+        );
+    }
+
+    for threat in &analysis.threats {
+        let (cx, cy) = layout.center(&threat.response);
+        let _ = writeln!(
+            svg,
+            r#"<circle class="threat player-{}" cx="{cx:.2}" cy="{cy:.2}" r="{:.2}" fill="none" stroke="{THREAT_COLOR}" stroke-width="{:.2}"/>"#,
+            threat.player.id(),
+            layout.radius * 0.5,
+            layout.radius * 0.15,
+        );
+    }
+
+    for group in analysis.groups.iter().filter(|group| group.connects_all_sides()) {
+        for stone in &group.stones {
+            let (cx, cy) = layout.center(stone);
+            let _ = writeln!(
+                svg,
+                r#"<polygon class="virtual-win player-{}" points="{}" fill="none" stroke="{VIRTUAL_WIN_COLOR}" stroke-width="{:.2}"/>"#,
+                group.player.id(),
+                layout.hexagon_points(cx, cy, layout.radius * 0.9),
+                layout.radius * 0.12,
+            );
+        }
+    }
+}
+
+fn player_color(id: u32) -> &'static str {
+    if id == 0 { PLAYER_0_COLOR } else { PLAYER_1_COLOR }
+}
+
+/// Geometry of the triangular board: cell centers and canvas size.
+struct SvgLayout {
+    board_size: u32,
+    radius: f32,
+    margin: f32,
+    width: f32,
+    height: f32,
+}
+
+impl SvgLayout {
+    fn new(board_size: u32, radius: f32) -> Self {
+        let radius = if radius.is_finite() && radius > 0.0 {
+            radius
+        } else {
+            SvgOptions::default().cell_radius
+        };
+        let margin = radius * 0.5;
+        let cell_width = 3f32.sqrt() * radius;
+        let rows = board_size.max(1) as f32;
+        let width = 2.0 * margin + rows * cell_width;
+        let height = 2.0 * margin + 2.0 * radius + (rows - 1.0) * 1.5 * radius;
+        Self {
+            board_size,
+            radius,
+            margin,
+            width,
+            height,
+        }
+    }
+
+    /// Returns the center of a cell. Row 0 (the top corner) has one cell and
+    /// each following row is shifted half a cell to the left.
+    fn center(&self, coords: &Coordinates) -> (f32, f32) {
+        let row = (self.board_size - 1 - coords.x()) as f32;
+        let col = coords.y() as f32;
+        let cell_width = 3f32.sqrt() * self.radius;
+        let offset = (self.board_size as f32 - 1.0 - row) / 2.0;
+        let cx = self.margin + cell_width * (col + offset + 0.5);
+        let cy = self.margin + self.radius + row * 1.5 * self.radius;
+        (cx, cy)
+    }
+
+    /// Returns the `points` attribute of a pointy-top hexagon.
+    fn hexagon_points(&self, cx: f32, cy: f32, radius: f32) -> String {
+        (0..6)
+            .map(|i| {
+                let angle = std::f32::consts::PI / 180.0 * (60.0 * i as f32 - 90.0);
+                format!("{:.2},{:.2}", cx + radius * angle.cos(), cy + radius * angle.sin())
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+fn heatmap_value(heatmap: Option<&[f32]>, idx: u32) -> Option<f32> {
+    let value = *heatmap?.get(idx as usize)?;
+    value.is_finite().then(|| value.clamp(0.0, 1.0))
+}
+
+/// Maps a value in `[0, 1]` to a color ramp from cold (blue) to hot (red).
+fn heat_color(value: f32) -> String {
+    let red = (255.0 * value).round() as u8;
+    let blue = (255.0 * (1.0 - value)).round() as u8;
+    format!("#{red:02x}40{blue:02x}")
+}
+
+fn cell_label(labels: SvgLabels, idx: u32, coords: &Coordinates) -> Option<String> {
+    match labels {
+        SvgLabels::None => None,
+        SvgLabels::Index => Some(idx.to_string()),
+        SvgLabels::Coordinates => Some(format!("{},{},{}", coords.x(), coords.y(), coords.z())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Movement, PlayerId};
+
+    fn place(game: &mut GameY, player: u32, x: u32, y: u32, z: u32) {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords: Coordinates::new(x, y, z),
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_empty_board_draws_every_cell() {
+        let game = GameY::new(4);
+        let svg = game.render_svg(&SvgOptions::default());
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches(r#"class="cell""#).count(), 10);
+        assert_eq!(svg.matches(r#"class="stone"#).count(), 0);
+    }
+
+    #[test]
+    fn test_stones_use_player_colors() {
+        let mut game = GameY::new(3);
+        place(&mut game, 0, 2, 0, 0);
+        place(&mut game, 1, 1, 1, 0);
+
+        let svg = game.render_svg(&SvgOptions::default());
+
+        assert_eq!(svg.matches(r#"class="stone player-0""#).count(), 1);
+        assert_eq!(svg.matches(r#"class="stone player-1""#).count(), 1);
+        assert!(svg.contains(PLAYER_0_COLOR));
+        assert!(svg.contains(PLAYER_1_COLOR));
+    }
+
+    #[test]
+    fn test_last_move_marker_can_be_disabled() {
+        let mut game = GameY::new(3);
+        place(&mut game, 0, 2, 0, 0);
+
+        let with_marker = game.render_svg(&SvgOptions::default());
+        let without_marker = game.render_svg(&SvgOptions {
+            show_last_move: false,
+            ..SvgOptions::default()
+        });
+
+        assert_eq!(with_marker.matches("last-move").count(), 1);
+        assert_eq!(without_marker.matches("last-move").count(), 0);
+    }
+
+    #[test]
+    fn test_winning_chain_is_highlighted() {
+        let mut game = GameY::new(3);
+        place(&mut game, 0, 0, 2, 0);
+        place(&mut game, 1, 2, 0, 0);
+        place(&mut game, 0, 0, 1, 1);
+        place(&mut game, 1, 1, 1, 0);
+        place(&mut game, 0, 0, 0, 2);
+
+        let svg = game.render_svg(&SvgOptions::default());
+        assert_eq!(svg.matches(r#"class="winning""#).count(), 3);
+    }
+
+    #[test]
+    fn test_labels_index_and_coordinates() {
+        let game = GameY::new(2);
+
+        let by_index = game.render_svg(&SvgOptions {
+            labels: SvgLabels::Index,
+            ..SvgOptions::default()
+        });
+        assert!(by_index.contains(">2</text>"));
+
+        let by_coords = game.render_svg(&SvgOptions {
+            labels: SvgLabels::Coordinates,
+            ..SvgOptions::default()
+        });
+        assert!(by_coords.contains(">1,0,0</text>"));
+    }
+
+    #[test]
+    fn test_heatmap_only_on_empty_cells() {
+        let mut game = GameY::new(2);
+        place(&mut game, 0, 1, 0, 0);
+
+        let svg = game.render_svg(&SvgOptions {
+            heatmap: Some(vec![1.0, 0.5, f32::NAN]),
+            ..SvgOptions::default()
+        });
+
+        // Cell 0 is occupied and cell 2 has a non-finite value.
+        assert_eq!(svg.matches(r#"class="heat""#).count(), 1);
+    }
+
+    #[test]
+    fn test_heat_color_ramp_endpoints() {
+        assert_eq!(heat_color(0.0), "#0040ff");
+        assert_eq!(heat_color(1.0), "#ff4000");
+    }
+
+    #[test]
+    fn test_connection_overlay_is_opt_in() {
+        let mut game = GameY::new(6);
+        place(&mut game, 0, 3, 1, 1);
+        place(&mut game, 1, 2, 2, 1);
+        place(&mut game, 0, 1, 2, 2);
+
+        let plain = game.render_svg(&SvgOptions::default());
+        assert!(!plain.contains("class=\"threat"));
+
+        let svg = game.render_svg(&SvgOptions {
+            show_connections: true,
+            ..SvgOptions::default()
+        });
+        assert_eq!(svg.matches(r#"class="threat player-0""#).count(), 1);
+        // (1,2,2) holds an edge template to side A and (3,1,1) to sides B and C.
+        assert_eq!(svg.matches(r#"class="edge-template player-0""#).count(), 3);
+    }
+
+    #[test]
+    fn test_connection_overlay_outlines_virtual_win() {
+        let mut game = GameY::new(4);
+        place(&mut game, 0, 1, 1, 1);
+
+        let svg = game.render_svg(&SvgOptions {
+            show_connections: true,
+            ..SvgOptions::default()
+        });
+        assert_eq!(svg.matches(r#"class="virtual-win player-0""#).count(), 1);
+        assert_eq!(svg.matches(r#"class="edge-template player-0""#).count(), 3);
+    }
+}
@@ -0,0 +1,373 @@
+//! Text engine protocol over stdin/stdout.
+//!
+//! A GTP-style protocol that lets any [`YBot`] be driven by an outside
+//! program (a GUI, a tournament runner, another arena) and lets
+//! [`crate::ExternalBot`] drive outside engines from ours.
+//!
+//! Each request is one line: an optional numeric id, a command and its
+//! arguments. Each reply is `=` (success) or `?` (failure), the id if the
+//! request had one, the result text and an empty line:
+//!
+//! ```text
+//! boardsize 5
+//! =
+//!
+//! 2 genmove b
+//! =2 7
+//!
+//! ```
+//!
+//! Supported commands:
+//!
+//! | command                  | effect                                        |
+//! |--------------------------|-----------------------------------------------|
+//! | `protocol_version`       | `2`                                           |
+//! | `name`, `version`        | engine name and crate version                 |
+//! | `known_command <cmd>`    | `true` or `false`                             |
+//! | `list_commands`          | one command per line                          |
+//! | `boardsize <n>`          | new empty game of size `n` (up to 15)         |
+//! | `clear_board`            | new empty game of the current size            |
+//! | `play <color> <vertex>`  | plays a move for `color`                      |
+//! | `genmove <color>`        | asks the bot for a move for `color`, plays it |
+//! | `undo`                   | takes back the last move                      |
+//! | `showboard`              | text rendering of the board                   |
+//! | `final_score`            | `B+` or `W+` once the game is over            |
+//! | `quit`                   | ends the session                              |
+//!
+//! Colors are `b` (player 0, also `black` or `blue`) and `w` (player 1, also
+//! `white`, `r` or `red`). A vertex is a cell index, as in the CLI, or one of
+//! the actions `swap` and `resign`. Moves must respect the turn order.
+
+use std::io::{self, BufRead, Write};
+use std::sync::Arc;
+
+use crate::game_server::MAX_BOARD_SIZE;
+use crate::{BotDecision, GameAction, GameStatus, GameY, Movement, PlayerId, RenderOptions, YBot};
+
+/// Version reported by `protocol_version`.
+pub const ENGINE_PROTOCOL_VERSION: &str = "2";
+
+/// Name reported by `name`.
+pub const ENGINE_NAME: &str = "gamey";
+
+/// Commands understood by [`Engine`], in the order `list_commands` prints them.
+pub const ENGINE_COMMANDS: &[&str] = &[
+    "protocol_version",
+    "name",
+    "version",
+    "known_command",
+    "list_commands",
+    "boardsize",
+    "clear_board",
+    "play",
+    "genmove",
+    "undo",
+    "showboard",
+    "final_score",
+    "quit",
+];
+
+/// Parses a protocol color into a player.
+pub fn parse_engine_color(color: &str) -> Option<PlayerId> {
+    match color.to_ascii_lowercase().as_str() {
+        "b" | "black" | "blue" => Some(PlayerId::new(0)),
+        "w" | "white" | "r" | "red" => Some(PlayerId::new(1)),
+        _ => None,
+    }
+}
+
+/// Protocol color of a player: `b` for player 0 and `w` for player 1.
+pub fn engine_color(player: PlayerId) -> &'static str {
+    match player.id() % 2 {
+        0 => "b",
+        _ => "w",
+    }
+}
+
+/// Protocol vertex of a movement: the cell index or the action name.
+pub fn engine_vertex(movement: &Movement, board_size: u32) -> String {
+    match movement {
+        Movement::Placement { coords, .. } => coords.to_index(board_size).to_string(),
+        Movement::Action { action: GameAction::Swap, .. } => "swap".to_string(),
+        Movement::Action { action: GameAction::Resign, .. } => "resign".to_string(),
+    }
+}
+
+/// Parses a protocol vertex played by `player` on `game`.
+pub fn parse_engine_vertex(vertex: &str, player: PlayerId, game: &GameY) -> Result<Movement, String> {
+    match vertex.to_ascii_lowercase().as_str() {
+        "swap" => Ok(Movement::Action { player, action: GameAction::Swap }),
+        "resign" => Ok(Movement::Action { player, action: GameAction::Resign }),
+        index => {
+            let idx = crate::parse_idx(index, game.total_cells())?;
+            let coords = crate::Coordinates::from_index(idx, game.board_size());
+            Ok(Movement::Placement { player, coords })
+        }
+    }
+}
+
+/// A protocol session: one bot and the game it is playing.
+pub struct Engine {
+    bot: Arc<dyn YBot>,
+    game: GameY,
+    quit: bool,
+}
+
+impl Engine {
+    pub fn new(bot: Arc<dyn YBot>, board_size: u32) -> Self {
+        Self {
+            bot,
+            game: GameY::new(board_size),
+            quit: false,
+        }
+    }
+
+    /// The game as built by the commands received so far.
+    pub fn game(&self) -> &GameY {
+        &self.game
+    }
+
+    /// Whether a `quit` command has been received.
+    pub fn has_quit(&self) -> bool {
+        self.quit
+    }
+
+    /// Executes one request line and returns the full reply, including the
+    /// trailing empty line. Blank lines and `#` comments get no reply.
+    pub fn execute(&mut self, line: &str) -> Option<String> {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        let mut parts = line.split_whitespace().peekable();
+        let id = parts.peek().and_then(|first| first.parse::<u32>().ok());
+        if id.is_some() {
+            parts.next();
+        }
+        let command = parts.next()?;
+        let args: Vec<&str> = parts.collect();
+
+        let (marker, text) = match self.run(command, &args) {
+            Ok(text) => ('=', text),
+            Err(text) => ('?', text),
+        };
+        let id = id.map(|id| id.to_string()).unwrap_or_default();
+        let separator = if text.is_empty() { "" } else { " " };
+        Some(format!("{marker}{id}{separator}{text}\n\n"))
+    }
+
+    fn run(&mut self, command: &str, args: &[&str]) -> Result<String, String> {
+        match command {
+            "protocol_version" => Ok(ENGINE_PROTOCOL_VERSION.to_string()),
+            "name" => Ok(ENGINE_NAME.to_string()),
+            "version" => Ok(env!("CARGO_PKG_VERSION").to_string()),
+            "known_command" => {
+                let known = args.first().is_some_and(|cmd| ENGINE_COMMANDS.contains(cmd));
+                Ok(known.to_string())
+            }
+            "list_commands" => Ok(ENGINE_COMMANDS.join("\n")),
+            "boardsize" => {
+                let size = args
+                    .first()
+                    .and_then(|size| size.parse::<u32>().ok())
+                    .filter(|&size| size > 0 && size <= MAX_BOARD_SIZE)
+                    .ok_or("unacceptable size")?;
+                self.game = GameY::new(size);
+                Ok(String::new())
+            }
+            "clear_board" => {
+                self.game = GameY::new(self.game.board_size());
+                Ok(String::new())
+            }
+            "play" => {
+                let [color, vertex] = args else {
+                    return Err("syntax error".to_string());
+                };
+                let player = parse_engine_color(color).ok_or("invalid color")?;
+                let movement = parse_engine_vertex(vertex, player, &self.game)
+                    .map_err(|e| format!("illegal move: {e}"))?;
+                self.apply(movement)?;
+                Ok(String::new())
+            }
+            "genmove" => {
+                let player = args
+                    .first()
+                    .and_then(|color| parse_engine_color(color))
+                    .ok_or("invalid color")?;
+                self.genmove(player)
+            }
+            "undo" => {
+                let history = self.game.history();
+                if history.is_empty() {
+                    return Err("cannot undo".to_string());
+                }
+                let mut replay = GameY::new(self.game.board_size());
+                for movement in &history[..history.len() - 1] {
+                    replay.add_move(movement.clone()).map_err(|e| e.to_string())?;
+                }
+                self.game = replay;
+                Ok(String::new())
+            }
+            "showboard" => {
+                // An empty line would end the reply early.
+                let options = RenderOptions { show_colors: false, ..RenderOptions::default() };
+                let board = self.game.render(&options);
+                let lines: Vec<&str> = board.lines().filter(|line| !line.trim().is_empty()).collect();
+                Ok(format!("\n{}", lines.join("\n")))
+            }
+            "final_score" => match self.game.status() {
+                GameStatus::Finished { winner: Some(winner) } => {
+                    Ok(format!("{}+", engine_color(*winner).to_ascii_uppercase()))
+                }
+                _ => Err("game not finished".to_string()),
+            },
+            "quit" => {
+                self.quit = true;
+                Ok(String::new())
+            }
+            _ => Err("unknown command".to_string()),
+        }
+    }
+
+    fn genmove(&mut self, player: PlayerId) -> Result<String, String> {
+        if self.game.next_player() != Some(player) {
+            return Err("not this color's turn".to_string());
+        }
+        let movement = match self.bot.choose_action(&self.game) {
+            Some(BotDecision::Move(coords)) => Movement::Placement { player, coords },
+            Some(BotDecision::Action(action)) => Movement::Action { player, action },
+            None => return Err("no move available".to_string()),
+        };
+        let vertex = engine_vertex(&movement, self.game.board_size());
+        self.apply(movement)?;
+        Ok(vertex)
+    }
+
+    fn apply(&mut self, movement: Movement) -> Result<(), String> {
+        if self.game.check_game_over() {
+            return Err("illegal move: game is over".to_string());
+        }
+        self.game
+            .check_player_turn(&movement)
+            .and_then(|_| self.game.add_move(movement))
+            .map_err(|e| format!("illegal move: {e}"))
+    }
+}
+
+/// Serves `engine` over `input`/`output` until `quit` or end of input.
+pub fn run_engine<R: BufRead, W: Write>(engine: &mut Engine, input: R, mut output: W) -> io::Result<()> {
+    for line in input.lines() {
+        if let Some(reply) = engine.execute(&line?) {
+            output.write_all(reply.as_bytes())?;
+            output.flush()?;
+        }
+        if engine.has_quit() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Serves `bot` over the process stdin/stdout (`gamey --mode engine`).
+pub fn run_engine_stdio(bot: Arc<dyn YBot>, board_size: u32) -> io::Result<()> {
+    let mut engine = Engine::new(bot, board_size);
+    run_engine(&mut engine, io::stdin().lock(), io::stdout().lock())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RandomBot;
+
+    fn engine(size: u32) -> Engine {
+        Engine::new(Arc::new(RandomBot), size)
+    }
+
+    #[test]
+    fn test_replies_carry_the_request_id() {
+        let mut engine = engine(3);
+        assert_eq!(engine.execute("7 protocol_version").unwrap(), "=7 2\n\n");
+        assert_eq!(engine.execute("name").unwrap(), "= gamey\n\n");
+        assert_eq!(engine.execute("3 frobnicate").unwrap(), "?3 unknown command\n\n");
+    }
+
+    #[test]
+    fn test_boardsize_rejects_sizes_the_server_does_not_play() {
+        let mut engine = engine(3);
+        assert_eq!(engine.execute("boardsize 0").unwrap(), "? unacceptable size\n\n");
+        assert_eq!(engine.execute("boardsize 70000").unwrap(), "? unacceptable size\n\n");
+        assert_eq!(engine.execute(&format!("boardsize {MAX_BOARD_SIZE}")).unwrap(), "=\n\n");
+        assert_eq!(engine.game.board_size(), MAX_BOARD_SIZE);
+    }
+
+    #[test]
+    fn test_blank_lines_and_comments_get_no_reply() {
+        let mut engine = engine(3);
+        assert!(engine.execute("").is_none());
+        assert!(engine.execute("   # just a comment").is_none());
+    }
+
+    #[test]
+    fn test_known_command() {
+        let mut engine = engine(3);
+        assert_eq!(engine.execute("known_command genmove").unwrap(), "= true\n\n");
+        assert_eq!(engine.execute("known_command komi").unwrap(), "= false\n\n");
+    }
+
+    #[test]
+    fn test_play_and_undo() {
+        let mut engine = engine(3);
+        assert_eq!(engine.execute("boardsize 4").unwrap(), "=\n\n");
+        assert_eq!(engine.execute("play b 0").unwrap(), "=\n\n");
+        assert_eq!(engine.execute("play w 5").unwrap(), "=\n\n");
+        assert_eq!(engine.game().history().len(), 2);
+
+        assert_eq!(engine.execute("undo").unwrap(), "=\n\n");
+        assert_eq!(engine.game().history().len(), 1);
+        assert_eq!(engine.game().next_player(), Some(PlayerId::new(1)));
+        assert_eq!(engine.game().board_size(), 4);
+    }
+
+    #[test]
+    fn test_illegal_moves_are_rejected() {
+        let mut engine = engine(3);
+        assert!(engine.execute("play w 0").unwrap().starts_with("? illegal move"));
+        engine.execute("play b 0");
+        assert!(engine.execute("play w 0").unwrap().starts_with("? illegal move"));
+        assert!(engine.execute("play w 6").unwrap().starts_with("? illegal move"));
+        assert_eq!(engine.execute("play green 1").unwrap(), "? invalid color\n\n");
+        assert_eq!(engine.execute("undo").unwrap(), "=\n\n");
+        assert_eq!(engine.execute("undo").unwrap(), "? cannot undo\n\n");
+    }
+
+    #[test]
+    fn test_genmove_plays_for_the_side_to_move() {
+        let mut engine = engine(3);
+        assert_eq!(engine.execute("genmove w").unwrap(), "? not this color's turn\n\n");
+
+        let reply = engine.execute("genmove b").unwrap();
+        let idx: u32 = reply.trim_start_matches("= ").trim().parse().unwrap();
+        assert!(idx < 6);
+        assert_eq!(engine.game().history().len(), 1);
+    }
+
+    #[test]
+    fn test_final_score_after_resign() {
+        let mut engine = engine(3);
+        assert_eq!(engine.execute("final_score").unwrap(), "? game not finished\n\n");
+        engine.execute("play b resign");
+        assert_eq!(engine.execute("final_score").unwrap(), "= W+\n\n");
+    }
+
+    #[test]
+    fn test_run_engine_stops_at_quit() {
+        let mut engine = engine(2);
+        let input = "1 play b 0\n2 showboard\n3 quit\n4 name\n";
+        let mut output = Vec::new();
+        run_engine(&mut engine, input.as_bytes(), &mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("=1\n\n=2 \n"));
+        // The board must not contain the empty line that ends a reply.
+        assert_eq!(output.matches("\n\n").count(), 3);
+        assert!(output.ends_with("=3\n\n"));
+        assert!(!output.contains("gamey"));
+    }
+}
@@ -0,0 +1,119 @@
+//! admin.rs
+//!
+//! Endpoints de administración. Solo están activos si el servidor arranca con
+//! un token (`--admin-token`), que debe llegar en la cabecera `X-Admin-Token`.
+//!
+//! - POST /api/v1/admin/bots/reload: vuelve a leer la configuración de bots
+
+use axum::{Json, extract::State, http::HeaderMap};
+use serde::Serialize;
+
+use super::error::ApiErrorResponse;
+use super::state::GameServerState;
+
+#[derive(Debug, Serialize)]
+pub struct ReloadBotsResponse {
+    pub bots: Vec<String>,
+}
+
+/// Comprueba el token de administración.
+fn authorize(state: &GameServerState, headers: &HeaderMap) -> Result<(), ApiErrorResponse> {
+    let Some(expected) = &state.admin_token else {
+        return Err(ApiErrorResponse::forbidden("Admin endpoints are disabled", "ADMIN_DISABLED"));
+    };
+    let given = headers.get("x-admin-token").and_then(|v| v.to_str().ok());
+    if given != Some(expected.as_str()) {
+        return Err(ApiErrorResponse::forbidden("Invalid admin token", "INVALID_ADMIN_TOKEN"));
+    }
+    Ok(())
+}
+
+/// POST /api/v1/admin/bots/reload
+///
+/// Si la configuración no es válida responde 400 y los bots no cambian.
+pub async fn reload_bots(
+    State(state): State<GameServerState>,
+    headers: HeaderMap,
+) -> Result<Json<ReloadBotsResponse>, ApiErrorResponse> {
+    authorize(&state, &headers)?;
+
+    let reloader = state.clone();
+    let result = tokio::task::spawn_blocking(move || reloader.reload_bots())
+        .await
+        .map_err(|e| ApiErrorResponse::internal(format!("Reload task failed: {e}"), "RELOAD_FAILED"))?;
+
+    match result {
+        Ok(mut bots) => {
+            bots.sort();
+            tracing::info!(count = bots.len(), "bot registry reloaded");
+            Ok(Json(ReloadBotsResponse { bots }))
+        }
+        Err(e) => Err(ApiErrorResponse::bad_request(e.to_string(), "INVALID_BOT_CONFIG")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_server::create_router;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    fn reload_request(token: Option<&str>) -> Request<Body> {
+        let mut builder = Request::builder().method("POST").uri("/api/v1/admin/bots/reload");
+        if let Some(token) = token {
+            builder = builder.header("x-admin-token", token);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn reload_is_forbidden_without_admin_token_configured() {
+        let app = create_router(GameServerState::new_default());
+        let response = app.oneshot(reload_request(Some("secret"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn reload_rejects_wrong_token() {
+        let app = create_router(GameServerState::new_default().with_admin_token("secret"));
+        let response = app.oneshot(reload_request(Some("guess"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn reload_picks_up_config_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bots.json");
+        std::fs::write(&path, r#"{ "bots": [ { "type": "random", "name": "random_bot" } ] }"#).unwrap();
+        let state = GameServerState::from_bot_config(&path).unwrap().with_admin_token("secret");
+
+        std::fs::write(
+            &path,
+            r#"{ "bots": [ { "type": "random", "name": "random_bot" }, { "type": "greedy", "name": "greedy_bot" } ] }"#,
+        )
+        .unwrap();
+        let response = create_router(state.clone()).oneshot(reload_request(Some("secret"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json, serde_json::json!({ "bots": ["greedy_bot", "random_bot"] }));
+        assert!(state.bots.find("greedy_bot").is_some());
+    }
+
+    #[tokio::test]
+    async fn reload_with_invalid_config_keeps_bots() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bots.json");
+        std::fs::write(&path, r#"{ "bots": [ { "type": "random", "name": "random_bot" } ] }"#).unwrap();
+        let state = GameServerState::from_bot_config(&path).unwrap().with_admin_token("secret");
+
+        std::fs::write(&path, r#"{ "bots": [ { "type": "mcts", "name": "sin_iteraciones" } ] }"#).unwrap();
+        let response = create_router(state.clone()).oneshot(reload_request(Some("secret"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(state.bots.names(), vec!["random_bot".to_string()]);
+    }
+}
@@ -0,0 +1,262 @@
+//! analysis.rs
+//!
+//! Endpoints de análisis: estadísticas por jugada (visitas, win rate),
+//! evaluación global y variante principal, calculadas por un bot que
+//! implemente `YBot::analyze`, junto con las conexiones virtuales de la
+//! posición (`GameY::connections`). Pensado para pintar un heatmap en la webapp.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{BotAnalysis, ConnectionAnalysis, GameY, YBot};
+
+use super::auth::resolve_principal;
+use super::dto::GameMode;
+use super::error::ApiErrorResponse;
+use super::position::parse_position;
+use super::state::GameServerState;
+
+const DEFAULT_ANALYSIS_BOT_ID: &str = "mcts_completo_medio";
+
+#[derive(Debug, Deserialize)]
+pub struct AnalysisQuery {
+    pub position: Option<String>,
+    pub position_format: Option<String>,
+    pub bot_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnalysisResponse {
+    pub bot_id: String,
+    #[serde(flatten)]
+    pub analysis: BotAnalysis,
+    /// Visitas normalizadas por `cell_id` (1.0 = jugada más visitada).
+    pub heatmap: Vec<f32>,
+    /// Puentes, plantillas de borde, amenazas y grupos virtuales de ambos jugadores.
+    pub connections: ConnectionAnalysis,
+}
+
+fn find_bot(state: &GameServerState, bot_id: &str) -> Result<Arc<dyn YBot>, ApiErrorResponse> {
+    state.bots.find(bot_id).ok_or_else(|| {
+        let mut names = state.bots.names();
+        names.sort();
+        ApiErrorResponse::not_found(
+            format!("Bot not found: {}. Available bots: [{}]", bot_id, names.join(", ")),
+            "bot_not_found",
+        )
+    })
+}
+
+async fn run_analysis(
+    state: &GameServerState,
+    bot: Arc<dyn YBot>,
+    bot_id: String,
+    game: GameY,
+) -> Result<AnalysisResponse, ApiErrorResponse> {
+    if game.check_game_over() {
+        return Err(ApiErrorResponse::conflict(
+            "Position is already finished",
+            "game_finished",
+        ));
+    }
+
+    let position = game.clone();
+    let analysis = state
+        .workers
+        .run(None, move |control| bot.analyze_controlled(&position, control))
+        .await?;
+    let analysis = analysis.ok_or_else(|| {
+        ApiErrorResponse::bad_request(
+            format!("Bot {bot_id} does not support analysis"),
+            "analysis_not_supported",
+        )
+    })?;
+
+    let heatmap = analysis.heatmap(game.total_cells());
+    Ok(AnalysisResponse {
+        bot_id,
+        analysis,
+        heatmap,
+        connections: game.connections(),
+    })
+}
+
+/// GET /api/v1/analysis?position=<yen|compact>&bot_id=<bot>
+pub async fn analyze_position(
+    State(state): State<GameServerState>,
+    Query(query): Query<AnalysisQuery>,
+) -> Result<Json<AnalysisResponse>, ApiErrorResponse> {
+    let game = parse_position(query.position, query.position_format.as_deref())?;
+    let bot_id = query
+        .bot_id
+        .unwrap_or_else(|| DEFAULT_ANALYSIS_BOT_ID.to_string());
+    let bot = find_bot(&state, &bot_id)?;
+
+    run_analysis(&state, bot, bot_id, game).await.map(Json)
+}
+
+/// GET /api/v1/hvb/games/{game_id}/analysis
+///
+/// Analiza la posición actual con el bot de la partida, sin modificarla.
+pub async fn analyze_hvb_game(
+    State(state): State<GameServerState>,
+    headers: HeaderMap,
+    Path(game_id): Path<String>,
+) -> Result<Json<AnalysisResponse>, ApiErrorResponse> {
+    let principal = resolve_principal(&headers);
+    let game_id = Uuid::parse_str(&game_id)
+        .map(|u| u.to_string())
+        .map_err(|_| ApiErrorResponse::bad_request("Invalid game_id", "invalid_game_id"))?;
+
+    let session = state
+        .sessions
+        .assert_owner(&principal, &game_id)
+        .await
+        .map_err(|_| ApiErrorResponse::not_found("Game not found", "game_not_found"))?;
+
+    if session.mode != GameMode::Hvb {
+        return Err(ApiErrorResponse::conflict("Game is not HvB", "invalid_game_mode"));
+    }
+
+    let bot_id = session
+        .bot_id
+        .clone()
+        .ok_or_else(|| ApiErrorResponse::internal("Session missing bot_id", "session_invalid"))?;
+    let bot = find_bot(&state, &bot_id)?;
+
+    run_analysis(&state, bot, bot_id, session.game).await.map(Json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use crate::game_server::create_router;
+    use crate::{MctsCompletoBot, RandomBot, YBotRegistry, YEN};
+
+    fn test_state() -> GameServerState {
+        GameServerState::with_bots(
+            YBotRegistry::new()
+                .with_bot(Arc::new(RandomBot))
+                .with_bot(Arc::new(MctsCompletoBot::new(DEFAULT_ANALYSIS_BOT_ID, 200))),
+        )
+    }
+
+    async fn get_json(app: axum::Router, uri: String, client: &str) -> (StatusCode, Value) {
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri(uri)
+                    .header("x-client-id", client)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn analyze_position_returns_statistics() {
+        let app = create_router(test_state());
+        let yen = YEN::new(3, 1, vec!['B', 'R'], "B/../...".to_string());
+        let uri = format!("/api/v1/analysis?position={}", yen.to_compact_string().unwrap());
+
+        let (status, json) = get_json(app, uri, "analysis").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["bot_id"], DEFAULT_ANALYSIS_BOT_ID);
+        assert_eq!(json["total_visits"], 200);
+        assert!(!json["moves"].as_array().unwrap().is_empty());
+        assert!(!json["principal_variation"].as_array().unwrap().is_empty());
+        assert_eq!(json["heatmap"].as_array().unwrap().len(), 6);
+        // La piedra de la esquina superior toca los lados B y C.
+        assert_eq!(json["connections"]["groups"][0]["sides"], serde_json::json!(["b", "c"]));
+        assert!(json["connections"]["threats"].as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn analyze_position_rejects_bot_without_analysis() {
+        let app = create_router(test_state());
+        let yen = YEN::new(3, 1, vec!['B', 'R'], "B/../...".to_string());
+        let uri = format!(
+            "/api/v1/analysis?position={}&bot_id=random_bot",
+            yen.to_compact_string().unwrap()
+        );
+
+        let (status, json) = get_json(app, uri, "analysis").await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(json["code"], "analysis_not_supported");
+    }
+
+    #[tokio::test]
+    async fn analyze_position_rejects_unknown_bot() {
+        let app = create_router(test_state());
+        let yen = YEN::new(3, 1, vec!['B', 'R'], "B/../...".to_string());
+        let uri = format!(
+            "/api/v1/analysis?position={}&bot_id=ghost_bot",
+            yen.to_compact_string().unwrap()
+        );
+
+        let (status, _) = get_json(app, uri, "analysis").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn analyze_hvb_game_uses_session_bot() {
+        let app = create_router(test_state());
+
+        let created = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/v1/hvb/games")
+                    .method("POST")
+                    .header("content-type", "application/json")
+                    .header("x-client-id", "analysis-owner")
+                    .body(Body::from(format!(
+                        r#"{{"size":3,"starter":"human","bot_id":"{DEFAULT_ANALYSIS_BOT_ID}"}}"#
+                    )))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = created.into_body().collect().await.unwrap().to_bytes();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        let game_id = json["game_id"].as_str().unwrap().to_string();
+
+        let (status, json) = get_json(
+            app.clone(),
+            format!("/api/v1/hvb/games/{game_id}/analysis"),
+            "analysis-owner",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["bot_id"], DEFAULT_ANALYSIS_BOT_ID);
+
+        let (status, _) = get_json(
+            app,
+            format!("/api/v1/hvb/games/{game_id}/analysis"),
+            "someone-else",
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
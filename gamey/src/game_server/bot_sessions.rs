@@ -0,0 +1,158 @@
+//! bot_sessions.rs
+//!
+//! Instancias de bot con estado por partida (`YBot::new_session`).
+//! Permiten que, por ejemplo, el MCTS conserve su árbol entre jugadas de la
+//! misma partida HvB en lugar de empezar desde cero en cada `bot-move`.
+//!
+//! Cada sesión puede guardar un árbol grande, así que el store admite como
+//! mucho `max_sessions` a la vez: al crear una más se descarta la usada hace
+//! más tiempo. Si esa partida vuelve a pedir jugada, su bot empieza una sesión
+//! nueva (pierde el árbol, no la partida).
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::RwLock;
+
+use crate::{BotSession, YBot};
+
+/// Sesiones de bot en memoria a la vez por defecto.
+pub const DEFAULT_MAX_BOT_SESSIONS: usize = 32;
+
+pub type SharedBotSession = Arc<Mutex<Box<dyn BotSession>>>;
+
+struct StoredSession {
+    session: SharedBotSession,
+    /// Valor del reloj del store en el último uso (para descartar la más antigua).
+    last_used: AtomicU64,
+}
+
+#[derive(Clone)]
+pub struct BotSessionStore {
+    inner: Arc<RwLock<HashMap<String, StoredSession>>>,
+    clock: Arc<AtomicU64>,
+    max_sessions: usize,
+}
+
+impl Default for BotSessionStore {
+    fn default() -> Self {
+        Self::with_max_sessions(DEFAULT_MAX_BOT_SESSIONS)
+    }
+}
+
+impl BotSessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_sessions(max_sessions: usize) -> Self {
+        Self {
+            inner: Arc::default(),
+            clock: Arc::default(),
+            max_sessions: max_sessions.max(1),
+        }
+    }
+
+    pub fn max_sessions(&self) -> usize {
+        self.max_sessions
+    }
+
+    /// Devuelve la sesión del bot para `game_id`, creándola si no existe (y
+    /// descartando la menos usada si el store está lleno).
+    /// `None` si el bot no tiene estado entre jugadas.
+    pub async fn get_or_create(&self, game_id: &str, bot: &dyn YBot) -> Option<SharedBotSession> {
+        if let Some(stored) = self.inner.read().await.get(game_id) {
+            stored.last_used.store(self.tick(), Ordering::Relaxed);
+            return Some(stored.session.clone());
+        }
+
+        let session = Arc::new(Mutex::new(bot.new_session()?));
+        let mut w = self.inner.write().await;
+        if !w.contains_key(game_id) && w.len() >= self.max_sessions {
+            let oldest = w
+                .iter()
+                .min_by_key(|(_, stored)| stored.last_used.load(Ordering::Relaxed))
+                .map(|(id, _)| id.clone());
+            if let Some(oldest) = oldest {
+                w.remove(&oldest);
+            }
+        }
+        let stored = w.entry(game_id.to_string()).or_insert_with(|| StoredSession {
+            session,
+            last_used: AtomicU64::new(0),
+        });
+        stored.last_used.store(self.tick(), Ordering::Relaxed);
+        Some(stored.session.clone())
+    }
+
+    pub async fn remove(&self, game_id: &str) {
+        self.inner.write().await.remove(game_id);
+    }
+
+    pub async fn contains(&self, game_id: &str) -> bool {
+        self.inner.read().await.contains_key(game_id)
+    }
+
+    pub async fn len(&self) -> usize {
+        self.inner.read().await.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.inner.read().await.is_empty()
+    }
+
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed) + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MctsCompletoBot, RandomBot};
+
+    #[tokio::test]
+    async fn get_or_create_reuses_existing_session() {
+        let store = BotSessionStore::new();
+        let bot = MctsCompletoBot::new("mcts", 10);
+
+        let first = store.get_or_create("game-1", &bot).await.unwrap();
+        let second = store.get_or_create("game-1", &bot).await.unwrap();
+
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(store.len().await, 1);
+    }
+
+    #[tokio::test]
+    async fn stateless_bot_has_no_session() {
+        let store = BotSessionStore::new();
+        assert!(store.get_or_create("game-1", &RandomBot).await.is_none());
+        assert!(store.is_empty().await);
+    }
+
+    #[tokio::test]
+    async fn full_store_evicts_least_recently_used_session() {
+        let store = BotSessionStore::with_max_sessions(2);
+        let bot = MctsCompletoBot::new("mcts", 10);
+        store.get_or_create("game-1", &bot).await.unwrap();
+        store.get_or_create("game-2", &bot).await.unwrap();
+        store.get_or_create("game-1", &bot).await.unwrap();
+
+        store.get_or_create("game-3", &bot).await.unwrap();
+
+        assert_eq!(store.len().await, 2);
+        assert!(store.contains("game-1").await);
+        assert!(!store.contains("game-2").await);
+        assert!(store.contains("game-3").await);
+    }
+
+    #[tokio::test]
+    async fn remove_drops_session() {
+        let store = BotSessionStore::new();
+        let bot = MctsCompletoBot::new("mcts", 10);
+        store.get_or_create("game-1", &bot).await.unwrap();
+
+        store.remove("game-1").await;
+        assert_eq!(store.len().await, 0);
+    }
+}
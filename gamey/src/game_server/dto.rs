@@ -6,27 +6,45 @@ use axum::{extract::State, Json};
 use serde::{Deserialize, Serialize};
 
 // use crate::{Coordinates, GameY, PlayerId, YEN};
-use crate::{Coordinates, YEN};
+use crate::{BotMetadata, Coordinates, YEN};
 use super::{API_V1, MAX_BOARD_SIZE, MIN_BOARD_SIZE};
 use super::state::GameServerState;
 
 /// Información que la UI necesita para construir el Home: límites + lista de bots.
+///
+/// `bots` (solo los ids) se mantiene por compatibilidad; `bot_details` añade la
+/// descripción, dificultad y capacidades de cada uno. Ambas listas siguen el
+/// orden de la configuración de bots.
 #[derive(Debug, Serialize)]
 pub struct MetaResponse {
     pub api_version: &'static str,
     pub min_board_size: u32,
     pub max_board_size: u32,
     pub bots: Vec<String>,
+    pub bot_details: Vec<BotInfo>,
+}
+
+/// Un bot disponible: su id (el `bot_id` de la API) y sus metadatos.
+#[derive(Debug, Serialize)]
+pub struct BotInfo {
+    pub id: String,
+    #[serde(flatten)]
+    pub metadata: BotMetadata,
 }
 
 /// GET /api/v1/meta
 pub async fn get_meta(State(state): State<GameServerState>) -> Json<MetaResponse> {
-    let bots = state.bots.names();
+    let registry = state.bots.snapshot();
+    let bot_details: Vec<BotInfo> = registry
+        .iter()
+        .map(|bot| BotInfo { id: bot.name().to_string(), metadata: bot.metadata() })
+        .collect();
     Json(MetaResponse {
         api_version: API_V1,
         min_board_size: MIN_BOARD_SIZE,
         max_board_size: MAX_BOARD_SIZE,
-        bots,
+        bots: bot_details.iter().map(|bot| bot.id.clone()).collect(),
+        bot_details,
     })
 }
 
@@ -56,22 +74,43 @@ pub enum HvHStarter {
 }
 
 /// Respuesta estándar de estado de juego (para HvH y HvB).
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct GameStateResponse {
     pub game_id: String,
     pub mode: GameMode,
     pub yen: YEN,
     pub status: GameStatus,
+    /// Asientos de una partida HvH online (se omite en el resto).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seats: Option<HvhSeatsInfo>,
 }
 
-#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+/// Asientos de una partida HvH online vistos por quien pregunta.
+#[derive(Debug, Clone, Serialize)]
+pub struct HvhSeatsInfo {
+    /// Código para unirse; solo lo ven los jugadores mientras falte el rival.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub join_code: Option<String>,
+    /// Si el asiento del jugador 0 y el del 1 están ocupados.
+    pub taken: [bool; 2],
+    /// Asiento de quien pregunta (`null` para espectadores).
+    pub your_seat: Option<u8>,
+}
+
+/// Body de `POST /api/v1/hvh/join`.
+#[derive(Debug, Deserialize)]
+pub struct JoinHvhRequest {
+    pub join_code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum GameMode {
     Hvh,
     Hvb,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(tag = "state", rename_all = "lowercase")]
 pub enum GameStatus {
     Ongoing { next: NextTurn },
@@ -168,6 +207,28 @@ mod tests {
         assert!(meta.bots.iter().any(|b| b == "random_bot"));
     }
 
+    #[tokio::test]
+    async fn get_meta_lists_bot_details_in_config_order() {
+        let Json(meta) = get_meta(State(GameServerState::new_default())).await;
+
+        let config_order: Vec<String> =
+            crate::BotRegistryConfig::default_config().bots.into_iter().map(|bot| bot.name).collect();
+        assert_eq!(meta.bots, config_order);
+        let detail_ids: Vec<&str> = meta.bot_details.iter().map(|bot| bot.id.as_str()).collect();
+        assert_eq!(detail_ids, meta.bots);
+
+        let random = &meta.bot_details[0];
+        assert_eq!(random.id, "random_bot");
+        assert_eq!(random.metadata.difficulty, Some(1));
+        assert!(!random.metadata.supports_analysis);
+
+        let json = serde_json::to_value(meta.bot_details.last().unwrap()).unwrap();
+        assert_eq!(json["id"], "solver");
+        assert_eq!(json["difficulty"], 5);
+        assert_eq!(json["supports_analysis"], true);
+        assert_eq!(json["rule_sets"], serde_json::json!(["standard", "swap"]));
+    }
+
     #[test]
     fn applied_move_new_builds_cell_and_coords() {
         let applied = AppliedMove::new(0, 2);
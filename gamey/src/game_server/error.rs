@@ -3,43 +3,68 @@
 //! Errores HTTP consistentes para el game_server.
 //! Evitamos depender de bot_server para mantener módulos desacoplados.
 
-use axum::{http::StatusCode, response::IntoResponse, Json};
+use axum::{http::{header, StatusCode}, response::IntoResponse, Json};
 use serde::Serialize;
 
 #[derive(Debug, Serialize, Clone)]
 pub struct ApiError {
     pub message: String,
     pub code: String,
+    /// Segundos tras los que tiene sentido reintentar (también como `Retry-After`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after_secs: Option<u64>,
 }
 
 impl ApiError {
     pub fn bad_request(message: impl Into<String>, code: impl Into<String>) -> (StatusCode, Json<ApiError>) {(
         StatusCode::BAD_REQUEST,
-        Json(ApiError { message: message.into(), code: code.into() }),
+        Json(ApiError { message: message.into(), code: code.into(), retry_after_secs: None }),
     )}
 
     pub fn not_found(message: impl Into<String>, code: impl Into<String>) -> (StatusCode, Json<ApiError>) {(
         StatusCode::NOT_FOUND,
-        Json(ApiError { message: message.into(), code: code.into() }),
+        Json(ApiError { message: message.into(), code: code.into(), retry_after_secs: None }),
+    )}
+
+    pub fn forbidden(message: impl Into<String>, code: impl Into<String>) -> (StatusCode, Json<ApiError>) {(
+        StatusCode::FORBIDDEN,
+        Json(ApiError { message: message.into(), code: code.into(), retry_after_secs: None }),
     )}
 
     pub fn conflict(message: impl Into<String>, code: impl Into<String>) -> (StatusCode, Json<ApiError>) {(
         StatusCode::CONFLICT,
-        Json(ApiError { message: message.into(), code: code.into() }),
+        Json(ApiError { message: message.into(), code: code.into(), retry_after_secs: None }),
     )}
 
     pub fn internal(message: impl Into<String>, code: impl Into<String>) -> (StatusCode, Json<ApiError>) {(
         StatusCode::INTERNAL_SERVER_ERROR,
-        Json(ApiError { message: message.into(), code: code.into() }),
+        Json(ApiError { message: message.into(), code: code.into(), retry_after_secs: None }),
+    )}
+
+    pub fn service_unavailable(
+        message: impl Into<String>,
+        code: impl Into<String>,
+        retry_after_secs: u64,
+    ) -> (StatusCode, Json<ApiError>) {(
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(ApiError { message: message.into(), code: code.into(), retry_after_secs: Some(retry_after_secs) }),
+    )}
+
+    pub fn gateway_timeout(message: impl Into<String>, code: impl Into<String>) -> (StatusCode, Json<ApiError>) {(
+        StatusCode::GATEWAY_TIMEOUT,
+        Json(ApiError { message: message.into(), code: code.into(), retry_after_secs: None }),
     )}
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ApiErrorResponse(pub StatusCode, pub ApiError);
 
 impl IntoResponse for ApiErrorResponse {
     fn into_response(self) -> axum::response::Response {
-        (self.0, Json(self.1)).into_response()
+        match self.1.retry_after_secs {
+            Some(secs) => (self.0, [(header::RETRY_AFTER, secs.to_string())], Json(self.1)).into_response(),
+            None => (self.0, Json(self.1)).into_response(),
+        }
     }
 }
 
@@ -54,6 +79,11 @@ impl ApiErrorResponse {
         Self(st, body)
     }
 
+    pub fn forbidden(message: impl Into<String>, code: impl Into<String>) -> Self {
+        let (st, Json(body)) = ApiError::forbidden(message, code);
+        Self(st, body)
+    }
+
     pub fn conflict(message: impl Into<String>, code: impl Into<String>) -> Self {
         let (st, Json(body)) = ApiError::conflict(message, code);
         Self(st, body)
@@ -63,6 +93,16 @@ impl ApiErrorResponse {
         let (st, Json(body)) = ApiError::internal(message, code);
         Self(st, body)
     }
+
+    pub fn service_unavailable(message: impl Into<String>, code: impl Into<String>, retry_after_secs: u64) -> Self {
+        let (st, Json(body)) = ApiError::service_unavailable(message, code, retry_after_secs);
+        Self(st, body)
+    }
+
+    pub fn gateway_timeout(message: impl Into<String>, code: impl Into<String>) -> Self {
+        let (st, Json(body)) = ApiError::gateway_timeout(message, code);
+        Self(st, body)
+    }
 }
 
 #[cfg(test)]
@@ -138,5 +178,13 @@ mod tests {
     fn into_response_preserves_status() {
         let response = ApiErrorResponse::bad_request("bad", "bad_code").into_response();
         assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(response.headers().get(header::RETRY_AFTER).is_none());
+    }
+
+    #[test]
+    fn service_unavailable_sets_retry_after() {
+        let response = ApiErrorResponse::service_unavailable("busy", "bots_busy", 2).into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "2");
     }
 }
\ No newline at end of file
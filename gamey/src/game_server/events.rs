@@ -0,0 +1,591 @@
+//! events.rs
+//!
+//! Eventos en directo de una partida con Server-Sent Events.
+//!
+//! `GET /api/v1/games/{game_id}/events` abre un stream SSE para quien pueda ver
+//! la partida (`SessionRole::Spectator`: el creador, los jugadores sentados y,
+//! en HvH online, cualquiera que conozca el `game_id`). El servidor es la autoridad:
+//! publica cada jugada en cuanto se aplica, así que los clientes no necesitan
+//! hacer polling de `GET .../games/{game_id}`.
+//!
+//! Eventos (`event:` = `type`, `data:` = JSON):
+//! - `snapshot`: estado completo y reloj; siempre es el primero del stream
+//! - `move`: jugada aplicada (quién, casilla, `yen` y estado resultantes)
+//! - `joined`: el rival ha ocupado su asiento (HvH online)
+//! - `clock`: tiempo de reflexión acumulado por jugador; tras cada jugada y
+//!   cada [`CLOCK_TICK`] mientras corre el reloj
+//! - `closed`: la partida se ha borrado; el stream termina
+//!
+//! En HvB el humano es el jugador 0 y el bot el 1.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures_util::stream::{self, Stream};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use uuid::Uuid;
+
+use crate::{Coordinates, YEN};
+
+use super::auth::resolve_principal;
+use super::dto::{GameMode, GameStateResponse, GameStatus};
+use super::error::ApiErrorResponse;
+use super::sessions::{GameSession, SessionRole, SessionStore};
+use super::state::GameServerState;
+
+/// Eventos que puede acumular un suscriptor lento antes de perder alguno.
+pub const EVENT_CHANNEL_CAPACITY: usize = 64;
+
+/// Cada cuánto se envía el reloj mientras corre.
+pub const CLOCK_TICK: Duration = Duration::from_secs(1);
+
+/// Tiempo de reflexión acumulado por cada jugador.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ClockSnapshot {
+    /// Milisegundos usados por el jugador 0 y el 1 (incluye el turno en curso).
+    pub elapsed_ms: [u64; 2],
+    /// Jugador cuyo reloj corre (`None` si la partida terminó).
+    pub running: Option<u8>,
+}
+
+#[derive(Debug)]
+struct GameClock {
+    elapsed: [Duration; 2],
+    running: Option<(u8, Instant)>,
+}
+
+impl GameClock {
+    fn new(turn: Option<u8>) -> Self {
+        Self {
+            elapsed: [Duration::ZERO; 2],
+            running: turn.map(|player| (player, Instant::now())),
+        }
+    }
+
+    /// Para el reloj del jugador en turno y arranca el de `next`.
+    fn switch_to(&mut self, next: Option<u8>) {
+        if let Some((player, since)) = self.running.take() {
+            self.elapsed[player as usize % 2] += since.elapsed();
+        }
+        self.running = next.map(|player| (player, Instant::now()));
+    }
+
+    fn snapshot(&self) -> ClockSnapshot {
+        let mut elapsed = self.elapsed;
+        if let Some((player, since)) = self.running {
+            elapsed[player as usize % 2] += since.elapsed();
+        }
+        ClockSnapshot {
+            elapsed_ms: elapsed.map(|d| d.as_millis() as u64),
+            running: self.running.map(|(player, _)| player),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GameEvent {
+    Snapshot {
+        #[serde(flatten)]
+        state: GameStateResponse,
+        clock: ClockSnapshot,
+    },
+    Move {
+        game_id: String,
+        player: u8,
+        cell_id: u32,
+        coords: Coordinates,
+        yen: YEN,
+        status: GameStatus,
+    },
+    Clock {
+        game_id: String,
+        clock: ClockSnapshot,
+    },
+    /// Un jugador ha ocupado su asiento en una partida HvH online.
+    Joined {
+        game_id: String,
+        seat: u8,
+    },
+    Closed {
+        game_id: String,
+    },
+}
+
+impl GameEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameEvent::Snapshot { .. } => "snapshot",
+            GameEvent::Move { .. } => "move",
+            GameEvent::Clock { .. } => "clock",
+            GameEvent::Joined { .. } => "joined",
+            GameEvent::Closed { .. } => "closed",
+        }
+    }
+
+    fn to_sse(&self) -> Event {
+        Event::default()
+            .event(self.name())
+            .json_data(self)
+            .unwrap_or_else(|_| Event::default().comment("unserializable event"))
+    }
+}
+
+struct GameChannel {
+    sender: broadcast::Sender<GameEvent>,
+    clock: GameClock,
+}
+
+impl GameChannel {
+    fn new(turn: Option<u8>) -> Self {
+        Self {
+            sender: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+            clock: GameClock::new(turn),
+        }
+    }
+}
+
+/// Canales de eventos y relojes de las partidas, por `game_id`.
+#[derive(Clone, Default)]
+pub struct GameEventHub {
+    games: Arc<Mutex<HashMap<String, GameChannel>>>,
+}
+
+impl GameEventHub {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn games(&self) -> MutexGuard<'_, HashMap<String, GameChannel>> {
+        self.games.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Abre el canal de una partida nueva y arranca el reloj de `turn`.
+    pub fn start(&self, game_id: &str, turn: Option<u8>) {
+        self.games().insert(game_id.to_string(), GameChannel::new(turn));
+    }
+
+    /// Publica una jugada de `player` ya guardada. `state` es el estado tras
+    /// la jugada y `next` el jugador al que le toca (`None` si terminó).
+    /// No hace nada si la partida no tiene canal (ya se borró).
+    pub fn publish_move(&self, player: u8, cell_id: u32, state: &GameStateResponse, next: Option<u8>) {
+        let mut games = self.games();
+        let Some(channel) = games.get_mut(&state.game_id) else {
+            return;
+        };
+        channel.clock.switch_to(next);
+
+        // Sin suscriptores `send` falla: no es un error.
+        let _ = channel.sender.send(GameEvent::Move {
+            game_id: state.game_id.clone(),
+            player,
+            cell_id,
+            coords: Coordinates::from_index(cell_id, state.yen.size()),
+            yen: state.yen.clone(),
+            status: state.status.clone(),
+        });
+        let _ = channel.sender.send(GameEvent::Clock {
+            game_id: state.game_id.clone(),
+            clock: channel.clock.snapshot(),
+        });
+    }
+
+    /// Publica que alguien se ha sentado en `seat`. Si el reloj estaba parado
+    /// (partida online esperando al rival), arranca el de `turn`.
+    pub fn publish_joined(&self, game_id: &str, seat: u8, turn: Option<u8>) {
+        let mut games = self.games();
+        let Some(channel) = games.get_mut(game_id) else {
+            return;
+        };
+        if channel.clock.running.is_none() {
+            channel.clock.switch_to(turn);
+        }
+
+        let _ = channel.sender.send(GameEvent::Joined { game_id: game_id.to_string(), seat });
+        let _ = channel.sender.send(GameEvent::Clock {
+            game_id: game_id.to_string(),
+            clock: channel.clock.snapshot(),
+        });
+    }
+
+    /// Se suscribe a los eventos de la partida; `None` si no tiene canal
+    /// (no existe o ya se borró).
+    pub fn subscribe(&self, game_id: &str) -> Option<broadcast::Receiver<GameEvent>> {
+        self.games().get(game_id).map(|channel| channel.sender.subscribe())
+    }
+
+    /// Abre el canal de cada partida de `sessions` que no lo tenga (p. ej. las
+    /// recuperadas de disco al arrancar), con el reloj a cero para quien juega.
+    pub async fn start_existing(&self, sessions: &dyn SessionStore) {
+        for summary in sessions.summaries().await {
+            let Some(session) = sessions.get(&summary.game_id).await else {
+                continue;
+            };
+            let (_, turn) = current_state(&summary.game_id, &session);
+            self.games().entry(summary.game_id).or_insert_with(|| GameChannel::new(turn));
+        }
+    }
+
+    pub fn clock(&self, game_id: &str) -> Option<ClockSnapshot> {
+        self.games().get(game_id).map(|channel| channel.clock.snapshot())
+    }
+
+    /// Suscriptores conectados a la partida.
+    pub fn subscribers(&self, game_id: &str) -> usize {
+        self.games().get(game_id).map_or(0, |channel| channel.sender.receiver_count())
+    }
+
+    /// Avisa de que la partida se ha borrado y cierra su canal.
+    pub fn close(&self, game_id: &str) {
+        if let Some(channel) = self.games().remove(game_id) {
+            let _ = channel.sender.send(GameEvent::Closed { game_id: game_id.to_string() });
+        }
+    }
+}
+
+/// Estado de la partida tal y como lo devuelve `GET` en su modo, y jugador en turno.
+fn current_state(game_id: &str, session: &GameSession) -> (GameStateResponse, Option<u8>) {
+    let finished = session.game.check_game_over();
+    match session.mode {
+        GameMode::Hvb => {
+            let turn = session.hvb_next_is_human.map(|human| if human { 0 } else { 1 });
+            let state = super::hvb::hvb_state_response(game_id.to_string(), session);
+            (state, turn.filter(|_| !finished))
+        }
+        GameMode::Hvh => {
+            let state = super::hvh::hvh_state_response(game_id.to_string(), session, None);
+            (state, session.hvh_next_player.filter(|_| !finished))
+        }
+    }
+}
+
+struct EventStream {
+    game_id: String,
+    hub: GameEventHub,
+    first: Option<GameEvent>,
+    receiver: broadcast::Receiver<GameEvent>,
+    ticker: tokio::time::Interval,
+    closed: bool,
+}
+
+impl EventStream {
+    async fn next_event(&mut self) -> Option<GameEvent> {
+        if let Some(first) = self.first.take() {
+            return Some(first);
+        }
+        loop {
+            if self.closed {
+                return None;
+            }
+            tokio::select! {
+                received = self.receiver.recv() => match received {
+                    Ok(event) => {
+                        self.closed = matches!(event, GameEvent::Closed { .. });
+                        return Some(event);
+                    }
+                    // Un cliente lento pierde eventos intermedios; el siguiente
+                    // `move` trae el `yen` completo.
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                },
+                _ = self.ticker.tick() => {
+                    if let Some(clock) = self.hub.clock(&self.game_id).filter(|clock| clock.running.is_some()) {
+                        return Some(GameEvent::Clock { game_id: self.game_id.clone(), clock });
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// GET /api/v1/games/{game_id}/events
+///
+/// Stream SSE con los eventos de la partida (HvH o HvB) para quien pueda verla.
+pub async fn game_events(
+    State(state): State<GameServerState>,
+    headers: HeaderMap,
+    Path(game_id): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiErrorResponse> {
+    let principal = resolve_principal(&headers);
+    let game_id = Uuid::parse_str(&game_id)
+        .map(|u| u.to_string())
+        .map_err(|_| ApiErrorResponse::bad_request("Invalid game_id", "invalid_game_id"))?;
+    let session = state
+        .sessions
+        .assert_role(&principal, &game_id, SessionRole::Spectator)
+        .await
+        .map_err(|_| ApiErrorResponse::not_found("Game not found", "game_not_found"))?;
+
+    // Primero la suscripción y luego el estado: una jugada intermedia puede
+    // llegar dos veces (en el `snapshot` y como `move`), pero no perderse.
+    let (_, turn) = current_state(&game_id, &session);
+    let receiver = state
+        .events
+        .subscribe(&game_id)
+        .ok_or_else(|| ApiErrorResponse::not_found("Game not found", "game_not_found"))?;
+    let session = state.sessions.get(&game_id).await.unwrap_or(session);
+    let (current, _) = current_state(&game_id, &session);
+    let clock = state.events.clock(&game_id).unwrap_or(ClockSnapshot { elapsed_ms: [0, 0], running: turn });
+    let first = GameEvent::Snapshot { state: current, clock };
+
+    let mut ticker = tokio::time::interval(CLOCK_TICK);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    ticker.reset();
+
+    let events = EventStream {
+        game_id,
+        hub: state.events.clone(),
+        first: Some(first),
+        receiver,
+        ticker,
+        closed: false,
+    };
+    let stream = stream::unfold(events, |mut events| async move {
+        let event = events.next_event().await?;
+        Some((Ok(event.to_sse()), events))
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::GameY;
+    use crate::game_server::dto::{GameConfig, HvBStarter, HvHStarter, NextTurn};
+
+    fn state_after_move(game_id: &str) -> GameStateResponse {
+        GameStateResponse {
+            game_id: game_id.to_string(),
+            mode: GameMode::Hvh,
+            yen: YEN::from(&GameY::new(3)),
+            status: GameStatus::Ongoing { next: NextTurn::Player1 },
+            seats: None,
+        }
+    }
+
+    fn hvh_session() -> GameSession {
+        GameSession {
+            owner_key: "guest:owner".to_string(),
+            mode: GameMode::Hvh,
+            config: GameConfig {
+                size: 3,
+                hvb_starter: HvBStarter::Human,
+                hvh_starter: Some(HvHStarter::Player0),
+                bot_id: None,
+            },
+            game: GameY::new(3),
+            bot_id: None,
+            hvb_next_is_human: None,
+            hvb_winner: None,
+            hvb_seed: None,
+            hvh_next_player: Some(0),
+            hvh_winner: None,
+            hvh_seats: None,
+            last_activity: std::time::SystemTime::now(),
+        }
+    }
+
+    #[test]
+    fn clock_accumulates_per_player() {
+        let mut clock = GameClock::new(Some(0));
+        std::thread::sleep(Duration::from_millis(20));
+        clock.switch_to(Some(1));
+
+        let snapshot = clock.snapshot();
+        assert!(snapshot.elapsed_ms[0] >= 20);
+        assert_eq!(snapshot.running, Some(1));
+
+        clock.switch_to(None);
+        let stopped = clock.snapshot();
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(clock.snapshot(), stopped);
+        assert_eq!(stopped.running, None);
+    }
+
+    #[tokio::test]
+    async fn subscribers_receive_moves_clock_and_close() {
+        let hub = GameEventHub::new();
+        hub.start("game", Some(0));
+
+        let mut receiver = hub.subscribe("game").unwrap();
+        assert_eq!(hub.subscribers("game"), 1);
+        assert_eq!(hub.clock("game").unwrap().running, Some(0));
+
+        hub.publish_move(0, 4, &state_after_move("game"), Some(1));
+        let GameEvent::Move { player, cell_id, coords, .. } = receiver.recv().await.unwrap() else {
+            panic!("expected a move event");
+        };
+        assert_eq!((player, cell_id), (0, 4));
+        assert_eq!(coords, Coordinates::from_index(4, 3));
+        let GameEvent::Clock { clock, .. } = receiver.recv().await.unwrap() else {
+            panic!("expected a clock event");
+        };
+        assert_eq!(clock.running, Some(1));
+
+        hub.close("game");
+        assert_eq!(receiver.recv().await.unwrap().name(), "closed");
+        assert!(hub.clock("game").is_none());
+    }
+
+    #[tokio::test]
+    async fn deleted_games_get_no_channel_back() {
+        let hub = GameEventHub::new();
+        hub.start("game", Some(0));
+        hub.close("game");
+
+        hub.publish_move(0, 4, &state_after_move("game"), Some(1));
+        hub.publish_joined("game", 1, Some(0));
+        assert!(hub.subscribe("game").is_none());
+        assert!(hub.clock("game").is_none());
+    }
+
+    #[tokio::test]
+    async fn start_existing_opens_channels_for_stored_games() {
+        use crate::game_server::sessions::MemorySessionStore;
+
+        let sessions = MemorySessionStore::new();
+        sessions.insert("game".to_string(), hvh_session()).await;
+        let hub = GameEventHub::new();
+
+        hub.start_existing(&sessions).await;
+        assert!(hub.subscribe("game").is_some());
+        assert_eq!(hub.clock("game").unwrap().running, Some(0));
+    }
+
+    #[test]
+    fn events_serialize_with_their_type() {
+        let event = GameEvent::Snapshot {
+            state: state_after_move("game"),
+            clock: ClockSnapshot { elapsed_ms: [1, 2], running: Some(1) },
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "snapshot");
+        assert_eq!(json["game_id"], "game");
+        assert_eq!(json["clock"]["elapsed_ms"], serde_json::json!([1, 2]));
+    }
+
+    /// Lee el siguiente evento SSE del cuerpo de la respuesta (saltando keep-alives).
+    async fn next_sse(body: &mut axum::body::Body) -> (String, serde_json::Value) {
+        use http_body_util::BodyExt;
+        loop {
+            let frame = body.frame().await.unwrap().unwrap();
+            let Ok(data) = frame.into_data() else { continue };
+            let text = String::from_utf8(data.to_vec()).unwrap();
+            let event = text.lines().find_map(|line| line.strip_prefix("event: "));
+            let data = text.lines().find_map(|line| line.strip_prefix("data: "));
+            if let (Some(event), Some(data)) = (event, data) {
+                return (event.to_string(), serde_json::from_str(data).unwrap());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn sse_stream_pushes_hvh_moves() {
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let state = GameServerState::new_default();
+        let app = crate::game_server::create_router(state.clone());
+
+        let created = app
+            .clone()
+            .oneshot(
+                Request::post("/api/v1/hvh/games")
+                    .header("x-client-id", "sse-player")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = http_body_util::BodyExt::collect(created.into_body()).await.unwrap().to_bytes();
+        let game_id = serde_json::from_slice::<serde_json::Value>(&body).unwrap()["game_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        // Partida local: solo la ve su creador.
+        let response = app
+            .clone()
+            .oneshot(
+                Request::get(format!("/api/v1/games/{game_id}/events"))
+                    .header("x-client-id", "sse-player")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+        let mut events = response.into_body();
+
+        let (name, snapshot) = next_sse(&mut events).await;
+        assert_eq!(name, "snapshot");
+        assert_eq!(snapshot["game_id"], game_id.as_str());
+
+        let moved = app
+            .oneshot(
+                Request::post(format!("/api/v1/hvh/games/{game_id}/moves"))
+                    .header("x-client-id", "sse-player")
+                    .header("content-type", "application/json")
+                    .body(axum::body::Body::from(r#"{ "cell_id": 0 }"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(moved.status(), axum::http::StatusCode::OK);
+
+        let (name, event) = next_sse(&mut events).await;
+        assert_eq!(name, "move");
+        assert_eq!(event["cell_id"], 0);
+        let (name, _) = next_sse(&mut events).await;
+        assert_eq!(name, "clock");
+    }
+
+    #[tokio::test]
+    async fn sse_stream_is_not_found_for_strangers_of_hvb_games() {
+        use axum::http::{Request, StatusCode};
+        use tower::ServiceExt;
+
+        let app = crate::game_server::create_router(GameServerState::new_default());
+
+        let created = app
+            .clone()
+            .oneshot(
+                Request::post("/api/v1/hvb/games")
+                    .header("x-client-id", "sse-owner")
+                    .header("content-type", "application/json")
+                    .body(axum::body::Body::from(r#"{ "size": 3, "starter": "human", "bot_id": "random_bot" }"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(created.status(), StatusCode::OK);
+        let body = http_body_util::BodyExt::collect(created.into_body()).await.unwrap().to_bytes();
+        let game_id = serde_json::from_slice::<serde_json::Value>(&body).unwrap()["game_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let events = |client_id: &'static str| {
+            Request::get(format!("/api/v1/games/{game_id}/events"))
+                .header("x-client-id", client_id)
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+        let stranger = app.clone().oneshot(events("sse-stranger")).await.unwrap();
+        assert_eq!(stranger.status(), StatusCode::NOT_FOUND);
+
+        let owner = app.oneshot(events("sse-owner")).await.unwrap();
+        assert_eq!(owner.headers()["content-type"], "text/event-stream");
+    }
+}
@@ -0,0 +1,648 @@
+//! file_sessions.rs
+//!
+//! Partidas guardadas en disco para que sobrevivan a reinicios y despliegues.
+//!
+//! `FileSessionStore` trabaja en memoria (`MemorySessionStore`) y además añade
+//! cada cambio a un fichero JSON Lines:
+//!
+//! ```text
+//! {"op":"put","game_id":"…","session":{…,"yen":{…},"history":[…]}}
+//! {"op":"remove","game_id":"…"}
+//! ```
+//!
+//! Cada `put` lleva la partida entera: la posición en YEN y el historial de
+//! jugadas. Al recuperarla se reproduce el historial (el YEN solo no conserva
+//! el orden de las jugadas) y se comprueba que llega a la misma posición. Una
+//! línea ocupa como mucho lo que un tablero lleno, así que una partida de `n`
+//! jugadas escribe del orden de `n²` jugadas en total; la compactación lo
+//! acota.
+//!
+//! Al arrancar se lee el fichero y se reescribe con una línea por partida; en
+//! marcha se compacta igual cuando las líneas superan en mucho a las partidas.
+//! Una línea que no se puede leer (p. ej. el servidor murió mientras la
+//! escribía) se ignora con un aviso.
+//!
+//! Escribe el fichero un único hilo, fuera de los hilos de Tokio. Cada cambio
+//! se aplica en memoria y se encola su línea en el mismo paso, de modo que el
+//! fichero sigue el orden de los cambios. La petición espera a que se escriba
+//! su línea, pero las demás solo esperan a que se encole.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, oneshot};
+
+use crate::{Coordinates, GameAction, GameY, GameYError, Movement, PlayerId, YEN};
+
+use super::dto::{GameConfig, GameMode, Winner};
+use super::sessions::{
+    GameLimitReached, GameSession, HvhSeats, JoinError, MemorySessionStore, SessionStore, SessionSummary,
+};
+
+/// Líneas a partir de las cuales se puede compactar en marcha.
+pub const COMPACT_MIN_RECORDS: usize = 1024;
+
+/// Se compacta cuando hay más de estas líneas por partida guardada.
+const COMPACT_RATIO: usize = 4;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum StoredMove {
+    Placement { player: u32, cell_id: u32 },
+    Swap { player: u32 },
+    Resign { player: u32 },
+}
+
+impl StoredMove {
+    fn from_movement(movement: &Movement, size: u32) -> Self {
+        match movement {
+            Movement::Placement { player, coords } => StoredMove::Placement {
+                player: player.id(),
+                cell_id: coords.to_index(size),
+            },
+            Movement::Action { player, action } => match action {
+                GameAction::Swap => StoredMove::Swap { player: player.id() },
+                GameAction::Resign => StoredMove::Resign { player: player.id() },
+            },
+        }
+    }
+
+    fn to_movement(&self, size: u32) -> Movement {
+        match *self {
+            StoredMove::Placement { player, cell_id } => Movement::Placement {
+                player: PlayerId::new(player),
+                coords: Coordinates::from_index(cell_id, size),
+            },
+            StoredMove::Swap { player } => Movement::Action {
+                player: PlayerId::new(player),
+                action: GameAction::Swap,
+            },
+            StoredMove::Resign { player } => Movement::Action {
+                player: PlayerId::new(player),
+                action: GameAction::Resign,
+            },
+        }
+    }
+}
+
+/// `GameSession` tal y como se escribe en el fichero.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredSession {
+    owner_key: String,
+    mode: GameMode,
+    config: GameConfig,
+    bot_id: Option<String>,
+    hvb_next_is_human: Option<bool>,
+    hvb_winner: Option<Winner>,
+    hvb_seed: Option<u64>,
+    hvh_next_player: Option<u8>,
+    hvh_winner: Option<u8>,
+    hvh_seats: Option<HvhSeats>,
+    /// Milisegundos desde 1970 (falta en ficheros anteriores: se toma "ahora").
+    last_activity_ms: Option<u64>,
+    yen: YEN,
+    history: Vec<StoredMove>,
+}
+
+impl From<&GameSession> for StoredSession {
+    fn from(session: &GameSession) -> Self {
+        let size = session.game.board_size();
+        StoredSession {
+            owner_key: session.owner_key.clone(),
+            mode: session.mode,
+            config: session.config.clone(),
+            bot_id: session.bot_id.clone(),
+            hvb_next_is_human: session.hvb_next_is_human,
+            hvb_winner: session.hvb_winner,
+            hvb_seed: session.hvb_seed,
+            hvh_next_player: session.hvh_next_player,
+            hvh_winner: session.hvh_winner,
+            hvh_seats: session.hvh_seats.clone(),
+            last_activity_ms: session
+                .last_activity
+                .duration_since(UNIX_EPOCH)
+                .ok()
+                .map(|elapsed| elapsed.as_millis() as u64),
+            yen: YEN::from(&session.game),
+            history: session.game.history().iter().map(|m| StoredMove::from_movement(m, size)).collect(),
+        }
+    }
+}
+
+impl StoredSession {
+    /// Reconstruye la partida con el historial; si no llega a la posición del
+    /// YEN (fichero editado a mano, por ejemplo), se usa el YEN.
+    fn restore_game(&self) -> Result<GameY, GameYError> {
+        let size = self.yen.size();
+        let mut game = GameY::new(size);
+        let replayed = self
+            .history
+            .iter()
+            .try_for_each(|stored| game.add_move(stored.to_movement(size)));
+        if replayed.is_ok() && YEN::from(&game).layout() == self.yen.layout() {
+            return Ok(game);
+        }
+        GameY::try_from(self.yen.clone())
+    }
+
+    fn into_session(self) -> Result<GameSession, GameYError> {
+        let game = self.restore_game()?;
+        Ok(GameSession {
+            owner_key: self.owner_key,
+            mode: self.mode,
+            config: self.config,
+            game,
+            bot_id: self.bot_id,
+            hvb_next_is_human: self.hvb_next_is_human,
+            hvb_winner: self.hvb_winner,
+            hvb_seed: self.hvb_seed,
+            hvh_next_player: self.hvh_next_player,
+            hvh_winner: self.hvh_winner,
+            hvh_seats: self.hvh_seats,
+            last_activity: self
+                .last_activity_ms
+                .map_or_else(SystemTime::now, |ms| UNIX_EPOCH + Duration::from_millis(ms)),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum LogRecord {
+    Put { game_id: String, session: Box<StoredSession> },
+    Remove { game_id: String },
+}
+
+impl LogRecord {
+    fn put(game_id: &str, session: &GameSession) -> Self {
+        LogRecord::Put { game_id: game_id.to_string(), session: Box::new(StoredSession::from(session)) }
+    }
+}
+
+fn io_error(message: String, error: io::Error) -> GameYError {
+    GameYError::IoError { message, error: error.to_string() }
+}
+
+/// Lee las partidas del fichero (vacío si no existe).
+fn replay(path: &Path) -> Result<HashMap<String, GameSession>, GameYError> {
+    let mut sessions = HashMap::new();
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(sessions),
+        Err(e) => return Err(io_error(format!("Failed to read {}", path.display()), e)),
+    };
+
+    for (number, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| io_error(format!("Failed to read {}", path.display()), e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<LogRecord>(&line) {
+            Ok(LogRecord::Put { game_id, session }) => match (*session).into_session() {
+                Ok(session) => {
+                    sessions.insert(game_id, session);
+                }
+                Err(e) => tracing::warn!("{}:{}: skipping game {}: {}", path.display(), number + 1, game_id, e),
+            },
+            Ok(LogRecord::Remove { game_id }) => {
+                sessions.remove(&game_id);
+            }
+            Err(e) => tracing::warn!("{}:{}: skipping unreadable line: {}", path.display(), number + 1, e),
+        }
+    }
+    Ok(sessions)
+}
+
+struct SessionLog {
+    path: PathBuf,
+    file: File,
+}
+
+impl SessionLog {
+    fn append(&mut self, record: &LogRecord) -> io::Result<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        self.file.write_all(line.as_bytes())
+    }
+
+    /// Sustituye el fichero por una línea por partida (vía fichero temporal,
+    /// para no perder nada si el proceso muere a medias).
+    fn rewrite(&mut self, sessions: &[(String, GameSession)]) -> io::Result<()> {
+        let tmp = self.path.with_extension("tmp");
+        let mut out = BufWriter::new(File::create(&tmp)?);
+        for (game_id, session) in sessions {
+            serde_json::to_writer(&mut out, &LogRecord::put(game_id, session))?;
+            out.write_all(b"\n")?;
+        }
+        out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        fs::rename(&tmp, &self.path)?;
+
+        self.file = OpenOptions::new().append(true).open(&self.path)?;
+        Ok(())
+    }
+}
+
+/// Lo que se pide al hilo que escribe el fichero.
+enum LogAction {
+    Append(LogRecord),
+    Rewrite(Vec<(String, GameSession)>),
+}
+
+struct LogJob {
+    action: LogAction,
+    /// Recibe el resultado cuando la acción llega al fichero.
+    done: oneshot::Sender<io::Result<()>>,
+}
+
+/// Arranca el hilo que escribe en `log`, en el orden en que se encolan los
+/// trabajos. Termina cuando se suelta el `Sender` devuelto.
+fn spawn_writer(mut log: SessionLog) -> io::Result<mpsc::Sender<LogJob>> {
+    let (jobs, queue) = mpsc::channel::<LogJob>();
+    std::thread::Builder::new().name("session-log".to_string()).spawn(move || {
+        for job in queue {
+            let result = match job.action {
+                LogAction::Append(record) => log.append(&record),
+                LogAction::Rewrite(entries) => log.rewrite(&entries),
+            };
+            let _ = job.done.send(result);
+        }
+    })?;
+    Ok(jobs)
+}
+
+/// Espera a que se escriba un trabajo encolado.
+async fn written(done: oneshot::Receiver<io::Result<()>>) -> io::Result<()> {
+    done.await.unwrap_or_else(|_| Err(io::Error::other("the session log writer stopped")))
+}
+
+/// Partidas en memoria respaldadas por un fichero JSON Lines.
+pub struct FileSessionStore {
+    memory: MemorySessionStore,
+    path: PathBuf,
+    /// Se toma para cambiar `memory` y encolar la línea del cambio, para que
+    /// el orden del fichero sea el de los cambios; no se mantiene mientras se
+    /// escribe. Guarda las líneas que tendrá el fichero con lo ya encolado.
+    order: Mutex<usize>,
+    /// Cola del hilo que escribe el fichero.
+    log: mpsc::Sender<LogJob>,
+}
+
+impl FileSessionStore {
+    /// Abre (o crea) el fichero `path` y recupera sus partidas.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, GameYError> {
+        let path = path.into();
+        let context = |action: &str| format!("Failed to {action} {}", path.display());
+
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|e| io_error(context("create the directory of"), e))?;
+        }
+        let sessions = replay(&path)?;
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| io_error(context("open"), e))?;
+        let mut log = SessionLog { path: path.clone(), file };
+        let entries: Vec<(String, GameSession)> =
+            sessions.iter().map(|(game_id, session)| (game_id.clone(), session.clone())).collect();
+        log.rewrite(&entries).map_err(|e| io_error(context("compact"), e))?;
+        let log = spawn_writer(log).map_err(|e| io_error(context("start the writer of"), e))?;
+
+        Ok(Self {
+            memory: MemorySessionStore::from_sessions(sessions),
+            path,
+            order: Mutex::new(entries.len()),
+            log,
+        })
+    }
+
+    /// Encola `action` para el hilo escritor. `order` es el contenido del
+    /// cerrojo `order`, ya tomado; se actualiza con las líneas que tendrá el
+    /// fichero.
+    fn enqueue(&self, order: &mut usize, action: LogAction) -> oneshot::Receiver<io::Result<()>> {
+        *order = match &action {
+            LogAction::Append(_) => *order + 1,
+            LogAction::Rewrite(entries) => entries.len(),
+        };
+        let (done, receiver) = oneshot::channel();
+        // Si el hilo ya no está, `done` se suelta y `written` da el error.
+        let _ = self.log.send(LogJob { action, done });
+        receiver
+    }
+
+    /// Línea con la partida tal como ha quedado en memoria (con el
+    /// `last_activity` que le puso `memory`). Se llama con `order` tomado,
+    /// justo después de guardarla.
+    async fn stored_record(&self, game_id: &str) -> LogRecord {
+        let session = self.memory.get(game_id).await.expect("the game was just stored");
+        LogRecord::put(game_id, &session)
+    }
+
+    /// Espera a que se escriba la línea de un cambio y compacta el fichero si
+    /// ha crecido demasiado. Un fallo de disco no interrumpe la partida: se
+    /// registra y se sigue en memoria.
+    async fn finish(&self, done: oneshot::Receiver<io::Result<()>>) {
+        if let Err(e) = written(done).await {
+            tracing::error!("failed to persist session in {}: {}", self.path.display(), e);
+            return;
+        }
+
+        let compacted = {
+            let mut order = self.order.lock().await;
+            let live = self.memory.count().await;
+            if *order <= COMPACT_MIN_RECORDS.max(COMPACT_RATIO * live) {
+                return;
+            }
+            let entries = self.memory.entries().await;
+            self.enqueue(&mut order, LogAction::Rewrite(entries))
+        };
+        if let Err(e) = written(compacted).await {
+            tracing::error!("failed to compact {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+impl SessionStore for FileSessionStore {
+    fn insert(&self, game_id: String, session: GameSession) -> BoxFuture<'_, ()> {
+        Box::pin(async move {
+            let done = {
+                let mut order = self.order.lock().await;
+                self.memory.insert(game_id.clone(), session).await;
+                let record = self.stored_record(&game_id).await;
+                self.enqueue(&mut order, LogAction::Append(record))
+            };
+            self.finish(done).await;
+        })
+    }
+
+    fn insert_limited(
+        &self,
+        game_id: String,
+        session: GameSession,
+        max: usize,
+    ) -> BoxFuture<'_, Result<(), GameLimitReached>> {
+        Box::pin(async move {
+            let done = {
+                let mut order = self.order.lock().await;
+                self.memory.insert_limited(game_id.clone(), session, max).await?;
+                let record = self.stored_record(&game_id).await;
+                self.enqueue(&mut order, LogAction::Append(record))
+            };
+            self.finish(done).await;
+            Ok(())
+        })
+    }
+
+    fn get<'a>(&'a self, game_id: &'a str) -> BoxFuture<'a, Option<GameSession>> {
+        self.memory.get(game_id)
+    }
+
+    fn remove<'a>(&'a self, game_id: &'a str) -> BoxFuture<'a, Option<GameSession>> {
+        Box::pin(async move {
+            let mut order = self.order.lock().await;
+            let removed = self.memory.remove(game_id).await;
+            if removed.is_some() {
+                let record = LogRecord::Remove { game_id: game_id.to_string() };
+                let done = self.enqueue(&mut order, LogAction::Append(record));
+                drop(order);
+                self.finish(done).await;
+            }
+            removed
+        })
+    }
+
+    fn update<'a>(&'a self, game_id: &'a str, session: GameSession) -> BoxFuture<'a, Result<(), ()>> {
+        Box::pin(async move {
+            let done = {
+                let mut order = self.order.lock().await;
+                self.memory.update(game_id, session).await?;
+                let record = self.stored_record(game_id).await;
+                self.enqueue(&mut order, LogAction::Append(record))
+            };
+            self.finish(done).await;
+            Ok(())
+        })
+    }
+
+    fn join<'a>(
+        &'a self,
+        join_code: &'a str,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<(String, GameSession, u8), JoinError>> {
+        Box::pin(async move {
+            let (joined, done) = {
+                let mut order = self.order.lock().await;
+                let (game_id, session, seat) = self.memory.join(join_code, key).await?;
+                let done = self.enqueue(&mut order, LogAction::Append(LogRecord::put(&game_id, &session)));
+                ((game_id, session, seat), done)
+            };
+            self.finish(done).await;
+            Ok(joined)
+        })
+    }
+
+    fn join_code_in_use<'a>(&'a self, join_code: &'a str) -> BoxFuture<'a, bool> {
+        self.memory.join_code_in_use(join_code)
+    }
+
+    fn count(&self) -> BoxFuture<'_, usize> {
+        self.memory.count()
+    }
+
+    fn summaries(&self) -> BoxFuture<'_, Vec<SessionSummary>> {
+        self.memory.summaries()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game_server::dto::{HvBStarter, HvHStarter};
+
+    fn session_with_moves(cells: &[u32]) -> GameSession {
+        let mut game = GameY::new(4);
+        for (turn, cell_id) in cells.iter().enumerate() {
+            game.add_move(Movement::Placement {
+                player: PlayerId::new(turn as u32 % 2),
+                coords: Coordinates::from_index(*cell_id, 4),
+            })
+            .unwrap();
+        }
+        GameSession {
+            owner_key: "guest:owner".to_string(),
+            mode: GameMode::Hvb,
+            config: GameConfig {
+                size: 4,
+                hvb_starter: HvBStarter::Human,
+                hvh_starter: Some(HvHStarter::Player0),
+                bot_id: Some("random_bot".to_string()),
+            },
+            game,
+            bot_id: Some("random_bot".to_string()),
+            hvb_next_is_human: Some(cells.len().is_multiple_of(2)),
+            hvb_winner: None,
+            hvb_seed: Some(7),
+            hvh_next_player: None,
+            hvh_winner: None,
+            hvh_seats: None,
+            last_activity: SystemTime::now(),
+        }
+    }
+
+    fn cells_in_order(session: &GameSession) -> Vec<u32> {
+        session
+            .game
+            .history()
+            .iter()
+            .filter_map(|m| match m {
+                Movement::Placement { coords, .. } => Some(coords.to_index(4)),
+                Movement::Action { .. } => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn sessions_survive_a_restart_with_their_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data").join("sessions.jsonl");
+
+        let store = FileSessionStore::open(&path).unwrap();
+        store.insert("kept".to_string(), session_with_moves(&[5])).await;
+        store.insert("gone".to_string(), session_with_moves(&[])).await;
+        store.update("kept", session_with_moves(&[5, 0, 9])).await.unwrap();
+        store.remove("gone").await;
+        let last_activity = store.get("kept").await.unwrap().last_activity;
+        drop(store);
+
+        let store = FileSessionStore::open(&path).unwrap();
+        assert_eq!(store.count().await, 1);
+        assert!(store.get("gone").await.is_none());
+        let kept = store.get("kept").await.unwrap();
+        assert_eq!(cells_in_order(&kept), vec![5, 0, 9]);
+        assert_eq!(kept.hvb_seed, Some(7));
+        assert_eq!(kept.hvb_next_is_human, Some(false));
+        assert_eq!(kept.game.last_placement(), Some(Coordinates::from_index(9, 4)));
+        let drift = last_activity.duration_since(kept.last_activity).unwrap();
+        assert!(drift < Duration::from_millis(1));
+
+        // Al abrir se compacta: una línea por partida.
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn unreadable_lines_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions.jsonl");
+
+        let store = FileSessionStore::open(&path).unwrap();
+        store.insert("game".to_string(), session_with_moves(&[1, 2])).await;
+        drop(store);
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"op\":\"put\",\"game_id\":\"torn\",\"sess").unwrap();
+
+        let store = FileSessionStore::open(&path).unwrap();
+        assert_eq!(store.count().await, 1);
+        assert_eq!(cells_in_order(&store.get("game").await.unwrap()), vec![1, 2]);
+    }
+
+    #[test]
+    fn yen_is_used_when_the_history_does_not_match() {
+        let mut stored = StoredSession::from(&session_with_moves(&[3, 4]));
+        stored.history.pop();
+
+        let session = stored.into_session().unwrap();
+        let mut cells = cells_in_order(&session);
+        cells.sort();
+        assert_eq!(cells, vec![3, 4]);
+    }
+
+    #[tokio::test]
+    async fn the_log_is_compacted_while_running() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions.jsonl");
+
+        let store = FileSessionStore::open(&path).unwrap();
+        store.insert("game".to_string(), session_with_moves(&[])).await;
+        for _ in 0..COMPACT_MIN_RECORDS {
+            store.update("game", session_with_moves(&[0])).await.unwrap();
+        }
+        assert!(fs::read_to_string(&path).unwrap().lines().count() <= COMPACT_MIN_RECORDS);
+        drop(store);
+
+        let store = FileSessionStore::open(&path).unwrap();
+        assert_eq!(cells_in_order(&store.get("game").await.unwrap()), vec![0]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_changes_reach_the_file_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions.jsonl");
+        let store = std::sync::Arc::new(FileSessionStore::open(&path).unwrap());
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for game in 0..4 {
+            let store = store.clone();
+            tasks.spawn(async move {
+                let game_id = format!("game-{game}");
+                store.insert(game_id.clone(), session_with_moves(&[])).await;
+                for moves in 1..=6u32 {
+                    let cells: Vec<u32> = (0..moves).map(|turn| (turn + game) % 10).collect();
+                    store.update(&game_id, session_with_moves(&cells)).await.unwrap();
+                }
+            });
+        }
+        tasks.join_all().await;
+        drop(store);
+
+        let store = FileSessionStore::open(&path).unwrap();
+        assert_eq!(store.count().await, 4);
+        for game in 0..4 {
+            let restored = store.get(&format!("game-{game}")).await.unwrap();
+            let cells: Vec<u32> = (0..6).map(|turn| (turn + game) % 10).collect();
+            assert_eq!(cells_in_order(&restored), cells);
+        }
+    }
+
+    #[tokio::test]
+    async fn a_restarted_server_continues_the_game() {
+        use std::sync::Arc;
+
+        use axum::extract::{Path as UrlPath, Query, State};
+        use axum::http::{HeaderMap, HeaderValue};
+        use axum::Json;
+
+        use crate::game_server::dto::{CellMoveRequest, GameStatus, NextTurn};
+        use crate::game_server::hvh;
+        use crate::game_server::state::GameServerState;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions.jsonl");
+        let mut headers = HeaderMap::new();
+        headers.insert("x-client-id", HeaderValue::from_static("restart"));
+
+        let open = || Arc::new(FileSessionStore::open(&path).unwrap());
+        let state = GameServerState::new_default().with_sessions(open());
+        let created = hvh::create_game(State(state.clone()), headers.clone(), Query(Default::default()))
+            .await
+            .unwrap();
+        let game_id = created.0.game_id.clone();
+        let moved = Json(CellMoveRequest { cell_id: 3, next_player: None });
+        let after_move = hvh::post_move(State(state.clone()), headers.clone(), UrlPath(game_id.clone()), moved)
+            .await
+            .unwrap();
+        drop(state);
+
+        let state = GameServerState::new_default().with_sessions(open());
+        let restored = hvh::get_game(State(state), headers, UrlPath(game_id)).await.unwrap();
+        assert_eq!(restored.0.yen.layout(), after_move.0["yen"]["layout"]);
+        assert!(matches!(restored.0.status, GameStatus::Ongoing { next: NextTurn::Player1 }));
+    }
+}
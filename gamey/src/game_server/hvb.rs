@@ -2,28 +2,37 @@
 //!
 //! Endpoints HvB (Humano vs Bot) usando sesiones.
 
+use std::sync::Arc;
+use std::time::SystemTime;
+
 use axum::{
     extract::{Path, State},
-    http::HeaderMap,
+    http::{HeaderMap, StatusCode},
     Json,
 };
 use uuid::Uuid;
 
-use crate::{Coordinates, GameY, Movement, PlayerId};
+use crate::{Coordinates, GameY, Movement, PlayerId, SearchControl, YBot, move_seed};
 
 use super::auth::{resolve_principal, Principal};
 use super::dto::{
     status_hvb, AppliedMove, CellMoveRequest, GameMode, GameStateResponse, HvBStarter, Winner,
 };
 use super::error::ApiErrorResponse;
+use super::jobs::{AbandonOnDrop, BotJobResponse};
 use super::sessions::GameSession;
 use super::state::GameServerState;
+use super::sweeper::insert_new_game;
 
 #[derive(Debug, serde::Deserialize)]
 pub struct CreateHvbGameRequest {
     pub size: Option<u32>,
     pub starter: Option<HvBStarter>,
     pub bot_id: Option<String>,
+    /// Si se indica, las jugadas del bot se derivan de esta semilla y la
+    /// partida se puede reproducir (sin reutilizar el árbol ni pensar en el
+    /// turno del humano).
+    pub seed: Option<u64>,
 }
 
 fn parse_uuid(id: &str) -> Result<String, ApiErrorResponse> {
@@ -106,12 +115,13 @@ fn current_hvb_status(session: &GameSession) -> super::dto::GameStatus {
     }
 }
 
-fn hvb_state_response(game_id: String, session: &GameSession) -> GameStateResponse {
+pub(super) fn hvb_state_response(game_id: String, session: &GameSession) -> GameStateResponse {
     GameStateResponse {
         game_id,
         mode: GameMode::Hvb,
         yen: crate::YEN::from(&session.game),
         status: current_hvb_status(session),
+        seats: None,
     }
 }
 
@@ -190,6 +200,8 @@ fn apply_hvb_outcome(session: &mut GameSession, human_turn: bool) {
     }
 }
 
+/// Guarda la partida tras una jugada y la publica a los suscriptores de
+/// eventos (el humano es el jugador 0 y el bot el 1).
 async fn persist_and_respond_move(
     state: &GameServerState,
     game_id: &str,
@@ -198,9 +210,37 @@ async fn persist_and_respond_move(
     applied: AppliedMove,
 ) -> Result<Json<serde_json::Value>, ApiErrorResponse> {
     save_session(state, game_id, session.clone()).await?;
+    let player = if move_field == "human_move" { 0 } else { 1 };
+    let next = session
+        .hvb_next_is_human
+        .filter(|_| !session.game.check_game_over())
+        .map(|human| if human { 0 } else { 1 });
+    state
+        .events
+        .publish_move(player, applied.cell_id, &hvb_state_response(game_id.to_string(), &session), next);
+    if session.game.check_game_over() {
+        // El bot ya no volverá a jugar en esta partida: liberamos su árbol.
+        state.ponder.stop(game_id);
+        state.bot_sessions.remove(game_id).await;
+    }
     Ok(Json(hvb_move_response(game_id, &session, move_field, applied)))
 }
 
+/// Si le toca al humano, lanza la búsqueda en segundo plano del bot de la
+/// partida (solo para bots con sesión, ver `BotSession::ponder`).
+async fn start_pondering(state: &GameServerState, game_id: &str, session: &GameSession) {
+    // Las partidas con semilla no usan la sesión del bot: deben ser reproducibles.
+    if session.game.check_game_over() || session.hvb_next_is_human != Some(true) || session.hvb_seed.is_some() {
+        return;
+    }
+    let Some(bot) = session.bot_id.as_deref().and_then(|bot_id| state.bots.find(bot_id)) else {
+        return;
+    };
+    if let Some(bot_session) = state.bot_sessions.get_or_create(game_id, bot.as_ref()).await {
+        state.ponder.start(game_id, bot_session, session.game.clone());
+    }
+}
+
 fn human_movement(cell_id: u32, size: u32) -> Movement {
     let coords = Coordinates::from_index(cell_id, size);
     Movement::Placement {
@@ -240,14 +280,19 @@ pub async fn create_game(
         ApiErrorResponse::bad_request("HvB requires bot_id", "missing_bot_id")
     })?;
 
-    let _bot = state.bots.find(&bot_id).ok_or_else(|| {
+    let bot = state.bots.find(&bot_id).ok_or_else(|| {
         ApiErrorResponse::not_found(format!("Unknown bot_id: {bot_id}"), "unknown_bot_id")
     })?;
+    // Algunos bots declaran un tamaño máximo de tablero (ver `BotMetadata`).
+    if !bot.metadata().supports_board_size(cfg.size) {
+        return Err(ApiErrorResponse::bad_request(
+            format!("Bot {bot_id} does not play on boards of size {}", cfg.size),
+            "unsupported_board_size",
+        ));
+    }
 
     let game = GameY::new(cfg.size);
 
-    state.config_store.set(&principal, cfg.clone());
-
     let game_id = Uuid::new_v4().to_string();
     let next_is_human = resolve_hvb_starter(&cfg.hvb_starter);
 
@@ -259,11 +304,17 @@ pub async fn create_game(
         bot_id: Some(bot_id),
         hvb_next_is_human: Some(next_is_human),
         hvb_winner: None,
+        hvb_seed: req.seed,
         hvh_next_player: None,
         hvh_winner: None,
+        hvh_seats: None,
+        last_activity: SystemTime::now(),
     };
 
-    state.sessions.insert(game_id.clone(), session.clone()).await;
+    insert_new_game(&state, game_id.clone(), session.clone()).await?;
+    state.config_store.set(&principal, cfg);
+    state.events.start(&game_id, Some(if next_is_human { 0 } else { 1 }));
+    start_pondering(&state, &game_id, &session).await;
 
     Ok(Json(hvb_state_response(game_id, &session)))
 }
@@ -291,7 +342,7 @@ pub async fn delete_game(
     let game_id = parse_uuid(&game_id)?;
 
     let _session = load_owned_session(&state, &principal, &game_id).await?;
-    state.sessions.remove(&game_id).await;
+    state.discard_game(&game_id).await;
 
     Ok(Json(serde_json::json!({ "deleted": true })))
 }
@@ -317,6 +368,10 @@ pub async fn post_human_move(
             ApiErrorResponse::conflict(format!("Human move rejected: {e}"), "move_rejected")
         })?;
 
+    // La búsqueda en segundo plano era para la posición anterior (una jugada
+    // rechazada no la interrumpe); el árbol se reutiliza en bot-move.
+    state.ponder.stop(&game_id);
+
     let human_applied = AppliedMove::new(req.cell_id, size);
     apply_hvb_outcome(&mut session, true);
 
@@ -325,12 +380,81 @@ pub async fn post_human_move(
 
 /// POST /api/v1/hvb/games/{game_id}/bot-move
 /// Aplica SOLO la jugada del bot.
+///
+/// Con la cabecera `Prefer: respond-async` no espera a la jugada: responde
+/// `202 Accepted` con un trabajo (ver `jobs.rs`) que se consulta en
+/// `GET /api/v1/hvb/games/{game_id}/bot-move/{job_id}`. Sin ella la jugada
+/// también se registra como trabajo, para que no coincida con otra.
 pub async fn post_bot_move(
     State(state): State<GameServerState>,
     headers: HeaderMap,
     Path(game_id): Path<String>,
-) -> Result<Json<serde_json::Value>, ApiErrorResponse> {
-    let (game_id, mut session) = load_hvb_session_for_action(&state, &headers, &game_id).await?;
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiErrorResponse> {
+    let (game_id, session) = load_hvb_session_for_action(&state, &headers, &game_id).await?;
+    require_bot_turn(&session)?;
+    let size = session.game.board_size();
+
+    // Primero se ocupa el hueco de la partida y después se vuelve a leer: otra
+    // jugada del bot ya no puede colarse entre la lectura y el guardado.
+    let asynchronous = prefers_async(&headers);
+    let control = if asynchronous { state.bot_jobs.async_control() } else { state.workers.control(None) };
+    let job = state.bot_jobs.start(&game_id, size, control).await?;
+    let abandon = AbandonOnDrop(job.clone());
+    let (session, bot) = match load_bot_turn(&state, &headers, &game_id).await {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            job.finish(Err(e.clone()));
+            return Err(e);
+        }
+    };
+
+    if !asynchronous {
+        let result = play_bot_move(&state, &game_id, session, bot, job.control(), || {}).await;
+        job.finish(result.clone().map(|Json(value)| value));
+        return result.map(|response| (StatusCode::OK, response));
+    }
+
+    let accepted = serde_json::to_value(job.to_response())
+        .map_err(|e| ApiErrorResponse::internal(format!("Failed to serialize job: {e}"), "job_serialization_failed"))?;
+
+    tokio::spawn(async move {
+        let _abandon = abandon;
+        let running = job.clone();
+        let result = play_bot_move(&state, &game_id, session, bot, job.control(), move || running.mark_running()).await;
+        job.finish(result.map(|Json(value)| value));
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(accepted)))
+}
+
+/// GET /api/v1/hvb/games/{game_id}/bot-move/{job_id}
+/// Estado de una jugada del bot pedida con `Prefer: respond-async`: progreso
+/// mientras busca y, al terminar, la jugada aplicada o el error.
+pub async fn get_bot_move_job(
+    State(state): State<GameServerState>,
+    headers: HeaderMap,
+    Path((game_id, job_id)): Path<(String, String)>,
+) -> Result<Json<BotJobResponse>, ApiErrorResponse> {
+    let principal = resolve_principal(&headers);
+    let game_id = parse_uuid(&game_id)?;
+    load_owned_session(&state, &principal, &game_id).await?;
+
+    let job = state
+        .bot_jobs
+        .get(&game_id, &job_id)
+        .await
+        .ok_or_else(|| ApiErrorResponse::not_found("Bot move job not found", "job_not_found"))?;
+
+    Ok(Json(job.to_response()))
+}
+
+/// Partida en el turno del bot y el bot que la juega.
+async fn load_bot_turn(
+    state: &GameServerState,
+    headers: &HeaderMap,
+    game_id: &str,
+) -> Result<(GameSession, Arc<dyn YBot>), ApiErrorResponse> {
+    let (_, session) = load_hvb_session_for_action(state, headers, game_id).await?;
     require_bot_turn(&session)?;
 
     let bot_id = session
@@ -341,12 +465,57 @@ pub async fn post_bot_move(
     let bot = state.bots.find(&bot_id).ok_or_else(|| {
         ApiErrorResponse::not_found(format!("Unknown bot_id: {bot_id}"), "unknown_bot_id")
     })?;
+    Ok((session, bot))
+}
+
+/// Si el cliente pide respuesta asíncrona (`Prefer: respond-async`, RFC 7240).
+fn prefers_async(headers: &HeaderMap) -> bool {
+    headers
+        .get_all("prefer")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|preference| preference.trim().eq_ignore_ascii_case("respond-async"))
+}
 
+/// Busca la jugada del bot en el pool y la aplica a la partida. `on_start` se
+/// llama cuando el bot empieza a buscar (ya fuera de la cola del pool).
+async fn play_bot_move(
+    state: &GameServerState,
+    game_id: &str,
+    mut session: GameSession,
+    bot: Arc<dyn YBot>,
+    control: Arc<SearchControl>,
+    on_start: impl FnOnce() + Send + 'static,
+) -> Result<Json<serde_json::Value>, ApiErrorResponse> {
     let size = session.game.board_size();
 
-    let bot_coords = bot.choose_move(&session.game).ok_or_else(|| {
-        ApiErrorResponse::conflict("Bot could not choose a move", "bot_no_move")
-    })?;
+    // Si el bot tiene estado por partida, reutiliza lo calculado en jugadas anteriores
+    // (incluido lo pensado durante el turno del humano). Con semilla se busca
+    // siempre desde cero para que la jugada sea reproducible.
+    state.ponder.stop(game_id);
+    let seed = session.hvb_seed.map(|seed| move_seed(seed, &session.game));
+    let bot_session = match seed {
+        Some(_) => None,
+        None => state.bot_sessions.get_or_create(game_id, bot.as_ref()).await,
+    };
+    let board = session.game.clone();
+    let bot_coords = state
+        .workers
+        .run_with(control, move |control| {
+            on_start();
+            match bot_session {
+                Some(bot_session) => bot_session
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .choose_move_controlled(&board, control),
+                None => bot.choose_move_controlled(&board, seed, control),
+            }
+        })
+        .await?
+        .ok_or_else(|| {
+            ApiErrorResponse::conflict("Bot could not choose a move", "bot_no_move")
+        })?;
 
     let bot_cell_id = bot_coords.to_index(size);
 
@@ -360,7 +529,19 @@ pub async fn post_bot_move(
     let bot_applied = AppliedMove::new(bot_cell_id, size);
     apply_hvb_outcome(&mut session, false);
 
-    persist_and_respond_move(&state, &game_id, session, "bot_move", bot_applied).await
+    // Solo se piensa en segundo plano si la partida se ha guardado: si se borró
+    // mientras el bot buscaba, se suelta la sesión del bot creada arriba.
+    match persist_and_respond_move(state, game_id, session.clone(), "bot_move", bot_applied).await {
+        Ok(response) => {
+            start_pondering(state, game_id, &session).await;
+            Ok(response)
+        }
+        Err(e) => {
+            state.ponder.stop(game_id);
+            state.bot_sessions.remove(game_id).await;
+            Err(e)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -422,8 +603,11 @@ mod tests {
             bot_id: bot_id.map(str::to_string),
             hvb_next_is_human: next_is_human,
             hvb_winner: winner,
+            hvb_seed: None,
             hvh_next_player: None,
             hvh_winner: None,
+            hvh_seats: None,
+            last_activity: SystemTime::now(),
         }
     }
 
@@ -549,6 +733,7 @@ mod tests {
                 size: None,
                 starter: None,
                 bot_id: None,
+                seed: None,
             }),
         )
         .await
@@ -575,6 +760,7 @@ mod tests {
                 size: None,
                 starter: None,
                 bot_id: None,
+                seed: None,
             }),
         )
         .await
@@ -584,6 +770,31 @@ mod tests {
         assert_eq!(err.1.code, "unknown_bot_id");
     }
 
+    #[tokio::test]
+    async fn create_game_rejects_board_size_the_bot_does_not_support() {
+        let config = crate::BotRegistryConfig::from_json(
+            r#"{ "bots": [ { "type": "random", "name": "pequeno", "max_board_size": 5 } ] }"#,
+        )
+        .unwrap();
+        let state = GameServerState::with_bots(config.build().unwrap());
+
+        let err = create_game(
+            State(state),
+            headers_with_client("hvb-size-limit"),
+            Json(CreateHvbGameRequest {
+                size: Some(7),
+                starter: None,
+                bot_id: Some("pequeno".to_string()),
+                seed: None,
+            }),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(err.0, StatusCode::BAD_REQUEST);
+        assert_eq!(err.1.code, "unsupported_board_size");
+    }
+
     #[tokio::test]
     async fn create_game_with_random_returns_valid_starting_turn() {
         let state = GameServerState::new_default();
@@ -601,6 +812,7 @@ mod tests {
                 size: None,
                 starter: None,
                 bot_id: None,
+                seed: None,
             }),
         )
         .await
@@ -672,6 +884,7 @@ mod tests {
                 size: None,
                 starter: None,
                 bot_id: None,
+                seed: None,
             }),
         )
         .await
@@ -1008,12 +1221,13 @@ mod tests {
         let session = hvb_session(principal.key(), 2, Some(false), None, Some("random_bot"));
         state.sessions.insert(game_id.clone(), session).await;
 
-        let res = post_bot_move(State(state.clone()), headers, Path(game_id.clone()))
+        let (status, Json(res)) = post_bot_move(State(state.clone()), headers, Path(game_id.clone()))
             .await
             .unwrap();
 
-        assert_eq!(res.0["game_id"], game_id);
-        assert!(res.0.get("bot_move").is_some());
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(res["game_id"], game_id);
+        assert!(res.get("bot_move").is_some());
 
         let stored = state.sessions.get(&game_id).await.unwrap();
         assert_eq!(stored.hvb_next_is_human, Some(true));
@@ -1038,6 +1252,7 @@ mod tests {
                 size: None,
                 starter: None,
                 bot_id: None,
+                seed: None,
             }),
         )
         .await
@@ -1052,6 +1267,281 @@ mod tests {
         assert_eq!(err.0, StatusCode::NOT_FOUND);
         assert_eq!(err.1.code, "game_not_found");
     }
+
+    fn async_headers(client_id: &str) -> HeaderMap {
+        let mut headers = headers_with_client(client_id);
+        headers.insert("prefer", HeaderValue::from_static("respond-async"));
+        headers
+    }
+
+    #[tokio::test]
+    async fn async_bot_move_reports_progress_and_result() {
+        let state = GameServerState::with_bots(
+            crate::YBotRegistry::new().with_bot(std::sync::Arc::new(
+                crate::MctsCompletoBot::new("mcts_async", 3000).with_threads(1),
+            )),
+        );
+        let principal = Principal::Guest { client_id: "hvb-async".to_string() };
+        let game_id = uuid::Uuid::new_v4().to_string();
+        let session = hvb_session(principal.key(), 7, Some(false), None, Some("mcts_async"));
+        state.sessions.insert(game_id.clone(), session).await;
+
+        let (status, Json(accepted)) =
+            post_bot_move(State(state.clone()), async_headers("hvb-async"), Path(game_id.clone()))
+                .await
+                .unwrap();
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let job_id = accepted["job_id"].as_str().unwrap().to_string();
+        assert!(accepted.get("result").is_none());
+
+        // Mientras el bot piensa no se puede pedir otra jugada.
+        let err = post_bot_move(State(state.clone()), headers_with_client("hvb-async"), Path(game_id.clone()))
+            .await
+            .unwrap_err();
+        assert_eq!(err.1.code, "bot_move_in_progress");
+
+        let mut job = None;
+        for _ in 0..500 {
+            let Json(current) = get_bot_move_job(
+                State(state.clone()),
+                headers_with_client("hvb-async"),
+                Path((game_id.clone(), job_id.clone())),
+            )
+            .await
+            .unwrap();
+            if current.status.is_finished() {
+                job = Some(current);
+                break;
+            }
+            sleep(Duration::from_millis(20)).await;
+        }
+
+        let job = job.expect("job finished");
+        assert_eq!(job.status, crate::game_server::jobs::BotJobStatus::Done);
+        assert_eq!(job.progress.iterations, 3000);
+        let result = job.result.unwrap();
+        assert_eq!(job.progress.best_cell_id, result["bot_move"]["cell_id"].as_u64().map(|id| id as u32));
+
+        let stored = state.sessions.get(&game_id).await.unwrap();
+        assert_eq!(stored.hvb_next_is_human, Some(true));
+    }
+
+    #[tokio::test]
+    async fn get_bot_move_job_rejects_unknown_job_and_other_owner() {
+        let state = GameServerState::new_default();
+        let principal = Principal::Guest { client_id: "hvb-job-owner".to_string() };
+        let game_id = uuid::Uuid::new_v4().to_string();
+        let session = hvb_session(principal.key(), 3, Some(false), None, Some("random_bot"));
+        state.sessions.insert(game_id.clone(), session).await;
+
+        let (_, Json(accepted)) =
+            post_bot_move(State(state.clone()), async_headers("hvb-job-owner"), Path(game_id.clone()))
+                .await
+                .unwrap();
+        let job_id = accepted["job_id"].as_str().unwrap().to_string();
+
+        let err = get_bot_move_job(
+            State(state.clone()),
+            headers_with_client("hvb-job-owner"),
+            Path((game_id.clone(), "missing".to_string())),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.1.code, "job_not_found");
+
+        let err = get_bot_move_job(
+            State(state.clone()),
+            headers_with_client("someone-else"),
+            Path((game_id.clone(), job_id.clone())),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.0, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn post_bot_move_keeps_bot_session_until_delete() {
+        let state = GameServerState::with_bots(
+            crate::YBotRegistry::new()
+                .with_bot(std::sync::Arc::new(crate::MctsCompletoBot::new("mcts_session", 50))),
+        );
+        let principal = Principal::Guest {
+            client_id: "hvb-bot-session".to_string(),
+        };
+
+        let game_id = uuid::Uuid::new_v4().to_string();
+        let session = hvb_session(principal.key(), 4, Some(false), None, Some("mcts_session"));
+        state.sessions.insert(game_id.clone(), session).await;
+
+        let (_, Json(res)) = post_bot_move(
+            State(state.clone()),
+            headers_with_client("hvb-bot-session"),
+            Path(game_id.clone()),
+        )
+        .await
+        .unwrap();
+        assert!(res.get("bot_move").is_some());
+        assert_eq!(state.bot_sessions.len().await, 1);
+
+        let deleted = delete_game(
+            State(state.clone()),
+            headers_with_client("hvb-bot-session"),
+            Path(game_id),
+        )
+        .await
+        .unwrap();
+        assert_eq!(deleted.0["deleted"], true);
+        assert_eq!(state.bot_sessions.len().await, 0);
+    }
+
+    #[tokio::test]
+    async fn seeded_game_replays_bot_moves_without_bot_session() {
+        let state = GameServerState::with_bots(
+            crate::YBotRegistry::new()
+                .with_bot(std::sync::Arc::new(crate::MctsCompletoBot::new("mcts_seeded", 200))),
+        );
+
+        let mut replays = Vec::new();
+        for client in ["hvb-seed-a", "hvb-seed-b"] {
+            let created = create_game(
+                State(state.clone()),
+                headers_with_client(client),
+                Json(CreateHvbGameRequest {
+                    size: Some(5),
+                    starter: Some(HvBStarter::Bot),
+                    bot_id: Some("mcts_seeded".to_string()),
+                    seed: Some(99),
+                }),
+            )
+            .await
+            .unwrap();
+            let game_id = created.0.game_id;
+            assert!(!state.ponder.is_pondering(&game_id));
+
+            let mut bot_moves = Vec::new();
+            for human_cell in [0, 14] {
+                let (_, Json(res)) = post_bot_move(State(state.clone()), headers_with_client(client), Path(game_id.clone()))
+                    .await
+                    .unwrap();
+                bot_moves.push(res["bot_move"]["cell_id"].clone());
+
+                let res = post_human_move(
+                    State(state.clone()),
+                    headers_with_client(client),
+                    Path(game_id.clone()),
+                    Json(CellMoveRequest { cell_id: human_cell, next_player: None }),
+                )
+                .await;
+                if res.is_err() {
+                    // El bot ya ocupó esa casilla: la repetición debe fallar igual.
+                    bot_moves.push(serde_json::Value::Null);
+                }
+            }
+            replays.push(bot_moves);
+        }
+
+        assert_eq!(replays[0], replays[1]);
+        assert!(state.bot_sessions.is_empty().await);
+    }
+
+    #[tokio::test]
+    async fn sync_bot_move_blocks_async_and_releases_bot_session_if_game_is_deleted() {
+        let state = GameServerState::with_bots(crate::YBotRegistry::new().with_bot(std::sync::Arc::new(
+            crate::MctsCompletoBot::new("mcts_deleted", 1_000_000).with_threads(1),
+        )));
+        let principal = Principal::Guest { client_id: "hvb-deleted".to_string() };
+        let game_id = uuid::Uuid::new_v4().to_string();
+        let session = hvb_session(principal.key(), 7, Some(false), None, Some("mcts_deleted"));
+        state.sessions.insert(game_id.clone(), session).await;
+
+        let searching = tokio::spawn(post_bot_move(
+            State(state.clone()),
+            headers_with_client("hvb-deleted"),
+            Path(game_id.clone()),
+        ));
+        while state.bot_sessions.is_empty().await {
+            sleep(Duration::from_millis(5)).await;
+        }
+
+        // El bot-move síncrono ocupa el hueco de la partida.
+        let err = post_bot_move(State(state.clone()), async_headers("hvb-deleted"), Path(game_id.clone()))
+            .await
+            .unwrap_err();
+        assert_eq!(err.1.code, "bot_move_in_progress");
+
+        // Se borra la partida mientras el bot busca: la jugada no se guarda y
+        // no queda ni sesión del bot ni ponder.
+        let deleted = delete_game(State(state.clone()), headers_with_client("hvb-deleted"), Path(game_id.clone()))
+            .await
+            .unwrap();
+        assert_eq!(deleted.0["deleted"], true);
+        assert!(searching.await.unwrap().is_err());
+        assert!(state.bot_sessions.is_empty().await);
+        assert!(!state.ponder.is_pondering(&game_id));
+    }
+
+    #[tokio::test]
+    async fn concurrent_bot_moves_apply_a_single_move() {
+        let state = GameServerState::new_default();
+        let principal = Principal::Guest { client_id: "hvb-twice".to_string() };
+        let game_id = uuid::Uuid::new_v4().to_string();
+        let session = hvb_session(principal.key(), 4, Some(false), None, Some("random_bot"));
+        state.sessions.insert(game_id.clone(), session).await;
+
+        let (first, second) = tokio::join!(
+            post_bot_move(State(state.clone()), headers_with_client("hvb-twice"), Path(game_id.clone())),
+            post_bot_move(State(state.clone()), headers_with_client("hvb-twice"), Path(game_id.clone())),
+        );
+        assert_eq!([&first, &second].iter().filter(|res| res.is_ok()).count(), 1);
+        assert_eq!(state.sessions.get(&game_id).await.unwrap().game.history().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn human_turn_ponders_until_human_moves() {
+        let mut state = GameServerState::with_bots(crate::YBotRegistry::new().with_bot(std::sync::Arc::new(
+            crate::MctsCompletoBot::new("mcts_ponder", 1_000_000),
+        )));
+        state.ponder = crate::game_server::ponder::PonderManager::new(1, Duration::from_secs(30));
+
+        let created = create_game(
+            State(state.clone()),
+            headers_with_client("hvb-ponder"),
+            Json(CreateHvbGameRequest {
+                size: Some(5),
+                starter: Some(HvBStarter::Human),
+                bot_id: Some("mcts_ponder".to_string()),
+                seed: None,
+            }),
+        )
+        .await
+        .unwrap();
+        let game_id = created.0.game_id;
+        assert!(state.ponder.is_pondering(&game_id));
+
+        // Una jugada rechazada no interrumpe la búsqueda.
+        let err = post_human_move(
+            State(state.clone()),
+            headers_with_client("hvb-ponder"),
+            Path(game_id.clone()),
+            Json(CellMoveRequest { cell_id: 99, next_player: None }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.1.code, "cell_id_out_of_range");
+        assert!(state.ponder.is_pondering(&game_id));
+
+        let res = post_human_move(
+            State(state.clone()),
+            headers_with_client("hvb-ponder"),
+            Path(game_id.clone()),
+            Json(CellMoveRequest { cell_id: 0, next_player: None }),
+        )
+        .await
+        .unwrap();
+        assert!(res.0.get("human_move").is_some());
+        assert!(!state.ponder.is_pondering(&game_id));
+        assert_eq!(state.bot_sessions.len().await, 1);
+    }
 }
 
 /// GET /api/v1/hvb/games/{game_id}/hint
@@ -1090,9 +1580,15 @@ pub async fn get_hint(
 
     let size = session.game.board_size();
 
-    let coords = bot.choose_move(&session.game).ok_or_else(|| {
-        ApiErrorResponse::conflict("Bot could not suggest a move", "bot_no_move")
-    })?;
+    let seed = session.hvb_seed.map(|seed| move_seed(seed, &session.game));
+    let board = session.game.clone();
+    let coords = state
+        .workers
+        .run(None, move |control| bot.choose_move_controlled(&board, seed, control))
+        .await?
+        .ok_or_else(|| {
+            ApiErrorResponse::conflict("Bot could not suggest a move", "bot_no_move")
+        })?;
 
     let cell_id = coords.to_index(size);
 
@@ -1,21 +1,46 @@
 //! hvh.rs
 //!
 //! Endpoints HvH (Humano vs Humano) usando sesiones.
+//!
+//! Dos formas de jugar:
+//! - local: un solo cliente (el creador) mueve por los dos jugadores
+//! - online (`?online=true`): el creador ocupa el asiento del jugador 0 y
+//!   recibe un `join_code`; un segundo cliente ocupa el del jugador 1 con
+//!   `POST /api/v1/hvh/join`. Cada uno solo puede mover en su turno y
+//!   cualquiera con el `game_id` puede mirar la partida.
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::HeaderMap,
     Json,
 };
+use serde::Deserialize;
+use std::time::SystemTime;
 use uuid::Uuid;
 
 use crate::{GameY, Movement, PlayerId};
 
 use super::auth::{resolve_principal, Principal};
-use super::dto::{AppliedMove, CellMoveRequest, GameMode, GameStateResponse, GameStatus, HvHStarter};
+use super::dto::{
+    AppliedMove, CellMoveRequest, GameMode, GameStateResponse, HvHStarter, HvhSeatsInfo,
+    JoinHvhRequest,
+};
 use super::error::ApiErrorResponse;
-use super::sessions::GameSession;
+use super::sessions::{GameSession, HvhSeats, JoinError, SessionRole};
 use super::state::GameServerState;
+use super::sweeper::insert_new_game;
+
+/// Letras de los códigos para unirse (sin 0/O ni 1/I, que se confunden).
+const JOIN_CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const JOIN_CODE_LEN: usize = 6;
+
+/// Query de `POST /api/v1/hvh/games`.
+#[derive(Debug, Default, Deserialize)]
+pub struct CreateHvhQuery {
+    /// Partida entre dos clientes con asientos y código para unirse.
+    #[serde(default)]
+    pub online: bool,
+}
 
 fn parse_uuid(id: &str) -> Result<String, ApiErrorResponse> {
     Uuid::parse_str(id)
@@ -33,18 +58,47 @@ fn resolve_hvh_starting_player(starter: Option<HvHStarter>) -> u8 {
     }
 }
 
-async fn load_owned_session(
+async fn load_session(
     state: &GameServerState,
     principal: &Principal,
     game_id: &str,
+    min: SessionRole,
 ) -> Result<GameSession, ApiErrorResponse> {
     state
         .sessions
-        .assert_owner(principal, game_id)
+        .assert_role(principal, game_id, min)
         .await
         .map_err(|_| ApiErrorResponse::not_found("Game not found", "game_not_found"))
 }
 
+async fn new_join_code(state: &GameServerState) -> String {
+    loop {
+        let code: String = (0..JOIN_CODE_LEN)
+            .map(|_| JOIN_CODE_ALPHABET[rand::random_range(0..JOIN_CODE_ALPHABET.len())] as char)
+            .collect();
+        if !state.sessions.join_code_in_use(&code).await {
+            return code;
+        }
+    }
+}
+
+/// Comprueba que `principal` puede mover ahora en una partida online.
+fn check_seat_turn(session: &GameSession, principal: &Principal) -> Result<(), ApiErrorResponse> {
+    let Some(seats) = &session.hvh_seats else {
+        return Ok(());
+    };
+    let Some(seat) = session.seat_of(&principal.key()) else {
+        return Err(ApiErrorResponse::forbidden("Spectators cannot move", "not_a_player"));
+    };
+    if seats.players.iter().any(Option::is_none) {
+        return Err(ApiErrorResponse::conflict("Waiting for the opponent to join", "waiting_for_opponent"));
+    }
+    if session.hvh_next_player.unwrap_or(0) != seat {
+        return Err(ApiErrorResponse::conflict("It is not your turn", "not_your_turn"));
+    }
+    Ok(())
+}
+
 async fn save_session(
     state: &GameServerState,
     game_id: &str,
@@ -68,8 +122,23 @@ fn validate_cell_id(cell_id: u32, size: u32) -> Result<(), ApiErrorResponse> {
     Ok(())
 }
 
-fn hvh_state_response(game_id: String, session: &GameSession) -> GameStateResponse {
+/// Estado de la partida visto por `viewer` (`None` = espectador anónimo).
+pub(super) fn hvh_state_response(
+    game_id: String,
+    session: &GameSession,
+    viewer: Option<&Principal>,
+) -> GameStateResponse {
     let finished = session.game.check_game_over();
+    let seats = session.hvh_seats.as_ref().map(|seats| {
+        let your_seat = viewer.and_then(|principal| session.seat_of(&principal.key()));
+        let taken = seats.players.clone().map(|player| player.is_some());
+        HvhSeatsInfo {
+            join_code: Some(seats.join_code.clone())
+                .filter(|_| your_seat.is_some() && taken.contains(&false)),
+            taken,
+            your_seat,
+        }
+    });
 
     GameStateResponse {
         game_id,
@@ -80,13 +149,18 @@ fn hvh_state_response(game_id: String, session: &GameSession) -> GameStateRespon
             session.hvh_next_player.unwrap_or(0),
             session.hvh_winner,
         ),
+        seats,
     }
 }
 
 /// POST /api/v1/hvh/games
+///
+/// Con `?online=true` la partida tiene dos asientos y la respuesta incluye el
+/// `join_code` para el rival.
 pub async fn create_game(
     State(state): State<GameServerState>,
     headers: HeaderMap,
+    Query(query): Query<CreateHvhQuery>,
 ) -> Result<Json<GameStateResponse>, ApiErrorResponse> {
     let principal = resolve_principal(&headers);
     let cfg = state.config_store.get_or_default(&principal).await;
@@ -96,6 +170,12 @@ pub async fn create_game(
     let game = GameY::new(cfg.size);
     let game_id = Uuid::new_v4().to_string();
 
+    let hvh_seats = if query.online {
+        Some(HvhSeats { join_code: new_join_code(&state).await, players: [Some(principal.key()), None] })
+    } else {
+        None
+    };
+
     let session = GameSession {
         owner_key: principal.key(),
         mode: GameMode::Hvh,
@@ -104,24 +184,46 @@ pub async fn create_game(
         bot_id: None,
         hvb_next_is_human: None,
         hvb_winner: None,
+        hvb_seed: None,
         hvh_next_player: Some(next_player),
         hvh_winner: None,
+        hvh_seats,
+        last_activity: SystemTime::now(),
     };
 
-    state.sessions.insert(game_id.clone(), session.clone()).await;
+    insert_new_game(&state, game_id.clone(), session.clone()).await?;
+    // En online el reloj no arranca hasta que llega el rival.
+    let turn = if session.hvh_seats.is_some() { None } else { Some(next_player) };
+    state.events.start(&game_id, turn);
 
-    let next = if next_player == 1 {
-        super::dto::NextTurn::Player1
-    } else {
-        super::dto::NextTurn::Player0
-    };
+    Ok(Json(hvh_state_response(game_id, &session, Some(&principal))))
+}
 
-    Ok(Json(GameStateResponse {
-        game_id,
-        mode: GameMode::Hvh,
-        yen: crate::YEN::from(&session.game),
-        status: GameStatus::Ongoing { next },
-    }))
+/// POST /api/v1/hvh/join
+///
+/// Ocupa el asiento libre de la partida online con ese `join_code`. Volver a
+/// unirse con el mismo cliente devuelve su asiento.
+pub async fn join_game(
+    State(state): State<GameServerState>,
+    headers: HeaderMap,
+    Json(req): Json<JoinHvhRequest>,
+) -> Result<Json<GameStateResponse>, ApiErrorResponse> {
+    let principal = resolve_principal(&headers);
+    let join_code = req.join_code.trim().to_ascii_uppercase();
+
+    let (game_id, session, seat) = state
+        .sessions
+        .join(&join_code, &principal.key())
+        .await
+        .map_err(|e| match e {
+            JoinError::UnknownCode => ApiErrorResponse::not_found("Unknown join code", "join_code_not_found"),
+            JoinError::Full => ApiErrorResponse::conflict("Both seats are taken", "game_full"),
+        })?;
+
+    let turn = session.hvh_next_player.filter(|_| !session.game.check_game_over());
+    state.events.publish_joined(&game_id, seat, turn);
+
+    Ok(Json(hvh_state_response(game_id, &session, Some(&principal))))
 }
 
 /// GET /api/v1/hvh/games/{game_id}
@@ -132,9 +234,9 @@ pub async fn get_game(
 ) -> Result<Json<GameStateResponse>, ApiErrorResponse> {
     let principal = resolve_principal(&headers);
     let game_id = parse_uuid(&game_id)?;
-    let session = load_owned_session(&state, &principal, &game_id).await?;
+    let session = load_session(&state, &principal, &game_id, SessionRole::Spectator).await?;
 
-    Ok(Json(hvh_state_response(game_id, &session)))
+    Ok(Json(hvh_state_response(game_id, &session, Some(&principal))))
 }
 
 /// DELETE /api/v1/hvh/games/{game_id}
@@ -146,8 +248,8 @@ pub async fn delete_game(
     let principal = resolve_principal(&headers);
     let game_id = parse_uuid(&game_id)?;
 
-    let _session = load_owned_session(&state, &principal, &game_id).await?;
-    state.sessions.remove(&game_id).await;
+    let _session = load_session(&state, &principal, &game_id, SessionRole::Owner).await?;
+    state.discard_game(&game_id).await;
 
     Ok(Json(serde_json::json!({ "deleted": true })))
 }
@@ -162,7 +264,8 @@ pub async fn post_move(
     let principal = resolve_principal(&headers);
     let game_id = parse_uuid(&game_id)?;
 
-    let mut session = load_owned_session(&state, &principal, &game_id).await?;
+    let mut session = load_session(&state, &principal, &game_id, SessionRole::Spectator).await?;
+    check_seat_turn(&session, &principal)?;
 
     let size = session.game.board_size();
     validate_cell_id(req.cell_id, size)?;
@@ -194,11 +297,17 @@ pub async fn post_move(
             crate::GameStatus::Finished { winner: None } => None,
             crate::GameStatus::Ongoing { .. } => None,
         };
+    } else if session.hvh_seats.is_some() {
+        session.hvh_next_player = Some(1 - played_by);
     } else {
         session.hvh_next_player = Some(req.next_player.unwrap_or(1 - played_by));
     }
 
     save_session(&state, &game_id, session.clone()).await?;
+    let next = if finished { None } else { session.hvh_next_player };
+    state
+        .events
+        .publish_move(played_by, req.cell_id, &hvh_state_response(game_id.clone(), &session, None), next);
 
     let status = super::dto::status_hvh_from_session(
         finished,
@@ -227,7 +336,9 @@ mod tests {
     use tokio::time::{sleep, Duration};
 
     use crate::game_server::auth::Principal;
-    use crate::game_server::dto::{CellMoveRequest, GameConfig, GameMode, HvBStarter, HvHStarter, NextTurn};
+    use crate::game_server::dto::{
+        CellMoveRequest, GameConfig, GameMode, GameStatus, HvBStarter, HvHStarter, NextTurn,
+    };
     use crate::game_server::state::GameServerState;
 
     fn headers_with_client(client_id: &str) -> HeaderMap {
@@ -264,7 +375,7 @@ mod tests {
 
         store_hvh_config(&state, &principal, 3, Some(HvHStarter::Player0)).await;
 
-        let res = create_game(State(state), headers).await.unwrap();
+        let res = create_game(State(state), headers, Query(CreateHvhQuery::default())).await.unwrap();
 
         assert!(matches!(res.0.mode, GameMode::Hvh));
         match res.0.status {
@@ -283,7 +394,7 @@ mod tests {
 
         store_hvh_config(&state, &principal, 3, Some(HvHStarter::Player1)).await;
 
-        let res = create_game(State(state), headers).await.unwrap();
+        let res = create_game(State(state), headers, Query(CreateHvhQuery::default())).await.unwrap();
 
         assert!(matches!(res.0.mode, GameMode::Hvh));
         match res.0.status {
@@ -302,7 +413,7 @@ mod tests {
 
         store_hvh_config(&state, &principal, 3, Some(HvHStarter::Random)).await;
 
-        let res = create_game(State(state), headers).await.unwrap();
+        let res = create_game(State(state), headers, Query(CreateHvhQuery::default())).await.unwrap();
 
         assert!(matches!(res.0.mode, GameMode::Hvh));
         match res.0.status {
@@ -350,7 +461,9 @@ mod tests {
 
         store_hvh_config(&state, &principal, 3, Some(HvHStarter::Player0)).await;
 
-        let created = create_game(State(state.clone()), headers.clone()).await.unwrap();
+        let created = create_game(State(state.clone()), headers.clone(), Query(CreateHvhQuery::default()))
+            .await
+            .unwrap();
         let game_id = created.0.game_id.clone();
 
         let fetched = get_game(State(state.clone()), headers.clone(), Path(game_id.clone()))
@@ -427,8 +540,11 @@ mod tests {
             bot_id: None,
             hvb_next_is_human: None,
             hvb_winner: None,
+            hvb_seed: None,
             hvh_next_player: Some(0),
             hvh_winner: None,
+            hvh_seats: None,
+            last_activity: SystemTime::now(),
         };
 
         let game_id = uuid::Uuid::new_v4().to_string();
@@ -468,8 +584,11 @@ mod tests {
             bot_id: None,
             hvb_next_is_human: None,
             hvb_winner: None,
+            hvb_seed: None,
             hvh_next_player: Some(0),
             hvh_winner: None,
+            hvh_seats: None,
+            last_activity: SystemTime::now(),
         };
 
         let game_id = uuid::Uuid::new_v4().to_string();
@@ -499,7 +618,9 @@ mod tests {
 
         store_hvh_config(&state, &owner, 3, Some(HvHStarter::Player0)).await;
 
-        let created = create_game(State(state.clone()), owner_headers).await.unwrap();
+        let created = create_game(State(state.clone()), owner_headers, Query(CreateHvhQuery::default()))
+            .await
+            .unwrap();
         let game_id = created.0.game_id;
 
         let err = get_game(State(state), other_headers, Path(game_id))
@@ -535,7 +656,9 @@ mod tests {
 
         store_hvh_config(&state, &owner, 3, Some(HvHStarter::Player0)).await;
 
-        let created = create_game(State(state.clone()), owner_headers).await.unwrap();
+        let created = create_game(State(state.clone()), owner_headers, Query(CreateHvhQuery::default()))
+            .await
+            .unwrap();
         let game_id = created.0.game_id;
 
         let err = delete_game(State(state), other_headers, Path(game_id))
@@ -569,8 +692,11 @@ mod tests {
             bot_id: None,
             hvb_next_is_human: None,
             hvb_winner: None,
+            hvb_seed: None,
             hvh_next_player: None,
             hvh_winner: None,
+            hvh_seats: None,
+            last_activity: SystemTime::now(),
         };
 
         state.sessions.insert(game_id.clone(), session).await;
@@ -622,8 +748,11 @@ mod tests {
             bot_id: None,
             hvb_next_is_human: None,
             hvb_winner: None,
+            hvb_seed: None,
             hvh_next_player: Some(0),
             hvh_winner: Some(1),
+            hvh_seats: None,
+            last_activity: SystemTime::now(),
         };
 
         state.sessions.insert(game_id.clone(), session).await;
@@ -677,8 +806,11 @@ mod tests {
             bot_id: None,
             hvb_next_is_human: None,
             hvb_winner: None,
+            hvb_seed: None,
             hvh_next_player: Some(0),
             hvh_winner: Some(0),
+            hvh_seats: None,
+            last_activity: SystemTime::now(),
         };
 
         state.sessions.insert(game_id.clone(), session).await;
@@ -725,8 +857,11 @@ mod tests {
             bot_id: None,
             hvb_next_is_human: None,
             hvb_winner: None,
+            hvb_seed: None,
             hvh_next_player: Some(1),
             hvh_winner: None,
+            hvh_seats: None,
+            last_activity: SystemTime::now(),
         };
 
         state.sessions.insert(game_id.clone(), session).await;
@@ -743,4 +878,142 @@ mod tests {
         assert_eq!(err.0, axum::http::StatusCode::CONFLICT);
         assert_eq!(err.1.code, "move_rejected");
     }
+
+    async fn create_online_game(state: &GameServerState, client_id: &str) -> GameStateResponse {
+        let principal = Principal::Guest { client_id: client_id.to_string() };
+        store_hvh_config(state, &principal, 3, Some(HvHStarter::Player0)).await;
+        create_game(
+            State(state.clone()),
+            headers_with_client(client_id),
+            Query(CreateHvhQuery { online: true }),
+        )
+        .await
+        .unwrap()
+        .0
+    }
+
+    async fn join(
+        state: &GameServerState,
+        client_id: &str,
+        join_code: &str,
+    ) -> Result<GameStateResponse, ApiErrorResponse> {
+        join_game(
+            State(state.clone()),
+            headers_with_client(client_id),
+            Json(JoinHvhRequest { join_code: join_code.to_string() }),
+        )
+        .await
+        .map(|res| res.0)
+    }
+
+    async fn play(
+        state: &GameServerState,
+        client_id: &str,
+        game_id: &str,
+        cell_id: u32,
+    ) -> Result<serde_json::Value, ApiErrorResponse> {
+        post_move(
+            State(state.clone()),
+            headers_with_client(client_id),
+            Path(game_id.to_string()),
+            Json(CellMoveRequest { cell_id, next_player: None }),
+        )
+        .await
+        .map(|res| res.0)
+    }
+
+    #[tokio::test]
+    async fn online_game_gives_the_creator_seat0_and_a_join_code() {
+        let state = GameServerState::new_default();
+        let created = create_online_game(&state, "online-creator").await;
+
+        let seats = created.seats.unwrap();
+        assert_eq!(seats.your_seat, Some(0));
+        assert_eq!(seats.taken, [true, false]);
+        let code = seats.join_code.unwrap();
+        assert_eq!(code.len(), JOIN_CODE_LEN);
+        assert!(code.bytes().all(|c| JOIN_CODE_ALPHABET.contains(&c)));
+
+        // El reloj espera al rival.
+        assert_eq!(state.events.clock(&created.game_id).unwrap().running, None);
+    }
+
+    #[tokio::test]
+    async fn join_takes_the_free_seat_and_starts_the_clock() {
+        let state = GameServerState::new_default();
+        let created = create_online_game(&state, "join-creator").await;
+        let code = created.seats.unwrap().join_code.unwrap();
+
+        let joined = join(&state, "join-rival", &code.to_lowercase()).await.unwrap();
+        assert_eq!(joined.game_id, created.game_id);
+        let seats = joined.seats.unwrap();
+        assert_eq!(seats.your_seat, Some(1));
+        assert_eq!(seats.taken, [true, true]);
+        assert!(seats.join_code.is_none());
+        assert_eq!(state.events.clock(&created.game_id).unwrap().running, Some(0));
+
+        // Volver a unirse devuelve el mismo asiento; un tercero no cabe.
+        assert_eq!(join(&state, "join-rival", &code).await.unwrap().seats.unwrap().your_seat, Some(1));
+        let err = join(&state, "join-third", &code).await.unwrap_err();
+        assert_eq!(err.0, axum::http::StatusCode::CONFLICT);
+        assert_eq!(err.1.code, "game_full");
+
+        let err = join(&state, "join-third", "ZZZZZZ").await.unwrap_err();
+        assert_eq!(err.0, axum::http::StatusCode::NOT_FOUND);
+        assert_eq!(err.1.code, "join_code_not_found");
+    }
+
+    #[tokio::test]
+    async fn online_moves_are_checked_against_the_seat_in_turn() {
+        let state = GameServerState::new_default();
+        let created = create_online_game(&state, "turn-creator").await;
+        let game_id = created.game_id.clone();
+        let code = created.seats.unwrap().join_code.unwrap();
+
+        let err = play(&state, "turn-creator", &game_id, 0).await.unwrap_err();
+        assert_eq!(err.1.code, "waiting_for_opponent");
+
+        join(&state, "turn-rival", &code).await.unwrap();
+
+        let err = play(&state, "turn-rival", &game_id, 0).await.unwrap_err();
+        assert_eq!(err.0, axum::http::StatusCode::CONFLICT);
+        assert_eq!(err.1.code, "not_your_turn");
+
+        let err = play(&state, "turn-viewer", &game_id, 0).await.unwrap_err();
+        assert_eq!(err.0, axum::http::StatusCode::FORBIDDEN);
+        assert_eq!(err.1.code, "not_a_player");
+
+        play(&state, "turn-creator", &game_id, 0).await.unwrap();
+        let err = play(&state, "turn-creator", &game_id, 1).await.unwrap_err();
+        assert_eq!(err.1.code, "not_your_turn");
+
+        let res = play(&state, "turn-rival", &game_id, 1).await.unwrap();
+        assert_eq!(res["status"]["next"], "player0");
+        assert_eq!(state.sessions.get(&game_id).await.unwrap().game.available_cells().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn spectators_can_read_but_not_delete_online_games() {
+        let state = GameServerState::new_default();
+        let created = create_online_game(&state, "spectate-creator").await;
+        let game_id = created.game_id.clone();
+        join(&state, "spectate-rival", &created.seats.unwrap().join_code.unwrap()).await.unwrap();
+
+        let viewer = headers_with_client("spectate-viewer");
+        let seen = get_game(State(state.clone()), viewer, Path(game_id.clone())).await.unwrap();
+        let seats = seen.0.seats.unwrap();
+        assert_eq!(seats.your_seat, None);
+        assert!(seats.join_code.is_none());
+
+        for client_id in ["spectate-viewer", "spectate-rival"] {
+            let err = delete_game(State(state.clone()), headers_with_client(client_id), Path(game_id.clone()))
+                .await
+                .unwrap_err();
+            assert_eq!(err.1.code, "game_not_found");
+        }
+        let deleted = delete_game(State(state), headers_with_client("spectate-creator"), Path(game_id))
+            .await
+            .unwrap();
+        assert_eq!(deleted.0["deleted"], true);
+    }
 }
@@ -0,0 +1,319 @@
+//! jobs.rs
+//!
+//! Jugadas del bot en segundo plano (HvB).
+//!
+//! En tableros grandes una jugada del bot puede tardar más que el timeout HTTP
+//! habitual. Con `Prefer: respond-async`, `POST .../bot-move` responde al
+//! momento `202 Accepted` con un trabajo (`BotJob`) y la búsqueda sigue en el
+//! pool de bots. La webapp consulta `GET .../bot-move/{job_id}` para mostrar
+//! el progreso (iteraciones y jugada preferida hasta ahora) y, al terminar,
+//! la jugada aplicada con la misma respuesta que el `bot-move` síncrono.
+//!
+//! Los trabajos asíncronos tienen su propio plazo (`DEFAULT_ASYNC_BOT_DEADLINE`,
+//! `--async-bot-deadline`), más largo que el de una petición HTTP.
+//!
+//! Cada partida tiene como mucho un trabajo sin terminar, y el `bot-move`
+//! síncrono también lo registra aquí: no puede haber dos búsquedas aplicando
+//! una jugada del bot a la misma posición. El último trabajo se conserva hasta
+//! que empieza otro o se borra la partida.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tokio::sync::RwLock;
+
+use crate::SearchControl;
+
+use super::error::{ApiError, ApiErrorResponse};
+
+/// Plazo por defecto de una jugada del bot pedida con `Prefer: respond-async`.
+pub const DEFAULT_ASYNC_BOT_DEADLINE: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BotJobStatus {
+    /// Esperando un hilo libre del pool.
+    Queued,
+    /// El bot está buscando.
+    Running,
+    /// Jugada aplicada: ver `result`.
+    Done,
+    /// La búsqueda o la jugada fallaron: ver `error`.
+    Failed,
+}
+
+impl BotJobStatus {
+    pub fn is_finished(self) -> bool {
+        matches!(self, BotJobStatus::Done | BotJobStatus::Failed)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BotJobProgress {
+    /// Simulaciones hechas hasta ahora.
+    pub iterations: u64,
+    /// Casilla que el bot prefiere por ahora (si la búsqueda informa de ella).
+    pub best_cell_id: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BotJobResponse {
+    pub job_id: String,
+    pub game_id: String,
+    pub status: BotJobStatus,
+    pub progress: BotJobProgress,
+    /// Respuesta del `bot-move` síncrono, cuando el trabajo termina bien.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ApiError>,
+}
+
+#[derive(Debug)]
+struct JobOutcome {
+    status: BotJobStatus,
+    result: Option<serde_json::Value>,
+    error: Option<ApiError>,
+}
+
+/// Una jugada del bot en curso o terminada.
+pub struct BotJob {
+    pub id: String,
+    pub game_id: String,
+    board_size: u32,
+    control: Arc<SearchControl>,
+    outcome: Mutex<JobOutcome>,
+}
+
+impl BotJob {
+    fn outcome(&self) -> std::sync::MutexGuard<'_, JobOutcome> {
+        self.outcome.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn control(&self) -> Arc<SearchControl> {
+        self.control.clone()
+    }
+
+    pub fn status(&self) -> BotJobStatus {
+        self.outcome().status
+    }
+
+    /// El bot ha empezado a buscar.
+    pub fn mark_running(&self) {
+        let mut outcome = self.outcome();
+        if outcome.status == BotJobStatus::Queued {
+            outcome.status = BotJobStatus::Running;
+        }
+    }
+
+    /// Guarda el resultado final del trabajo.
+    pub fn finish(&self, result: Result<serde_json::Value, ApiErrorResponse>) {
+        let mut outcome = self.outcome();
+        match result {
+            Ok(value) => {
+                outcome.status = BotJobStatus::Done;
+                outcome.result = Some(value);
+            }
+            Err(ApiErrorResponse(_, error)) => {
+                outcome.status = BotJobStatus::Failed;
+                outcome.error = Some(error);
+            }
+        }
+    }
+
+    /// Da por fallido el trabajo si sigue sin terminar.
+    fn abandon(&self) {
+        if !self.status().is_finished() {
+            self.control.cancel();
+            self.finish(Err(ApiErrorResponse::conflict(
+                "The bot move was cancelled before it finished",
+                "bot_move_cancelled",
+            )));
+        }
+    }
+
+    pub fn to_response(&self) -> BotJobResponse {
+        let progress = self.control.progress();
+        let outcome = self.outcome();
+        BotJobResponse {
+            job_id: self.id.clone(),
+            game_id: self.game_id.clone(),
+            status: outcome.status,
+            progress: BotJobProgress {
+                iterations: progress.iterations,
+                best_cell_id: progress.best_move.map(|coords| coords.to_index(self.board_size)),
+            },
+            result: outcome.result.clone(),
+            error: outcome.error.clone(),
+        }
+    }
+}
+
+/// Trabajo de un `bot-move` síncrono: si el handler se descarta antes de
+/// terminar (el cliente cortó la conexión), lo da por fallido para que no
+/// bloquee la partida.
+pub struct AbandonOnDrop(pub Arc<BotJob>);
+
+impl Drop for AbandonOnDrop {
+    fn drop(&mut self) {
+        self.0.abandon();
+    }
+}
+
+#[derive(Clone)]
+pub struct BotJobStore {
+    /// Último trabajo de cada partida, por `game_id`.
+    inner: Arc<RwLock<HashMap<String, Arc<BotJob>>>>,
+    /// Plazo de los trabajos asíncronos.
+    async_deadline: Duration,
+}
+
+impl Default for BotJobStore {
+    fn default() -> Self {
+        Self::with_async_deadline(DEFAULT_ASYNC_BOT_DEADLINE)
+    }
+}
+
+impl BotJobStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_async_deadline(async_deadline: Duration) -> Self {
+        Self { inner: Arc::default(), async_deadline }
+    }
+
+    pub fn async_deadline(&self) -> Duration {
+        self.async_deadline
+    }
+
+    /// Control de un trabajo asíncrono nuevo: su plazo empieza a contar ya.
+    pub fn async_control(&self) -> Arc<SearchControl> {
+        Arc::new(SearchControl::with_deadline(Instant::now() + self.async_deadline))
+    }
+
+    /// Registra un trabajo nuevo para `game_id`, sustituyendo al anterior.
+    /// Falla con 409 si la partida ya tiene uno sin terminar.
+    pub async fn start(
+        &self,
+        game_id: &str,
+        board_size: u32,
+        control: Arc<SearchControl>,
+    ) -> Result<Arc<BotJob>, ApiErrorResponse> {
+        let mut jobs = self.inner.write().await;
+        if jobs.get(game_id).is_some_and(|job| !job.status().is_finished()) {
+            return Err(ApiErrorResponse::conflict(
+                "The bot is already thinking its move",
+                "bot_move_in_progress",
+            ));
+        }
+
+        let job = Arc::new(BotJob {
+            id: uuid::Uuid::new_v4().to_string(),
+            game_id: game_id.to_string(),
+            board_size,
+            control,
+            outcome: Mutex::new(JobOutcome { status: BotJobStatus::Queued, result: None, error: None }),
+        });
+        jobs.insert(game_id.to_string(), job.clone());
+        Ok(job)
+    }
+
+    /// Trabajo `job_id` de la partida `game_id`.
+    pub async fn get(&self, game_id: &str, job_id: &str) -> Option<Arc<BotJob>> {
+        self.inner
+            .read()
+            .await
+            .get(game_id)
+            .filter(|job| job.id == job_id)
+            .cloned()
+    }
+
+    /// Olvida el trabajo de la partida, cancelando la búsqueda si sigue en curso.
+    pub async fn remove(&self, game_id: &str) {
+        if let Some(job) = self.inner.write().await.remove(game_id) {
+            job.control.cancel();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+
+    use crate::Coordinates;
+
+    #[tokio::test]
+    async fn one_unfinished_job_per_game() {
+        let store = BotJobStore::new();
+        let job = store.start("game", 5, Arc::new(SearchControl::new())).await.unwrap();
+        assert_eq!(job.status(), BotJobStatus::Queued);
+
+        let err = store.start("game", 5, Arc::new(SearchControl::new())).await.err().unwrap();
+        assert_eq!(err.0, StatusCode::CONFLICT);
+        assert!(store.start("other", 5, Arc::new(SearchControl::new())).await.is_ok());
+
+        job.mark_running();
+        job.finish(Ok(serde_json::json!({ "ok": true })));
+        let next = store.start("game", 5, Arc::new(SearchControl::new())).await.unwrap();
+        assert!(store.get("game", &job.id).await.is_none());
+        assert!(store.get("game", &next.id).await.is_some());
+    }
+
+    #[test]
+    fn response_reports_progress_and_outcome() {
+        let control = Arc::new(SearchControl::new());
+        let job = BotJob {
+            id: "job".to_string(),
+            game_id: "game".to_string(),
+            board_size: 3,
+            control: control.clone(),
+            outcome: Mutex::new(JobOutcome { status: BotJobStatus::Queued, result: None, error: None }),
+        };
+
+        job.mark_running();
+        control.add_iterations(40);
+        control.propose_best_move(0, Coordinates::new(2, 0, 0), 0.5);
+        let response = job.to_response();
+        assert_eq!(response.status, BotJobStatus::Running);
+        assert_eq!(response.progress.iterations, 40);
+        assert_eq!(response.progress.best_cell_id, Some(0));
+
+        job.finish(Err(ApiErrorResponse::conflict("no move", "bot_no_move")));
+        let response = job.to_response();
+        assert_eq!(response.status, BotJobStatus::Failed);
+        assert_eq!(response.error.unwrap().code, "bot_no_move");
+        assert!(response.result.is_none());
+    }
+
+    #[tokio::test]
+    async fn abandoned_job_frees_the_game() {
+        let store = BotJobStore::new();
+        let job = store.start("game", 5, Arc::new(SearchControl::new())).await.unwrap();
+
+        drop(AbandonOnDrop(job.clone()));
+        assert_eq!(job.status(), BotJobStatus::Failed);
+        assert!(job.control().is_cancelled());
+        assert!(store.start("game", 5, Arc::new(SearchControl::new())).await.is_ok());
+    }
+
+    #[test]
+    fn async_jobs_get_their_own_deadline() {
+        let store = BotJobStore::with_async_deadline(Duration::from_secs(600));
+        let deadline = store.async_control().deadline().unwrap();
+        assert!(deadline > Instant::now() + Duration::from_secs(590));
+    }
+
+    #[tokio::test]
+    async fn remove_cancels_the_search() {
+        let store = BotJobStore::new();
+        let control = Arc::new(SearchControl::new());
+        store.start("game", 5, control.clone()).await.unwrap();
+
+        store.remove("game").await;
+        assert!(control.is_cancelled());
+    }
+}
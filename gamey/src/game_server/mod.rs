@@ -9,15 +9,26 @@
 //! - Identidad opcional (Guest por `X-Client-Id` hoy; User por token mañana)
 //! - Config "recordada" por principal (guest/user)
 
+pub mod admin;
+pub mod analysis;
 pub mod auth;
+pub mod bot_sessions;
 pub mod config;
 pub mod dto;
 pub mod error;
+pub mod events;
+pub mod file_sessions;
 pub mod hvb;
 pub mod hvh;
+pub mod jobs;
 pub mod play;
+pub mod ponder;
+pub mod position;
+pub mod render;
 pub mod sessions;
 pub mod state;
+pub mod sweeper;
+pub mod workers;
 
 use axum::{Router, http, routing::{get, post}};
 use http::Method;
@@ -43,6 +54,7 @@ pub fn create_router(state: GameServerState) -> Router {
     Router::new()
         // Salud
         .route("/status", get(status))
+        .route("/metrics", get(sweeper::metrics))
         // API externa de competición / bots
         .route("/play", get(play::play))
         // Info para UI: límites + bots disponibles
@@ -53,12 +65,24 @@ pub fn create_router(state: GameServerState) -> Router {
         .route("/api/v1/hvh/games", post(hvh::create_game))
         .route("/api/v1/hvh/games/{game_id}", get(hvh::get_game).delete(hvh::delete_game))
         .route("/api/v1/hvh/games/{game_id}/moves", post(hvh::post_move))
+        .route("/api/v1/hvh/join", post(hvh::join_game))
         // HvB
         .route("/api/v1/hvb/games", post(hvb::create_game))
         .route("/api/v1/hvb/games/{game_id}", get(hvb::get_game).delete(hvb::delete_game))
         .route("/api/v1/hvb/games/{game_id}/moves", post(hvb::post_human_move))
         .route("/api/v1/hvb/games/{game_id}/bot-move", post(hvb::post_bot_move))
+        .route("/api/v1/hvb/games/{game_id}/bot-move/{job_id}", get(hvb::get_bot_move_job))
         .route("/api/v1/hvb/games/{game_id}/hint", get(hvb::get_hint))
+        .route("/api/v1/hvb/games/{game_id}/analysis", get(analysis::analyze_hvb_game))
+        // Análisis de posiciones (heatmap, evaluación, variante principal)
+        .route("/api/v1/analysis", get(analysis::analyze_position))
+        // Tablero como imagen SVG
+        .route("/api/v1/games/{game_id}/board.svg", get(render::render_game))
+        .route("/api/v1/render.svg", get(render::render_position))
+        // Eventos en directo (SSE) para jugadores y espectadores
+        .route("/api/v1/games/{game_id}/events", get(events::game_events))
+        // Administración (requiere `--admin-token`)
+        .route("/api/v1/admin/bots/reload", post(admin::reload_bots))
         .with_state(state)
         .layer(cors)
 }
@@ -3,38 +3,49 @@ use axum::{
     Json,
 };
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
-use crate::{BotDecision, Coordinates, GameAction, GameY, YEN};
+use crate::{is_swap_turn, BotDecision, Coordinates, GameAction, RuleSet};
 
-use super::{error::ApiErrorResponse, state::GameServerState, API_V1};
+use super::{error::ApiErrorResponse, position::parse_position, state::GameServerState, API_V1};
 
 const DEFAULT_BOT_ID: &str = "random_bot";
 
 #[derive(Debug, Deserialize)]
 pub struct PlayQuery {
     pub position: Option<String>,
+    /// `yen` o `compact`. Si falta, se detecta a partir de `position`.
+    pub position_format: Option<String>,
     pub bot_id: Option<String>,
     pub api_version: Option<String>,
+    /// Semilla opcional: con la misma posición y semilla el bot decide lo mismo.
+    pub seed: Option<u64>,
+    /// Tiempo máximo de respuesta; los bots MCTS cortan la búsqueda al llegar.
+    pub timeout_ms: Option<u64>,
+    /// Reglas de la partida: `standard` (por defecto) o `swap`. Solo con
+    /// `swap` puede el bot intercambiar en la segunda jugada.
+    pub rules: Option<RuleSet>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum PlayResponse {
     Move { coords: Coordinates },
     Action { action: PlayAction },
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum PlayAction {
     Swap,
     Resign,
 }
 
-/// GET /play?position=<json-yen-url-encoded>&bot_id=<bot>&api_version=v1
+/// GET /play?position=<json-yen-url-encoded>&bot_id=<bot>&api_version=v1[&seed=<u64>][&timeout_ms=<u64>][&rules=standard|swap]
+///
+/// `position` contiene un YEN serializado como JSON dentro del query param,
+/// o bien la posición compacta en base64 URL-safe (ver `notation::compact`).
 ///
-/// `position` contiene un YEN serializado como JSON dentro del query param.
-/// 
 /// La respuesta incluye solo la acción elegida por el bot:
 /// - coords: coordenadas elegidas por el bot
 /// - action: acción especial como swap o resign
@@ -58,23 +69,7 @@ pub async fn play(
         .bot_id
         .unwrap_or_else(|| DEFAULT_BOT_ID.to_string());
 
-    let position_raw = query.position.ok_or_else(|| {
-        ApiErrorResponse::bad_request(
-            "Missing required query parameter: position",
-            "missing_position",
-        )
-    })?;
-
-    let yen: YEN = serde_json::from_str(&position_raw).map_err(|e| {
-        ApiErrorResponse::bad_request(
-            format!("Invalid position parameter. Expected JSON-encoded YEN: {e}"),
-            "invalid_position",
-        )
-    })?;
-
-    let game = GameY::try_from(yen).map_err(|e| {
-        ApiErrorResponse::bad_request(format!("Invalid YEN position: {e}"), "invalid_yen")
-    })?;
+    let game = parse_position(query.position, query.position_format.as_deref())?;
 
     if game.check_game_over() {
         return Err(ApiErrorResponse::conflict(
@@ -97,12 +92,34 @@ pub async fn play(
         )
     })?;
 
-    let decision = bot.choose_action(&game).ok_or_else(|| {
-        ApiErrorResponse::conflict(
-            "Bot could not choose an action for the given position",
-            "no_valid_moves",
-        )
-    })?;
+    if !bot.metadata().supports_board_size(game.board_size()) {
+        return Err(ApiErrorResponse::bad_request(
+            format!("Bot {bot_id} does not play on boards of size {}", game.board_size()),
+            "unsupported_board_size",
+        ));
+    }
+
+    let seed = query.seed;
+    let limit = query.timeout_ms.map(Duration::from_millis);
+    // Con las reglas estándar el segundo jugador no puede intercambiar: el bot coloca.
+    let placement_only = query.rules.unwrap_or(RuleSet::Standard) == RuleSet::Standard && is_swap_turn(&game);
+    let position = game.clone();
+    let decision = state
+        .workers
+        .run(limit, move |control| {
+            if placement_only {
+                bot.choose_move_controlled(&position, seed, control).map(BotDecision::Move)
+            } else {
+                bot.choose_action_controlled(&position, seed, control)
+            }
+        })
+        .await?
+        .ok_or_else(|| {
+            ApiErrorResponse::conflict(
+                "Bot could not choose an action for the given position",
+                "no_valid_moves",
+            )
+        })?;
 
     let response = match decision {
         BotDecision::Move(coords) => {
@@ -149,7 +166,7 @@ mod tests {
 
     use crate::game_server::create_router;
     use crate::game_server::state::GameServerState;
-    use crate::YBot;
+    use crate::{GameY, YBot, YEN};
 
     fn percent_encode(input: &str) -> String {
         input.bytes()
@@ -202,6 +219,31 @@ mod tests {
         assert!(json.get("position").is_none());
     }
 
+    #[tokio::test]
+    async fn play_with_seed_is_reproducible() {
+        let state = GameServerState::new_default();
+        let yen = YEN::from(&GameY::new(7));
+        let uri = format!(
+            "/play?position={}&bot_id=random_bot&seed=1234",
+            build_position_query(&yen)
+        );
+
+        let mut answers = Vec::new();
+        for _ in 0..3 {
+            let response = create_router(state.clone())
+                .oneshot(Request::builder().uri(uri.clone()).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            let json: Value = serde_json::from_slice(&body).unwrap();
+            answers.push(json["coords"].clone());
+        }
+
+        assert!(answers.windows(2).all(|w| w[0] == w[1]));
+    }
+
     #[tokio::test]
     async fn play_uses_default_bot_when_bot_id_is_missing() {
         let app = create_router(GameServerState::new_default());
@@ -271,6 +313,73 @@ mod tests {
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
 
+    #[tokio::test]
+    async fn play_rejects_board_size_the_bot_does_not_support() {
+        let config = crate::BotRegistryConfig::from_json(
+            r#"{ "bots": [ { "type": "random", "name": "pequeno", "max_board_size": 5 } ] }"#,
+        )
+        .unwrap();
+        let app = create_router(GameServerState::with_bots(config.build().unwrap()));
+        let yen = YEN::from(&GameY::new(7));
+        let uri = format!("/play?position={}&bot_id=pequeno", build_position_query(&yen));
+
+        let response = app
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], "unsupported_board_size");
+    }
+
+    #[tokio::test]
+    async fn play_accepts_compact_position() {
+        let app = create_router(GameServerState::new_default());
+        let yen = YEN::new(3, 1, vec!['B', 'R'], "B/../...".to_string());
+
+        let uri = format!(
+            "/play?position={}&bot_id=random_bot",
+            yen.to_compact_string().unwrap()
+        );
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri(uri)
+                    .method("GET")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert!(json.get("coords").is_some());
+    }
+
+    #[tokio::test]
+    async fn play_rejects_invalid_compact_position() {
+        let app = create_router(GameServerState::new_default());
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/play?position=AQMA&position_format=compact")
+                    .method("GET")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
     #[tokio::test]
     async fn play_requires_position() {
         let app = create_router(GameServerState::new_default());
@@ -304,11 +413,7 @@ mod tests {
     #[tokio::test]
     async fn play_can_return_swap_action() {
         let bots = crate::YBotRegistry::new().with_bot(Arc::new(ActionBot));
-        let state = GameServerState {
-            bots: Arc::new(bots),
-            sessions: crate::game_server::sessions::SessionStore::new(),
-            config_store: crate::game_server::state::config_store::ConfigStore::new(),
-        };
+        let state = GameServerState::with_bots(bots);
         let app = create_router(state);
         let yen = YEN::new(3, 0, vec!['B', 'R'], "./../...".to_string());
 
@@ -334,4 +439,100 @@ mod tests {
         let json: Value = serde_json::from_slice(&body).unwrap();
         assert_eq!(json, serde_json::json!({ "action": "swap" }));
     }
+
+    #[tokio::test]
+    async fn play_only_swaps_with_swap_rules() {
+        let bot = crate::MctsCompletoBot::new("intercambio", 2000).with_swap();
+        let state = GameServerState::with_bots(crate::YBotRegistry::new().with_bot(Arc::new(bot)));
+        let mut game = GameY::new(4);
+        game.add_move(crate::Movement::Placement {
+            player: crate::PlayerId::new(0),
+            coords: Coordinates::new(1, 1, 1),
+        })
+        .unwrap();
+        let position = build_position_query(&YEN::from(&game));
+
+        for (rules, expected_swap) in [("", false), ("&rules=standard", false), ("&rules=swap", true)] {
+            let uri = format!("/play?position={position}&bot_id=intercambio&seed=11{rules}");
+            let response = create_router(state.clone())
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            let json: Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(json["action"] == "swap", expected_swap, "rules {rules:?}: {json}");
+        }
+    }
+
+    #[tokio::test]
+    async fn play_returns_resign_from_mcts_bot() {
+        let state = GameServerState::with_bots(crate::YBotRegistry::new().with_bot(Arc::new(
+            crate::MctsCompletoBot::new("mcts_abandona", 100).with_resign_threshold(1.1),
+        )));
+        let yen = YEN::from(&GameY::new(4));
+        let uri = format!("/play?position={}&bot_id=mcts_abandona", build_position_query(&yen));
+
+        let response = create_router(state)
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json, serde_json::json!({ "action": "resign" }));
+    }
+
+    #[tokio::test]
+    async fn play_timeout_cuts_the_search() {
+        let state = GameServerState::with_bots(
+            crate::YBotRegistry::new().with_bot(std::sync::Arc::new(crate::MctsCompletoBot::new("lento", 100_000_000))),
+        );
+        let yen = YEN::from(&GameY::new(9));
+        let uri = format!(
+            "/play?position={}&bot_id=lento&timeout_ms=200",
+            build_position_query(&yen)
+        );
+
+        let start = std::time::Instant::now();
+        let response = create_router(state)
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn play_answers_503_when_workers_are_busy() {
+        let mut state = GameServerState::new_default();
+        state.workers = crate::game_server::workers::BotWorkerPool::new(1, 0, Duration::from_secs(5));
+        let busy = state.workers.clone();
+        let running = tokio::spawn(async move {
+            busy.run(Some(Duration::from_millis(300)), |control| {
+                while !control.should_stop() {
+                    std::thread::sleep(Duration::from_millis(5));
+                }
+            })
+            .await
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let yen = YEN::from(&GameY::new(5));
+        let uri = format!("/play?position={}", build_position_query(&yen));
+        let response = create_router(state)
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert!(response.headers().get(axum::http::header::RETRY_AFTER).is_some());
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], "bots_busy");
+        running.await.unwrap().unwrap();
+    }
 }
@@ -0,0 +1,204 @@
+//! ponder.rs
+//!
+//! "Pensar en el turno del rival" (pondering) en partidas HvB: mientras el
+//! humano piensa, el bot amplía en segundo plano el árbol de su sesión
+//! (`BotSession::ponder`), de modo que `bot-move` parte de una búsqueda ya
+//! avanzada en lugar de empezar de cero.
+//!
+//! Límites de CPU:
+//! - cada partida piensa como mucho en un hilo y durante `max_time` por turno
+//!   (además del tope de iteraciones propio de la sesión)
+//! - como mucho `max_concurrent` partidas piensan a la vez; si no hay hueco,
+//!   simplemente no se piensa en ese turno
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::Semaphore;
+
+use crate::GameY;
+
+use super::bot_sessions::SharedBotSession;
+
+/// Tiempo máximo que se piensa durante un turno del humano.
+pub const DEFAULT_PONDER_MAX_TIME: Duration = Duration::from_secs(30);
+
+#[derive(Clone)]
+pub struct PonderManager {
+    permits: Arc<Semaphore>,
+    max_time: Duration,
+    /// Señal de parada de la búsqueda en curso de cada partida.
+    active: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
+}
+
+impl PonderManager {
+    pub fn new(max_concurrent: usize, max_time: Duration) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(max_concurrent)),
+            max_time,
+            active: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Una partida pensando a la vez por núcleo disponible.
+    pub fn new_default() -> Self {
+        let cores = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        Self::new(cores, DEFAULT_PONDER_MAX_TIME)
+    }
+
+    fn active(&self) -> std::sync::MutexGuard<'_, HashMap<String, Arc<AtomicBool>>> {
+        self.active.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Empieza a pensar en segundo plano sobre `board` (turno del humano).
+    ///
+    /// Si la partida ya estaba pensando se detiene la búsqueda anterior. No
+    /// hace nada si ya se alcanzó el máximo de partidas pensando a la vez.
+    /// Devuelve si se ha lanzado la búsqueda.
+    pub fn start(&self, game_id: &str, bot_session: SharedBotSession, board: GameY) -> bool {
+        self.stop(game_id);
+
+        let Ok(permit) = self.permits.clone().try_acquire_owned() else {
+            return false;
+        };
+
+        let stop = Arc::new(AtomicBool::new(false));
+        self.active().insert(game_id.to_string(), stop.clone());
+
+        let manager = self.clone();
+        let game_id = game_id.to_string();
+        let max_time = self.max_time;
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            if !stop.load(Ordering::Relaxed) {
+                bot_session
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .ponder(&board, &stop, max_time);
+            }
+
+            // Solo borramos la entrada si sigue siendo la nuestra.
+            let mut active = manager.active();
+            if active.get(&game_id).is_some_and(|current| Arc::ptr_eq(current, &stop)) {
+                active.remove(&game_id);
+            }
+        });
+
+        true
+    }
+
+    /// Pide que termine la búsqueda en segundo plano de la partida (si la hay).
+    ///
+    /// La búsqueda termina en la siguiente iteración; quien necesite la
+    /// sesión del bot solo tiene que esperar a su `Mutex`.
+    pub fn stop(&self, game_id: &str) {
+        if let Some(stop) = self.active().remove(game_id) {
+            stop.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub fn is_pondering(&self, game_id: &str) -> bool {
+        self.active().contains_key(game_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::time::Instant;
+
+    use crate::{BotDecision, BotSession};
+
+    /// Sesión de prueba que "piensa" hasta que se le pide parar o se agota el tiempo.
+    struct CountingSession {
+        ponders: Arc<AtomicUsize>,
+    }
+
+    impl BotSession for CountingSession {
+        fn choose_action(&mut self, _board: &GameY) -> Option<BotDecision> {
+            None
+        }
+
+        fn ponder(&mut self, _board: &GameY, stop: &AtomicBool, max_time: Duration) {
+            self.ponders.fetch_add(1, Ordering::SeqCst);
+            let start = Instant::now();
+            while !stop.load(Ordering::Relaxed) && start.elapsed() < max_time {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        }
+    }
+
+    fn counting_session() -> (SharedBotSession, Arc<AtomicUsize>) {
+        let ponders = Arc::new(AtomicUsize::new(0));
+        let session: SharedBotSession = Arc::new(Mutex::new(Box::new(CountingSession {
+            ponders: ponders.clone(),
+        })));
+        (session, ponders)
+    }
+
+    async fn wait_until_idle(manager: &PonderManager, game_id: &str) {
+        let start = Instant::now();
+        while manager.is_pondering(game_id) {
+            assert!(start.elapsed() < Duration::from_secs(5), "ponder did not stop");
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn stop_ends_background_search() {
+        let manager = PonderManager::new(2, Duration::from_secs(60));
+        let (session, ponders) = counting_session();
+
+        assert!(manager.start("game-1", session.clone(), GameY::new(3)));
+        assert!(manager.is_pondering("game-1"));
+
+        manager.stop("game-1");
+        assert!(!manager.is_pondering("game-1"));
+
+        // La sesión queda libre en cuanto la búsqueda ve la señal.
+        tokio::task::spawn_blocking(move || drop(session.lock().unwrap()))
+            .await
+            .unwrap();
+        assert!(ponders.load(Ordering::SeqCst) <= 1);
+    }
+
+    #[tokio::test]
+    async fn search_ends_after_max_time() {
+        let manager = PonderManager::new(1, Duration::from_millis(20));
+        let (session, ponders) = counting_session();
+
+        assert!(manager.start("game-1", session, GameY::new(3)));
+        wait_until_idle(&manager, "game-1").await;
+        assert_eq!(ponders.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn concurrent_searches_are_bounded() {
+        let manager = PonderManager::new(1, Duration::from_secs(60));
+        let (first, _) = counting_session();
+        let (second, _) = counting_session();
+
+        assert!(manager.start("game-1", first, GameY::new(3)));
+        assert!(!manager.start("game-2", second, GameY::new(3)));
+        assert!(!manager.is_pondering("game-2"));
+
+        manager.stop("game-1");
+    }
+
+    #[tokio::test]
+    async fn restarting_replaces_previous_search() {
+        let manager = PonderManager::new(2, Duration::from_secs(60));
+        let (session, ponders) = counting_session();
+
+        assert!(manager.start("game-1", session.clone(), GameY::new(3)));
+        assert!(manager.start("game-1", session, GameY::new(3)));
+        assert!(manager.is_pondering("game-1"));
+
+        manager.stop("game-1");
+        wait_until_idle(&manager, "game-1").await;
+        assert!(ponders.load(Ordering::SeqCst) <= 2);
+    }
+}
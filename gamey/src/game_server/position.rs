@@ -0,0 +1,127 @@
+//! position.rs
+//!
+//! Lectura del parámetro `position` compartida por los endpoints que reciben
+//! una posición arbitraria (`/play`, `/api/v1/render.svg`).
+//!
+//! Se aceptan dos formatos:
+//! - `yen`: YEN serializado como JSON (url-encoded)
+//! - `compact`: codificación binaria compacta en base64 URL-safe
+//!
+//! Si no se indica `position_format`, se detecta automáticamente: un JSON
+//! siempre empieza por `{`, algo imposible en base64.
+
+use crate::{GameY, YEN};
+
+use super::error::ApiErrorResponse;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionFormat {
+    Yen,
+    Compact,
+}
+
+impl PositionFormat {
+    pub fn parse(raw: Option<&str>) -> Result<Option<Self>, ApiErrorResponse> {
+        match raw {
+            None => Ok(None),
+            Some("yen") => Ok(Some(PositionFormat::Yen)),
+            Some("compact") => Ok(Some(PositionFormat::Compact)),
+            Some(other) => Err(ApiErrorResponse::bad_request(
+                format!("Unsupported position_format: {other}. Expected yen or compact"),
+                "unsupported_position_format",
+            )),
+        }
+    }
+
+    fn detect(raw: &str) -> Self {
+        if raw.trim_start().starts_with('{') {
+            PositionFormat::Yen
+        } else {
+            PositionFormat::Compact
+        }
+    }
+}
+
+/// Convierte el parámetro `position` en un `GameY`.
+pub fn parse_position(
+    raw: Option<String>,
+    format: Option<&str>,
+) -> Result<GameY, ApiErrorResponse> {
+    let raw = raw.ok_or_else(|| {
+        ApiErrorResponse::bad_request(
+            "Missing required query parameter: position",
+            "missing_position",
+        )
+    })?;
+
+    let format = PositionFormat::parse(format)?.unwrap_or_else(|| PositionFormat::detect(&raw));
+
+    let yen: YEN = match format {
+        PositionFormat::Yen => serde_json::from_str(&raw).map_err(|e| {
+            ApiErrorResponse::bad_request(
+                format!("Invalid position parameter. Expected JSON-encoded YEN: {e}"),
+                "invalid_position",
+            )
+        })?,
+        PositionFormat::Compact => YEN::from_compact_string(&raw).map_err(|e| {
+            ApiErrorResponse::bad_request(
+                format!("Invalid position parameter. Expected compact position: {e}"),
+                "invalid_position",
+            )
+        })?,
+    };
+
+    GameY::try_from(yen).map_err(|e| {
+        ApiErrorResponse::bad_request(format!("Invalid YEN position: {e}"), "invalid_yen")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+
+    fn sample_yen() -> YEN {
+        YEN::new(3, 1, vec!['B', 'R'], "B/../...".to_string())
+    }
+
+    #[test]
+    fn parse_position_requires_value() {
+        let err = parse_position(None, None).unwrap_err();
+        assert_eq!(err.0, StatusCode::BAD_REQUEST);
+        assert_eq!(err.1.code, "missing_position");
+    }
+
+    #[test]
+    fn parse_position_detects_yen_json() {
+        let raw = serde_json::to_string(&sample_yen()).unwrap();
+        let game = parse_position(Some(raw), None).unwrap();
+        assert_eq!(YEN::from(&game).layout(), "B/../...");
+    }
+
+    #[test]
+    fn parse_position_detects_compact() {
+        let raw = sample_yen().to_compact_string().unwrap();
+        let game = parse_position(Some(raw), None).unwrap();
+        assert_eq!(YEN::from(&game).layout(), "B/../...");
+    }
+
+    #[test]
+    fn parse_position_respects_explicit_format() {
+        let raw = sample_yen().to_compact_string().unwrap();
+        let err = parse_position(Some(raw), Some("yen")).unwrap_err();
+        assert_eq!(err.1.code, "invalid_position");
+    }
+
+    #[test]
+    fn parse_position_rejects_unknown_format() {
+        let err = parse_position(Some("{}".to_string()), Some("fen")).unwrap_err();
+        assert_eq!(err.1.code, "unsupported_position_format");
+    }
+
+    #[test]
+    fn parse_position_rejects_invalid_compact() {
+        let err = parse_position(Some("@@@".to_string()), None).unwrap_err();
+        assert_eq!(err.1.code, "invalid_position");
+    }
+}
@@ -0,0 +1,295 @@
+//! render.rs
+//!
+//! Endpoints que devuelven el tablero como imagen SVG (`image/svg+xml`):
+//! - a partir de una partida en curso (`game_id`)
+//! - a partir de una posición YEN arbitraria
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{GameY, SvgLabels, SvgOptions};
+
+use super::auth::resolve_principal;
+use super::error::ApiErrorResponse;
+use super::position::parse_position;
+use super::sessions::SessionRole;
+use super::state::GameServerState;
+
+pub const SVG_CONTENT_TYPE: &str = "image/svg+xml";
+
+/// Opciones de dibujo comunes a ambos endpoints.
+#[derive(Debug, Default, Deserialize)]
+pub struct RenderQuery {
+    /// `none` (por defecto), `index` o `coords`.
+    pub labels: Option<String>,
+    /// Marcar la última jugada (por defecto `true`).
+    pub last_move: Option<bool>,
+    /// Resaltar la cadena ganadora (por defecto `true`).
+    pub winning: Option<bool>,
+    /// Valores en `[0, 1]` separados por comas, uno por celda (índice).
+    pub heatmap: Option<String>,
+    /// Dibujar puentes, plantillas de borde, amenazas y grupos que ya unen
+    /// los tres lados virtualmente (por defecto `false`).
+    pub connections: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RenderPositionQuery {
+    pub position: Option<String>,
+    pub position_format: Option<String>,
+}
+
+fn parse_labels(raw: Option<&str>) -> Result<SvgLabels, ApiErrorResponse> {
+    match raw {
+        None | Some("none") => Ok(SvgLabels::None),
+        Some("index") => Ok(SvgLabels::Index),
+        Some("coords") => Ok(SvgLabels::Coordinates),
+        Some(other) => Err(ApiErrorResponse::bad_request(
+            format!("Invalid labels value: {other}. Expected none, index or coords"),
+            "invalid_labels",
+        )),
+    }
+}
+
+fn parse_heatmap(raw: Option<&str>) -> Result<Option<Vec<f32>>, ApiErrorResponse> {
+    let Some(raw) = raw.filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+
+    raw.split(',')
+        .map(|v| v.trim().parse::<f32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+        .map_err(|_| {
+            ApiErrorResponse::bad_request(
+                "Invalid heatmap. Expected comma-separated numbers",
+                "invalid_heatmap",
+            )
+        })
+}
+
+fn svg_options(query: &RenderQuery) -> Result<SvgOptions, ApiErrorResponse> {
+    Ok(SvgOptions {
+        labels: parse_labels(query.labels.as_deref())?,
+        show_last_move: query.last_move.unwrap_or(true),
+        highlight_winning_chain: query.winning.unwrap_or(true),
+        heatmap: parse_heatmap(query.heatmap.as_deref())?,
+        show_connections: query.connections.unwrap_or(false),
+        ..SvgOptions::default()
+    })
+}
+
+fn svg_response(game: &GameY, options: &SvgOptions) -> Response {
+    ([(header::CONTENT_TYPE, SVG_CONTENT_TYPE)], game.render_svg(options)).into_response()
+}
+
+/// GET /api/v1/games/{game_id}/board.svg
+///
+/// Dibuja una partida (HvH o HvB) del principal que hace la petición.
+pub async fn render_game(
+    State(state): State<GameServerState>,
+    headers: HeaderMap,
+    Path(game_id): Path<String>,
+    Query(query): Query<RenderQuery>,
+) -> Result<Response, ApiErrorResponse> {
+    let principal = resolve_principal(&headers);
+    let game_id = Uuid::parse_str(&game_id)
+        .map(|u| u.to_string())
+        .map_err(|_| ApiErrorResponse::bad_request("Invalid game_id", "invalid_game_id"))?;
+
+    let session = state
+        .sessions
+        .assert_role(&principal, &game_id, SessionRole::Spectator)
+        .await
+        .map_err(|_| ApiErrorResponse::not_found("Game not found", "game_not_found"))?;
+
+    let options = svg_options(&query)?;
+    Ok(svg_response(&session.game, &options))
+}
+
+/// GET /api/v1/render.svg?position=<json-yen-url-encoded>
+///
+/// Dibuja una posición (YEN o compacta) sin necesidad de crear una partida.
+pub async fn render_position(
+    Query(query): Query<RenderPositionQuery>,
+    Query(render): Query<RenderQuery>,
+) -> Result<Response, ApiErrorResponse> {
+    let game = parse_position(query.position, query.position_format.as_deref())?;
+
+    let options = svg_options(&render)?;
+    Ok(svg_response(&game, &options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    use crate::YEN;
+    use crate::game_server::create_router;
+
+    fn percent_encode(input: &str) -> String {
+        input
+            .bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                    (b as char).to_string()
+                }
+                _ => format!("%{:02X}", b),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parse_labels_accepts_known_values() {
+        assert_eq!(parse_labels(None).unwrap(), SvgLabels::None);
+        assert_eq!(parse_labels(Some("index")).unwrap(), SvgLabels::Index);
+        assert_eq!(parse_labels(Some("coords")).unwrap(), SvgLabels::Coordinates);
+    }
+
+    #[test]
+    fn parse_labels_rejects_unknown_value() {
+        let err = parse_labels(Some("letters")).unwrap_err();
+        assert_eq!(err.0, StatusCode::BAD_REQUEST);
+        assert_eq!(err.1.code, "invalid_labels");
+    }
+
+    #[test]
+    fn parse_heatmap_reads_comma_separated_values() {
+        assert_eq!(parse_heatmap(Some("0.5, 1,0")).unwrap(), Some(vec![0.5, 1.0, 0.0]));
+        assert_eq!(parse_heatmap(None).unwrap(), None);
+        assert_eq!(parse_heatmap(Some("")).unwrap(), None);
+    }
+
+    #[test]
+    fn parse_heatmap_rejects_garbage() {
+        let err = parse_heatmap(Some("0.5,hot")).unwrap_err();
+        assert_eq!(err.1.code, "invalid_heatmap");
+    }
+
+    #[tokio::test]
+    async fn render_position_returns_svg() {
+        let app = create_router(GameServerState::new_default());
+        let yen = YEN::new(3, 0, vec!['B', 'R'], "B/../...".to_string());
+        let uri = format!(
+            "/api/v1/render.svg?position={}&labels=index&last_move=false",
+            percent_encode(&serde_json::to_string(&yen).unwrap())
+        );
+
+        let response = app
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            SVG_CONTENT_TYPE
+        );
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let svg = String::from_utf8(body.to_vec()).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(r#"class="stone player-0""#));
+        assert!(!svg.contains("last-move"));
+    }
+
+    #[tokio::test]
+    async fn render_position_can_draw_connections() {
+        let app = create_router(GameServerState::new_default());
+        let yen = YEN::new(4, 1, vec!['B', 'R'], "./../.B./....".to_string());
+        let uri = format!(
+            "/api/v1/render.svg?position={}&connections=true",
+            percent_encode(&serde_json::to_string(&yen).unwrap())
+        );
+
+        let response = app
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let svg = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(svg.matches(r#"class="edge-template player-0""#).count(), 3);
+        assert!(svg.contains(r#"class="virtual-win player-0""#));
+    }
+
+    #[tokio::test]
+    async fn render_position_requires_position() {
+        let app = create_router(GameServerState::new_default());
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/v1/render.svg")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn render_game_returns_svg_for_owner() {
+        let state = GameServerState::new_default();
+        let app = create_router(state);
+
+        let created = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/v1/hvh/games")
+                    .method("POST")
+                    .header("content-type", "application/json")
+                    .header("x-client-id", "render-owner")
+                    .body(Body::from("{}"))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = created.into_body().collect().await.unwrap().to_bytes();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let game_id = json["game_id"].as_str().unwrap().to_string();
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/v1/games/{game_id}/board.svg"))
+                    .header("x-client-id", "render-owner")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            SVG_CONTENT_TYPE
+        );
+
+        let other = app
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/v1/games/{game_id}/board.svg"))
+                    .header("x-client-id", "someone-else")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(other.status(), StatusCode::NOT_FOUND);
+    }
+}
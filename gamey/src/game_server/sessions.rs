@@ -1,9 +1,21 @@
 //! sessions.rs
 //!
-//! Store in-memory de partidas activas.
+//! Store de partidas activas (`SessionStore`):
+//! - `MemorySessionStore`: en memoria (por defecto)
+//! - `FileSessionStore` (`file_sessions.rs`): además las guarda en disco y las
+//!   recupera al arrancar (`--sessions <fichero>`)
+//!
+//! Acceso a una sesión (`SessionRole`):
+//! - el creador (`owner_key`) puede todo, también borrarla
+//! - en HvH online, quien ocupa un asiento juega con ese jugador
+//! - en HvH online, cualquiera que conozca el `game_id` puede mirarla
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::SystemTime;
+
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 
 use crate::GameY;
@@ -11,6 +23,37 @@ use crate::GameY;
 use super::auth::Principal;
 use super::dto::{GameConfig, GameMode, Winner};
 
+/// Asientos de una partida HvH entre dos clientes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HvhSeats {
+    /// Código que el creador comparte con su rival para que ocupe el asiento libre.
+    pub join_code: String,
+    /// Clave del principal sentado en cada asiento (jugador 0 y jugador 1).
+    pub players: [Option<String>; 2],
+}
+
+/// Qué puede hacer un principal con una sesión (de menos a más).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SessionRole {
+    /// Solo consultar el estado.
+    Spectator,
+    /// Jugar con su asiento.
+    Player,
+    /// Creador de la partida.
+    Owner,
+}
+
+/// No se ha podido ocupar un asiento con el código.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinError {
+    UnknownCode,
+    Full,
+}
+
+/// El dueño de la partida ya tiene el máximo de partidas sin terminar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameLimitReached;
+
 #[derive(Debug, Clone)]
 pub struct GameSession {
     pub owner_key: String,
@@ -22,52 +65,313 @@ pub struct GameSession {
     // Estado específico HvB
     pub hvb_next_is_human: Option<bool>,
     pub hvb_winner: Option<Winner>,
+    /// Semilla de las decisiones del bot (partidas reproducibles).
+    pub hvb_seed: Option<u64>,
 
     // Estado específico HvH
     pub hvh_next_player: Option<u8>,
     pub hvh_winner: Option<u8>,
+    /// Asientos si la partida HvH se juega entre dos clientes (`None` = un
+    /// solo cliente mueve por los dos jugadores).
+    pub hvh_seats: Option<HvhSeats>,
+
+    /// Último cambio guardado (lo pone el store); decide cuándo caduca.
+    pub last_activity: SystemTime,
 }
 
+/// Lo que el barrido de partidas necesita saber de cada una.
 #[derive(Debug, Clone)]
-pub struct SessionStore {
-    inner: Arc<RwLock<HashMap<String, GameSession>>>,
+pub struct SessionSummary {
+    pub game_id: String,
+    pub owner_key: String,
+    pub finished: bool,
+    pub last_activity: SystemTime,
 }
 
-impl SessionStore {
-    pub fn new() -> Self {
-        Self { inner: Arc::new(RwLock::new(HashMap::new())) }
+impl GameSession {
+    /// Marca la partida como usada ahora.
+    pub fn touch(&mut self) {
+        self.last_activity = SystemTime::now();
+    }
+
+    fn summary(&self, game_id: &str) -> SessionSummary {
+        SessionSummary {
+            game_id: game_id.to_string(),
+            owner_key: self.owner_key.clone(),
+            finished: self.game.check_game_over(),
+            last_activity: self.last_activity,
+        }
     }
 
-    pub async fn insert(&self, game_id: String, session: GameSession) {
-        self.inner.write().await.insert(game_id, session);
+    /// Asiento que ocupa el principal con clave `key` en una partida HvH online.
+    pub fn seat_of(&self, key: &str) -> Option<u8> {
+        let seats = self.hvh_seats.as_ref()?;
+        seats
+            .players
+            .iter()
+            .position(|player| player.as_deref() == Some(key))
+            .map(|seat| seat as u8)
     }
 
-    pub async fn get(&self, game_id: &str) -> Option<GameSession> {
-        self.inner.read().await.get(game_id).cloned()
+    pub fn role_of(&self, principal: &Principal) -> Option<SessionRole> {
+        let key = principal.key();
+        if self.owner_key == key {
+            Some(SessionRole::Owner)
+        } else if self.seat_of(&key).is_some() {
+            Some(SessionRole::Player)
+        } else if self.hvh_seats.is_some() {
+            Some(SessionRole::Spectator)
+        } else {
+            None
+        }
     }
+}
 
-    pub async fn remove(&self, game_id: &str) -> Option<GameSession> {
-        self.inner.write().await.remove(game_id)
+/// Almacén de partidas por `game_id`.
+///
+/// Las implementaciones guardan la partida completa en cada `insert`/`update`
+/// y actualizan su `last_activity` (también al ocupar un asiento con `join`);
+/// quien llama trabaja con copias (`GameSession` es `Clone`).
+pub trait SessionStore: Send + Sync {
+    fn insert(&self, game_id: String, session: GameSession) -> BoxFuture<'_, ()>;
+
+    /// Como `insert`, pero solo si el dueño de la partida tiene menos de `max`
+    /// partidas sin terminar (0 = sin límite). Se cuenta e inserta a la vez, así
+    /// que dos creaciones simultáneas no pueden pasarse del límite.
+    fn insert_limited(
+        &self,
+        game_id: String,
+        session: GameSession,
+        max: usize,
+    ) -> BoxFuture<'_, Result<(), GameLimitReached>>;
+
+    fn get<'a>(&'a self, game_id: &'a str) -> BoxFuture<'a, Option<GameSession>>;
+
+    fn remove<'a>(&'a self, game_id: &'a str) -> BoxFuture<'a, Option<GameSession>>;
+
+    /// Sustituye una partida existente; falla si no existe.
+    fn update<'a>(&'a self, game_id: &'a str, session: GameSession) -> BoxFuture<'a, Result<(), ()>>;
+
+    /// Sienta al principal con clave `key` en el asiento libre de la partida
+    /// con código `join_code`. Si ya estaba sentado, devuelve su asiento.
+    fn join<'a>(
+        &'a self,
+        join_code: &'a str,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<(String, GameSession, u8), JoinError>>;
+
+    /// Indica si algún HvH online usa ya este código.
+    fn join_code_in_use<'a>(&'a self, join_code: &'a str) -> BoxFuture<'a, bool>;
+
+    /// Número de partidas guardadas.
+    fn count(&self) -> BoxFuture<'_, usize>;
+
+    /// Resumen de todas las partidas (dueño, si terminó y última actividad).
+    fn summaries(&self) -> BoxFuture<'_, Vec<SessionSummary>>;
+
+    fn assert_owner<'a>(
+        &'a self,
+        principal: &'a Principal,
+        game_id: &'a str,
+    ) -> BoxFuture<'a, Result<GameSession, ()>> {
+        self.assert_role(principal, game_id, SessionRole::Owner)
     }
 
-    pub async fn assert_owner(&self, principal: &Principal, game_id: &str) -> Result<GameSession, ()> {
-        let s = self.get(game_id).await.ok_or(())?;
-        if s.owner_key == principal.key() {
-            Ok(s)
-        } else {
-            Err(())
+    /// Devuelve la sesión si el principal tiene al menos el rol `min`.
+    fn assert_role<'a>(
+        &'a self,
+        principal: &'a Principal,
+        game_id: &'a str,
+        min: SessionRole,
+    ) -> BoxFuture<'a, Result<GameSession, ()>> {
+        Box::pin(async move {
+            let s = self.get(game_id).await.ok_or(())?;
+            match s.role_of(principal) {
+                Some(role) if role >= min => Ok(s),
+                _ => Err(()),
+            }
+        })
+    }
+}
+
+/// Store compartido por los handlers.
+pub type SharedSessionStore = Arc<dyn SessionStore>;
+
+/// Partidas y, por dueño, los `game_id` de las suyas (para contarlas sin
+/// recorrer todas).
+#[derive(Debug, Default)]
+struct Sessions {
+    games: HashMap<String, GameSession>,
+    by_owner: HashMap<String, HashSet<String>>,
+}
+
+impl Sessions {
+    fn insert(&mut self, game_id: String, session: GameSession) {
+        if let Some(previous) = self.games.get(&game_id).filter(|previous| previous.owner_key != session.owner_key) {
+            let owner_key = previous.owner_key.clone();
+            self.forget_owner(&owner_key, &game_id);
         }
+        self.by_owner.entry(session.owner_key.clone()).or_default().insert(game_id.clone());
+        self.games.insert(game_id, session);
     }
 
-    pub async fn update(&self, game_id: &str, session: GameSession) -> Result<(), ()> {
-        let mut w = self.inner.write().await;
-        if w.contains_key(game_id) {
-            w.insert(game_id.to_string(), session);
-            Ok(())
-        } else {
-            Err(())
+    fn remove(&mut self, game_id: &str) -> Option<GameSession> {
+        let session = self.games.remove(game_id)?;
+        self.forget_owner(&session.owner_key, game_id);
+        Some(session)
+    }
+
+    fn forget_owner(&mut self, owner_key: &str, game_id: &str) {
+        if let Some(games) = self.by_owner.get_mut(owner_key) {
+            games.remove(game_id);
+            if games.is_empty() {
+                self.by_owner.remove(owner_key);
+            }
         }
     }
+
+    /// Partidas sin terminar de `owner_key`.
+    fn ongoing_of(&self, owner_key: &str) -> usize {
+        self.by_owner.get(owner_key).map_or(0, |games| {
+            games
+                .iter()
+                .filter_map(|game_id| self.games.get(game_id))
+                .filter(|session| !session.game.check_game_over())
+                .count()
+        })
+    }
+}
+
+/// Partidas solo en memoria: se pierden al reiniciar el servidor.
+#[derive(Debug, Clone, Default)]
+pub struct MemorySessionStore {
+    inner: Arc<RwLock<Sessions>>,
+}
+
+impl MemorySessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(super) fn from_sessions(sessions: HashMap<String, GameSession>) -> Self {
+        let mut inner = Sessions::default();
+        for (game_id, session) in sessions {
+            inner.insert(game_id, session);
+        }
+        Self { inner: Arc::new(RwLock::new(inner)) }
+    }
+
+    /// Copia de todas las partidas.
+    pub(super) async fn entries(&self) -> Vec<(String, GameSession)> {
+        self.inner
+            .read()
+            .await
+            .games
+            .iter()
+            .map(|(game_id, session)| (game_id.clone(), session.clone()))
+            .collect()
+    }
+}
+
+impl SessionStore for MemorySessionStore {
+    fn insert(&self, game_id: String, mut session: GameSession) -> BoxFuture<'_, ()> {
+        Box::pin(async move {
+            session.touch();
+            self.inner.write().await.insert(game_id, session);
+        })
+    }
+
+    fn insert_limited(
+        &self,
+        game_id: String,
+        mut session: GameSession,
+        max: usize,
+    ) -> BoxFuture<'_, Result<(), GameLimitReached>> {
+        Box::pin(async move {
+            let mut w = self.inner.write().await;
+            if max > 0 && w.ongoing_of(&session.owner_key) >= max {
+                return Err(GameLimitReached);
+            }
+            session.touch();
+            w.insert(game_id, session);
+            Ok(())
+        })
+    }
+
+    fn get<'a>(&'a self, game_id: &'a str) -> BoxFuture<'a, Option<GameSession>> {
+        Box::pin(async move { self.inner.read().await.games.get(game_id).cloned() })
+    }
+
+    fn remove<'a>(&'a self, game_id: &'a str) -> BoxFuture<'a, Option<GameSession>> {
+        Box::pin(async move { self.inner.write().await.remove(game_id) })
+    }
+
+    fn update<'a>(&'a self, game_id: &'a str, mut session: GameSession) -> BoxFuture<'a, Result<(), ()>> {
+        Box::pin(async move {
+            let mut w = self.inner.write().await;
+            if w.games.contains_key(game_id) {
+                session.touch();
+                w.insert(game_id.to_string(), session);
+                Ok(())
+            } else {
+                Err(())
+            }
+        })
+    }
+
+    fn join<'a>(
+        &'a self,
+        join_code: &'a str,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<(String, GameSession, u8), JoinError>> {
+        Box::pin(async move {
+            let mut w = self.inner.write().await;
+            let (game_id, session) = w
+                .games
+                .iter_mut()
+                .find(|(_, s)| s.hvh_seats.as_ref().is_some_and(|seats| seats.join_code == join_code))
+                .ok_or(JoinError::UnknownCode)?;
+
+            let seat = match session.seat_of(key) {
+                Some(seat) => seat,
+                None => {
+                    let seats = session.hvh_seats.as_mut().ok_or(JoinError::UnknownCode)?;
+                    let free = seats.players.iter().position(Option::is_none).ok_or(JoinError::Full)?;
+                    seats.players[free] = Some(key.to_string());
+                    free as u8
+                }
+            };
+            session.touch();
+            Ok((game_id.clone(), session.clone(), seat))
+        })
+    }
+
+    fn join_code_in_use<'a>(&'a self, join_code: &'a str) -> BoxFuture<'a, bool> {
+        Box::pin(async move {
+            self.inner
+                .read()
+                .await
+                .games
+                .values()
+                .any(|s| s.hvh_seats.as_ref().is_some_and(|seats| seats.join_code == join_code))
+        })
+    }
+
+    fn count(&self) -> BoxFuture<'_, usize> {
+        Box::pin(async move { self.inner.read().await.games.len() })
+    }
+
+    fn summaries(&self) -> BoxFuture<'_, Vec<SessionSummary>> {
+        Box::pin(async move {
+            self.inner
+                .read()
+                .await
+                .games
+                .iter()
+                .map(|(game_id, session)| session.summary(game_id))
+                .collect()
+        })
+    }
 }
 
 #[cfg(test)]
@@ -92,15 +396,32 @@ mod tests {
 
             hvb_next_is_human: None,
             hvb_winner: None,
+            hvb_seed: None,
 
             hvh_next_player: Some(0),
             hvh_winner: None,
+            hvh_seats: None,
+            last_activity: SystemTime::now(),
+        }
+    }
+
+    fn online_session(owner: &Principal) -> GameSession {
+        GameSession {
+            hvh_seats: Some(HvhSeats {
+                join_code: "ABC123".to_string(),
+                players: [Some(owner.key()), None],
+            }),
+            ..sample_session(owner.key())
         }
     }
 
+    fn guest(client_id: &str) -> Principal {
+        Principal::Guest { client_id: client_id.to_string() }
+    }
+
     #[tokio::test]
     async fn insert_and_get_session() {
-        let store = SessionStore::new();
+        let store = MemorySessionStore::new();
         let session = sample_session("guest:abc".to_string());
 
         store.insert("game-1".to_string(), session.clone()).await;
@@ -112,13 +433,13 @@ mod tests {
 
     #[tokio::test]
     async fn get_missing_session_returns_none() {
-        let store = SessionStore::new();
+        let store = MemorySessionStore::new();
         assert!(store.get("missing").await.is_none());
     }
 
     #[tokio::test]
     async fn remove_session_returns_removed_value() {
-        let store = SessionStore::new();
+        let store = MemorySessionStore::new();
         let session = sample_session("guest:abc".to_string());
 
         store.insert("game-2".to_string(), session).await;
@@ -130,7 +451,7 @@ mod tests {
 
     #[tokio::test]
     async fn assert_owner_returns_session_for_owner() {
-        let store = SessionStore::new();
+        let store = MemorySessionStore::new();
         let principal = Principal::Guest {
             client_id: "abc".to_string(),
         };
@@ -144,7 +465,7 @@ mod tests {
 
     #[tokio::test]
     async fn assert_owner_fails_when_game_does_not_exist() {
-        let store = SessionStore::new();
+        let store = MemorySessionStore::new();
         let principal = Principal::Guest {
             client_id: "abc".to_string(),
         };
@@ -154,7 +475,7 @@ mod tests {
 
     #[tokio::test]
     async fn assert_owner_fails_for_different_owner() {
-        let store = SessionStore::new();
+        let store = MemorySessionStore::new();
         let owner = Principal::Guest {
             client_id: "owner".to_string(),
         };
@@ -171,7 +492,7 @@ mod tests {
 
     #[tokio::test]
     async fn update_existing_session_succeeds() {
-        let store = SessionStore::new();
+        let store = MemorySessionStore::new();
         let mut session = sample_session("guest:abc".to_string());
 
         store.insert("game-5".to_string(), session.clone()).await;
@@ -186,10 +507,54 @@ mod tests {
 
     #[tokio::test]
     async fn update_missing_session_fails() {
-        let store = SessionStore::new();
+        let store = MemorySessionStore::new();
         let session = sample_session("guest:abc".to_string());
 
         let res = store.update("missing", session).await;
         assert!(res.is_err());
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn roles_in_online_games() {
+        let store = MemorySessionStore::new();
+        let owner = guest("owner");
+        let rival = guest("rival");
+        let viewer = guest("viewer");
+        store.insert("game-6".to_string(), online_session(&owner)).await;
+
+        let (game_id, session, seat) = store.join("ABC123", &rival.key()).await.unwrap();
+        assert_eq!((game_id.as_str(), seat), ("game-6", 1));
+        assert_eq!(session.role_of(&owner), Some(SessionRole::Owner));
+        assert_eq!(session.role_of(&rival), Some(SessionRole::Player));
+        assert_eq!(session.role_of(&viewer), Some(SessionRole::Spectator));
+        assert_eq!(session.seat_of(&owner.key()), Some(0));
+
+        assert!(store.assert_role(&viewer, "game-6", SessionRole::Spectator).await.is_ok());
+        assert!(store.assert_role(&viewer, "game-6", SessionRole::Player).await.is_err());
+        assert!(store.assert_role(&rival, "game-6", SessionRole::Player).await.is_ok());
+        assert!(store.assert_owner(&rival, "game-6").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn join_fills_one_free_seat() {
+        let store = MemorySessionStore::new();
+        let owner = guest("owner");
+        store.insert("game-7".to_string(), online_session(&owner)).await;
+
+        assert_eq!(store.join("NOPE", "guest:rival").await.err(), Some(JoinError::UnknownCode));
+        assert_eq!(store.join("ABC123", "guest:rival").await.unwrap().2, 1);
+        assert_eq!(store.join("ABC123", "guest:rival").await.unwrap().2, 1);
+        assert_eq!(store.join("ABC123", &owner.key()).await.unwrap().2, 0);
+        assert_eq!(store.join("ABC123", "guest:third").await.err(), Some(JoinError::Full));
+        assert!(store.join_code_in_use("ABC123").await);
+    }
+
+    #[tokio::test]
+    async fn local_games_have_no_spectators() {
+        let store = MemorySessionStore::new();
+        store.insert("game-8".to_string(), sample_session("guest:owner".to_string())).await;
+
+        assert!(store.assert_role(&guest("viewer"), "game-8", SessionRole::Spectator).await.is_err());
+        assert!(store.assert_role(&guest("owner"), "game-8", SessionRole::Spectator).await.is_ok());
+    }
+}
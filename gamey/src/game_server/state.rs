@@ -1,21 +1,37 @@
 //! state.rs
 //!
 //! Estado global del game_server (Axum state):
-//! - registro de bots
+//! - registro de bots (declarado en `config/bots.json` o en `--bots`, recargable)
 //! - store de config (in-memory hoy)
-//! - store de sesiones (in-memory)
-
+//! - store de sesiones (en memoria o en fichero con `--sessions`)
+//! - sesiones con estado de los bots (árbol MCTS reutilizado entre jugadas)
+//! - búsquedas en segundo plano durante el turno del humano (ponder)
+//! - hilos de búsqueda de los bots, con cola y plazos (workers)
+//! - jugadas del bot en segundo plano consultables por `job_id` (jobs)
+//! - eventos en directo de cada partida, con su reloj (events)
+//! - caducidad de partidas y límites por cliente, con sus contadores (sweeper)
+//! - libro de aperturas opcional para todos los bots
+
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
-use crate::{MctsBot, MctsCompletoBot, RandomBot, YBotRegistry};
+use crate::{BotRegistryConfig, GameYError, OpeningBook, SharedRegistry, YBotRegistry};
 
 use self::config_store::ConfigStore;
 
-use super::sessions::SessionStore;
+use super::bot_sessions::BotSessionStore;
+use super::events::GameEventHub;
+use super::jobs::BotJobStore;
+use super::ponder::PonderManager;
+use super::sessions::{MemorySessionStore, SharedSessionStore};
+use super::sweeper::{SessionMetrics, SessionPolicy};
+use super::workers::BotWorkerPool;
 
 pub mod config_store {
     use std::collections::HashMap;
     use std::sync::Arc;
+    use std::time::{Duration, SystemTime};
     use tokio::sync::RwLock;
 
     use crate::game_server::dto::{GameConfig, HvBStarter, HvHStarter};
@@ -25,11 +41,16 @@ pub mod config_store {
     #[derive(Debug, Clone)]
     pub struct ConfigStore {
         inner: Arc<RwLock<HashMap<String, GameConfig>>>,
+        /// Último uso de cada config guardada (para olvidar las de clientes que no vuelven).
+        last_used: Arc<RwLock<HashMap<String, SystemTime>>>,
     }
 
     impl ConfigStore {
         pub fn new() -> Self {
-            Self { inner: Arc::new(RwLock::new(HashMap::new())) }
+            Self {
+                inner: Arc::new(RwLock::new(HashMap::new())),
+                last_used: Arc::new(RwLock::new(HashMap::new())),
+            }
         }
 
         pub fn default_config() -> GameConfig {
@@ -57,44 +78,173 @@ pub mod config_store {
         }
 
         pub async fn get_or_default(&self, principal: &Principal) -> GameConfig {
+            let key = Self::key(principal);
             let r = self.inner.read().await;
-            Self::get_or_default_blocking(&r, &Self::key(principal))
+            if r.contains_key(&key) {
+                self.last_used.write().await.insert(key.clone(), SystemTime::now());
+            }
+            Self::get_or_default_blocking(&r, &key)
         }
 
         pub fn set(&self, principal: &Principal, cfg: GameConfig) {
             let key = Self::key(principal);
             let cfg = Self::normalize(cfg);
             let inner = self.inner.clone();
+            let last_used = self.last_used.clone();
             tokio::spawn(async move {
-                inner.write().await.insert(key, cfg);
+                let mut w = inner.write().await;
+                last_used.write().await.insert(key.clone(), SystemTime::now());
+                w.insert(key, cfg);
             });
         }
+
+        /// Olvida las configs sin usar desde hace más de `ttl`. Devuelve cuántas.
+        pub async fn evict_idle(&self, now: SystemTime, ttl: Duration) -> usize {
+            let mut w = self.inner.write().await;
+            let mut last_used = self.last_used.write().await;
+            let before = w.len();
+            w.retain(|key, _| {
+                last_used
+                    .get(key)
+                    .is_some_and(|used| now.duration_since(*used).unwrap_or_default() <= ttl)
+            });
+            last_used.retain(|key, _| w.contains_key(key));
+            before - w.len()
+        }
+
+        pub async fn count(&self) -> usize {
+            self.inner.read().await.len()
+        }
+    }
+}
+
+/// De dónde salen los bots: el fichero de configuración (o la configuración
+/// por defecto) y el libro de aperturas global. Se vuelve a leer al recargar.
+#[derive(Clone, Default)]
+pub struct BotSource {
+    pub config: Option<PathBuf>,
+    pub book: Option<Arc<OpeningBook>>,
+}
+
+impl BotSource {
+    pub fn load(&self) -> Result<YBotRegistry, GameYError> {
+        let config = match &self.config {
+            Some(path) => BotRegistryConfig::load(path)?,
+            None => BotRegistryConfig::default_config(),
+        };
+        let bots = config.build()?;
+        Ok(match &self.book {
+            Some(book) => bots.with_opening_book(book.clone()),
+            None => bots,
+        })
     }
 }
 
 #[derive(Clone)]
 pub struct GameServerState {
-    pub bots: Arc<YBotRegistry>,
-    pub sessions: SessionStore,
+    pub bots: SharedRegistry,
+    pub bot_source: BotSource,
+    /// Token para los endpoints de administración (`None` = desactivados).
+    pub admin_token: Option<String>,
+    pub sessions: SharedSessionStore,
     pub config_store: ConfigStore,
+    pub bot_sessions: BotSessionStore,
+    pub ponder: PonderManager,
+    pub workers: BotWorkerPool,
+    pub bot_jobs: BotJobStore,
+    pub events: GameEventHub,
+    pub session_policy: SessionPolicy,
+    pub session_metrics: Arc<SessionMetrics>,
 }
 
 impl GameServerState {
+    /// Estado con los bots de `config/bots.json`.
     pub fn new_default() -> Self {
-        let bots = YBotRegistry::new()
-            .with_bot(Arc::new(RandomBot))
-            .with_bot(Arc::new(MctsBot::new("mcts_medio",     20000)))
-            .with_bot(Arc::new(MctsBot::new("mcts_dificil",   30000)))
-            .with_bot(Arc::new(MctsCompletoBot::new("mcts_demencial", 30000)))
-            .with_bot(Arc::new(MctsCompletoBot::new("mcts_completo_medio", 15000)))
-            .with_bot(Arc::new(MctsCompletoBot::new("mcts_completo_dificil", 30000)));
+        Self::with_bots(BotSource::default().load().expect("config/bots.json is valid"))
+    }
 
+    /// Estado con los bots declarados en el fichero `path`.
+    pub fn from_bot_config(path: impl Into<PathBuf>) -> Result<Self, GameYError> {
+        let source = BotSource { config: Some(path.into()), book: None };
+        let mut state = Self::with_bots(source.load()?);
+        state.bot_source = source;
+        Ok(state)
+    }
+
+    /// Estado con un registro de bots ya construido.
+    pub fn with_bots(bots: YBotRegistry) -> Self {
         Self {
-            bots: Arc::new(bots),
-            sessions: SessionStore::new(),
+            bots: SharedRegistry::new(bots),
+            bot_source: BotSource::default(),
+            admin_token: None,
+            sessions: Arc::new(MemorySessionStore::new()),
             config_store: ConfigStore::new(),
+            bot_sessions: BotSessionStore::new(),
+            ponder: PonderManager::new_default(),
+            workers: BotWorkerPool::new_default(),
+            bot_jobs: BotJobStore::new(),
+            events: GameEventHub::new(),
+            session_policy: SessionPolicy::default(),
+            session_metrics: Arc::new(SessionMetrics::default()),
         }
     }
+
+    /// Hace que todos los bots registrados consulten el libro de aperturas
+    /// antes de buscar (también después de recargar).
+    pub fn with_opening_book(mut self, book: Arc<OpeningBook>) -> Self {
+        self.bots.replace(self.bots.snapshot().as_ref().clone().with_opening_book(book.clone()));
+        self.bot_source.book = Some(book);
+        self
+    }
+
+    /// Usa otro store de partidas (p. ej. `FileSessionStore`).
+    pub fn with_sessions(mut self, sessions: SharedSessionStore) -> Self {
+        self.sessions = sessions;
+        self
+    }
+
+    /// Cambia la caducidad de las partidas y el límite por cliente.
+    pub fn with_session_policy(mut self, policy: SessionPolicy) -> Self {
+        self.session_policy = policy;
+        self
+    }
+
+    /// Cambia el plazo de las jugadas del bot pedidas con `Prefer: respond-async`.
+    pub fn with_async_bot_deadline(mut self, deadline: Duration) -> Self {
+        self.bot_jobs = BotJobStore::with_async_deadline(deadline);
+        self
+    }
+
+    /// Cambia cuántas sesiones de bot se guardan a la vez.
+    pub fn with_max_bot_sessions(mut self, max_sessions: usize) -> Self {
+        self.bot_sessions = BotSessionStore::with_max_sessions(max_sessions);
+        self
+    }
+
+    /// Activa los endpoints de administración con este token.
+    pub fn with_admin_token(mut self, token: impl Into<String>) -> Self {
+        self.admin_token = Some(token.into());
+        self
+    }
+
+    /// Borra la partida y todo lo asociado: sesión del bot, ponder, trabajo
+    /// del bot en curso y canal de eventos (avisando a los suscriptores).
+    pub async fn discard_game(&self, game_id: &str) {
+        self.sessions.remove(game_id).await;
+        self.ponder.stop(game_id);
+        self.bot_sessions.remove(game_id).await;
+        self.bot_jobs.remove(game_id).await;
+        self.events.close(game_id);
+    }
+
+    /// Vuelve a leer la configuración de bots y sustituye el registro. Si la
+    /// configuración no es válida se conserva el registro actual.
+    pub fn reload_bots(&self) -> Result<Vec<String>, GameYError> {
+        let bots = self.bot_source.load()?;
+        let names = bots.names();
+        self.bots.replace(bots);
+        Ok(names)
+    }
 }
 
 #[cfg(test)]
@@ -193,6 +343,23 @@ mod tests {
         assert!(matches!(cfg.hvh_starter, Some(HvHStarter::Player1)));
     }
 
+    #[test]
+    fn with_opening_book_keeps_bot_names() {
+        let mut book = OpeningBook::new();
+        book.insert(&crate::GameY::new(5), crate::Coordinates::new(2, 1, 1), 1);
+
+        let state = GameServerState::new_default();
+        let mut before = state.bots.names();
+        let state = state.with_opening_book(Arc::new(book));
+        let mut after = state.bots.names();
+        before.sort();
+        after.sort();
+        assert_eq!(before, after);
+
+        let bot = state.bots.find("random_bot").unwrap();
+        assert_eq!(bot.choose_move(&crate::GameY::new(5)), Some(crate::Coordinates::new(2, 1, 1)));
+    }
+
     #[test]
     fn new_default_creates_state_with_expected_bots() {
         let state = GameServerState::new_default();
@@ -205,4 +372,24 @@ mod tests {
         assert!(names.iter().any(|b| b == "mcts_demencial"));
         assert!(names.iter().any(|b| b == "mcts_completo_medio"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn reload_bots_rereads_the_config_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bots.json");
+        std::fs::write(&path, r#"{ "bots": [ { "type": "random", "name": "random_bot" } ] }"#).unwrap();
+
+        let state = GameServerState::from_bot_config(&path).unwrap();
+        assert_eq!(state.bots.names(), vec!["random_bot".to_string()]);
+
+        std::fs::write(&path, r#"{ "bots": [ { "type": "greedy", "name": "greedy_bot" } ] }"#).unwrap();
+        let clone = state.clone();
+        assert_eq!(clone.reload_bots().unwrap(), vec!["greedy_bot".to_string()]);
+        assert!(state.bots.find("greedy_bot").is_some());
+        assert!(state.bots.find("random_bot").is_none());
+
+        std::fs::write(&path, "{ not json").unwrap();
+        assert!(state.reload_bots().is_err());
+        assert!(state.bots.find("greedy_bot").is_some());
+    }
+}
@@ -0,0 +1,325 @@
+//! sweeper.rs
+//!
+//! Caducidad de partidas y límites por cliente.
+//!
+//! Las partidas solo se borraban con `DELETE`, así que las abandonadas se
+//! acumulaban para siempre (igual que la config recordada de cada
+//! `X-Client-Id`). Un barrido en segundo plano borra cada
+//! `SessionPolicy::sweep_interval`:
+//! - las partidas terminadas sin cambios desde hace `finished_ttl`
+//! - las partidas sin terminar sin cambios desde hace `idle_ttl`
+//! - la config recordada sin usar desde hace `config_ttl`
+//!
+//! Además cada cliente puede tener como mucho `max_games_per_principal`
+//! partidas sin terminar (`409` `too_many_games` al crear otra).
+//!
+//! `GET /metrics` expone los contadores en formato Prometheus.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+use axum::{extract::State, http::header, response::IntoResponse};
+
+use super::error::ApiErrorResponse;
+use super::sessions::GameSession;
+use super::state::GameServerState;
+
+pub const DEFAULT_FINISHED_TTL: Duration = Duration::from_secs(60 * 60);
+pub const DEFAULT_IDLE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+pub const DEFAULT_CONFIG_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+pub const DEFAULT_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+pub const DEFAULT_MAX_GAMES_PER_PRINCIPAL: usize = 20;
+
+/// Cuánto duran las partidas y cuántas puede tener cada cliente.
+#[derive(Debug, Clone)]
+pub struct SessionPolicy {
+    pub finished_ttl: Duration,
+    pub idle_ttl: Duration,
+    pub config_ttl: Duration,
+    pub sweep_interval: Duration,
+    /// Partidas sin terminar por cliente (0 = sin límite).
+    pub max_games_per_principal: usize,
+}
+
+impl Default for SessionPolicy {
+    fn default() -> Self {
+        Self {
+            finished_ttl: DEFAULT_FINISHED_TTL,
+            idle_ttl: DEFAULT_IDLE_TTL,
+            config_ttl: DEFAULT_CONFIG_TTL,
+            sweep_interval: DEFAULT_SWEEP_INTERVAL,
+            max_games_per_principal: DEFAULT_MAX_GAMES_PER_PRINCIPAL,
+        }
+    }
+}
+
+/// Contadores desde que arrancó el servidor.
+#[derive(Debug, Default)]
+pub struct SessionMetrics {
+    pub evicted_finished: AtomicU64,
+    pub evicted_idle: AtomicU64,
+    pub evicted_configs: AtomicU64,
+    pub rejected_games: AtomicU64,
+    pub sweeps: AtomicU64,
+}
+
+/// Lo que ha borrado un barrido.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SweepReport {
+    pub finished: usize,
+    pub idle: usize,
+    pub configs: usize,
+}
+
+fn older_than(now: SystemTime, then: SystemTime, ttl: Duration) -> bool {
+    now.duration_since(then).is_ok_and(|age| age > ttl)
+}
+
+/// Borra lo que haya caducado en `now`.
+pub async fn sweep_once(state: &GameServerState, now: SystemTime) -> SweepReport {
+    let policy = &state.session_policy;
+    let mut report = SweepReport::default();
+
+    for summary in state.sessions.summaries().await {
+        let ttl = if summary.finished { policy.finished_ttl } else { policy.idle_ttl };
+        if !older_than(now, summary.last_activity, ttl) {
+            continue;
+        }
+        // Puede haber cambiado desde el resumen: se vuelve a mirar antes de borrar.
+        let still_expired = state
+            .sessions
+            .get(&summary.game_id)
+            .await
+            .is_some_and(|session| older_than(now, session.last_activity, ttl));
+        if !still_expired {
+            continue;
+        }
+
+        state.discard_game(&summary.game_id).await;
+        if summary.finished {
+            report.finished += 1;
+        } else {
+            report.idle += 1;
+        }
+    }
+    report.configs = state.config_store.evict_idle(now, policy.config_ttl).await;
+
+    let metrics = &state.session_metrics;
+    metrics.evicted_finished.fetch_add(report.finished as u64, Ordering::Relaxed);
+    metrics.evicted_idle.fetch_add(report.idle as u64, Ordering::Relaxed);
+    metrics.evicted_configs.fetch_add(report.configs as u64, Ordering::Relaxed);
+    metrics.sweeps.fetch_add(1, Ordering::Relaxed);
+    report
+}
+
+/// Lanza el barrido periódico en segundo plano.
+pub fn spawn_sweeper(state: GameServerState) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(state.session_policy.sweep_interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            let report = sweep_once(&state, SystemTime::now()).await;
+            if report != SweepReport::default() {
+                tracing::info!(
+                    finished = report.finished,
+                    idle = report.idle,
+                    configs = report.configs,
+                    "expired sessions swept"
+                );
+            }
+        }
+    })
+}
+
+/// Guarda una partida nueva. Falla con 409 si su dueño ya tiene el máximo de
+/// partidas sin terminar (el store cuenta e inserta a la vez).
+pub async fn insert_new_game(
+    state: &GameServerState,
+    game_id: String,
+    session: GameSession,
+) -> Result<(), ApiErrorResponse> {
+    let max = state.session_policy.max_games_per_principal;
+    state.sessions.insert_limited(game_id, session, max).await.map_err(|_| {
+        state.session_metrics.rejected_games.fetch_add(1, Ordering::Relaxed);
+        ApiErrorResponse::conflict(
+            format!("Too many ongoing games (max {max}); finish or delete one first"),
+            "too_many_games",
+        )
+    })
+}
+
+/// GET /metrics
+///
+/// Contadores de partidas en formato de texto de Prometheus.
+pub async fn metrics(State(state): State<GameServerState>) -> impl IntoResponse {
+    let metrics = &state.session_metrics;
+    let sessions = state.sessions.count().await;
+    let configs = state.config_store.count().await;
+    let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+
+    let body = format!(
+        "# HELP gamey_sessions Games currently stored.\n\
+         # TYPE gamey_sessions gauge\n\
+         gamey_sessions {sessions}\n\
+         # HELP gamey_configs Remembered client configurations currently stored.\n\
+         # TYPE gamey_configs gauge\n\
+         gamey_configs {configs}\n\
+         # HELP gamey_sessions_evicted_total Games removed by the expiry sweeper.\n\
+         # TYPE gamey_sessions_evicted_total counter\n\
+         gamey_sessions_evicted_total{{reason=\"finished\"}} {}\n\
+         gamey_sessions_evicted_total{{reason=\"idle\"}} {}\n\
+         # HELP gamey_configs_evicted_total Remembered configurations removed by the expiry sweeper.\n\
+         # TYPE gamey_configs_evicted_total counter\n\
+         gamey_configs_evicted_total {}\n\
+         # HELP gamey_games_rejected_total Games not created because the client reached its limit.\n\
+         # TYPE gamey_games_rejected_total counter\n\
+         gamey_games_rejected_total {}\n\
+         # HELP gamey_sweeps_total Expiry sweeps run.\n\
+         # TYPE gamey_sweeps_total counter\n\
+         gamey_sweeps_total {}\n",
+        load(&metrics.evicted_finished),
+        load(&metrics.evicted_idle),
+        load(&metrics.evicted_configs),
+        load(&metrics.rejected_games),
+        load(&metrics.sweeps),
+    );
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::body::Body;
+    use axum::http::{HeaderMap, HeaderValue, Request, StatusCode};
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    use crate::game_server::auth::Principal;
+    use crate::game_server::create_router;
+    use crate::game_server::dto::{GameConfig, HvBStarter, HvHStarter};
+
+    fn headers_with_client(client_id: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-client-id", HeaderValue::from_str(client_id).unwrap());
+        headers
+    }
+
+    fn guest(client_id: &str) -> Principal {
+        Principal::Guest { client_id: client_id.to_string() }
+    }
+
+    async fn create_hvh(state: &GameServerState, client_id: &str) -> Result<String, ApiErrorResponse> {
+        use axum::extract::Query;
+        super::super::hvh::create_game(
+            State(state.clone()),
+            headers_with_client(client_id),
+            Query(Default::default()),
+        )
+        .await
+        .map(|res| res.0.game_id)
+    }
+
+    /// Termina la partida en un tablero de 1 casilla.
+    async fn finish(state: &GameServerState, game_id: &str) {
+        let mut session = state.sessions.get(game_id).await.unwrap();
+        session.game = crate::GameY::new(1);
+        session
+            .game
+            .add_move(crate::Movement::Placement {
+                player: crate::PlayerId::new(0),
+                coords: crate::Coordinates::new(0, 0, 0),
+            })
+            .unwrap();
+        state.sessions.update(game_id, session).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn sweep_removes_finished_and_idle_games_after_their_ttl() {
+        let state = GameServerState::new_default();
+        let finished = create_hvh(&state, "sweep").await.unwrap();
+        let idle = create_hvh(&state, "sweep").await.unwrap();
+        finish(&state, &finished).await;
+
+        let now = SystemTime::now();
+        assert_eq!(sweep_once(&state, now).await, SweepReport::default());
+
+        let report = sweep_once(&state, now + DEFAULT_FINISHED_TTL + Duration::from_secs(1)).await;
+        assert_eq!(report, SweepReport { finished: 1, idle: 0, configs: 0 });
+        assert!(state.sessions.get(&finished).await.is_none());
+        assert!(state.sessions.get(&idle).await.is_some());
+
+        let report = sweep_once(&state, now + DEFAULT_IDLE_TTL + Duration::from_secs(1)).await;
+        assert_eq!(report.idle, 1);
+        assert_eq!(state.sessions.count().await, 0);
+        assert_eq!(state.session_metrics.evicted_finished.load(Ordering::Relaxed), 1);
+        assert_eq!(state.session_metrics.evicted_idle.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn sweep_forgets_unused_configs() {
+        let state = GameServerState::new_default();
+        let cfg = GameConfig {
+            size: 5,
+            hvb_starter: HvBStarter::Human,
+            hvh_starter: Some(HvHStarter::Player0),
+            bot_id: Some("random_bot".to_string()),
+        };
+        state.config_store.set(&guest("cfg"), cfg);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(state.config_store.count().await, 1);
+
+        let now = SystemTime::now();
+        assert_eq!(sweep_once(&state, now).await.configs, 0);
+        assert_eq!(sweep_once(&state, now + DEFAULT_CONFIG_TTL + Duration::from_secs(1)).await.configs, 1);
+        assert_eq!(state.config_store.get_or_default(&guest("cfg")).await.size, 7);
+    }
+
+    #[tokio::test]
+    async fn each_client_has_a_limit_of_ongoing_games() {
+        let mut state = GameServerState::new_default();
+        state.session_policy.max_games_per_principal = 2;
+
+        let first = create_hvh(&state, "limited").await.unwrap();
+        create_hvh(&state, "limited").await.unwrap();
+        let err = create_hvh(&state, "limited").await.unwrap_err();
+        assert_eq!(err.0, StatusCode::CONFLICT);
+        assert_eq!(err.1.code, "too_many_games");
+        assert!(create_hvh(&state, "someone-else").await.is_ok());
+
+        // Las terminadas no cuentan.
+        finish(&state, &first).await;
+        assert!(create_hvh(&state, "limited").await.is_ok());
+        assert_eq!(state.session_metrics.rejected_games.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn concurrent_creates_do_not_exceed_the_limit() {
+        let mut state = GameServerState::new_default();
+        state.session_policy.max_games_per_principal = 2;
+
+        let creates = (0..8).map(|_| create_hvh(&state, "eager"));
+        let created = futures_util::future::join_all(creates).await;
+        assert_eq!(created.iter().filter(|created| created.is_ok()).count(), 2);
+        assert_eq!(state.sessions.count().await, 2);
+        assert_eq!(state.session_metrics.rejected_games.load(Ordering::Relaxed), 6);
+    }
+
+    #[tokio::test]
+    async fn metrics_endpoint_reports_the_counters() {
+        let state = GameServerState::new_default();
+        create_hvh(&state, "metrics").await.unwrap();
+        state.session_metrics.evicted_idle.fetch_add(3, Ordering::Relaxed);
+
+        let res = create_router(state)
+            .oneshot(Request::builder().uri("/metrics").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = res.into_body().collect().await.unwrap().to_bytes();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.contains("gamey_sessions 1\n"));
+        assert!(text.contains("gamey_sessions_evicted_total{reason=\"idle\"} 3\n"));
+    }
+}
@@ -0,0 +1,220 @@
+//! workers.rs
+//!
+//! Ejecución de los bots fuera de los hilos de Tokio.
+//!
+//! Una búsqueda de un MCTS con 30000 iteraciones tarda segundos de CPU; si se
+//! hace dentro del handler bloquea un hilo del runtime y con él todas las
+//! peticiones que le tocan. `BotWorkerPool` lleva cada búsqueda a
+//! `spawn_blocking` con estos límites:
+//! - como mucho `workers` búsquedas a la vez (una por núcleo por defecto)
+//! - como mucho `max_queued` peticiones esperando turno; si la cola está llena
+//!   se responde 503 `bots_busy` con `Retry-After`
+//! - cada petición tiene un plazo: cuenta desde que llega, así que el tiempo
+//!   en cola se descuenta del de búsqueda. Los bots interrumpibles (MCTS)
+//!   devuelven la mejor jugada encontrada al llegar el plazo
+//! - si el cliente se desconecta, axum descarta el futuro del handler y la
+//!   búsqueda se cancela (`SearchControl::cancel`)
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::Semaphore;
+
+use crate::SearchControl;
+
+use super::error::ApiErrorResponse;
+
+/// Peticiones que pueden esperar turno además de las que se están ejecutando.
+pub const DEFAULT_MAX_QUEUED: usize = 32;
+
+/// Plazo por defecto (y máximo) de cada petición.
+pub const DEFAULT_BOT_DEADLINE: Duration = Duration::from_secs(30);
+
+/// Segundos que se sugiere esperar (`Retry-After`) cuando no hay hueco.
+pub const DEFAULT_RETRY_AFTER_SECS: u64 = 2;
+
+/// Margen tras el plazo para que un bot interrumpible devuelva su jugada.
+/// Pasado este margen se responde 504 aunque el bot siga calculando.
+const DEADLINE_GRACE: Duration = Duration::from_secs(2);
+
+/// Cancela la búsqueda si el handler se descarta antes de que termine.
+struct CancelOnDrop(Arc<SearchControl>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+#[derive(Clone)]
+pub struct BotWorkerPool {
+    workers: Arc<Semaphore>,
+    /// Plazas totales: las de ejecución más las de la cola.
+    admission: Arc<Semaphore>,
+    deadline: Duration,
+    retry_after_secs: u64,
+}
+
+impl BotWorkerPool {
+    pub fn new(workers: usize, max_queued: usize, deadline: Duration) -> Self {
+        let workers = workers.max(1);
+        Self {
+            workers: Arc::new(Semaphore::new(workers)),
+            admission: Arc::new(Semaphore::new(workers + max_queued)),
+            deadline,
+            retry_after_secs: DEFAULT_RETRY_AFTER_SECS,
+        }
+    }
+
+    /// Un hilo de búsqueda por núcleo disponible.
+    pub fn new_default() -> Self {
+        let cores = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        Self::new(cores, DEFAULT_MAX_QUEUED, DEFAULT_BOT_DEADLINE)
+    }
+
+    pub fn deadline(&self) -> Duration {
+        self.deadline
+    }
+
+    fn busy(&self, message: &str) -> ApiErrorResponse {
+        ApiErrorResponse::service_unavailable(message, "bots_busy", self.retry_after_secs)
+    }
+
+    /// Control de una petición nueva: su plazo empieza a contar ya. `limit`
+    /// lo acorta (nunca lo alarga más allá del del pool).
+    pub fn control(&self, limit: Option<Duration>) -> Arc<SearchControl> {
+        let budget = limit.map_or(self.deadline, |limit| limit.min(self.deadline));
+        Arc::new(SearchControl::with_deadline(Instant::now() + budget))
+    }
+
+    /// Ejecuta `job` en un hilo de búsqueda y espera su resultado.
+    ///
+    /// `job` recibe el `SearchControl` con el plazo y la señal de cancelación,
+    /// que debe pasar al bot (`YBot::choose_action_controlled`).
+    pub async fn run<T, F>(&self, limit: Option<Duration>, job: F) -> Result<T, ApiErrorResponse>
+    where
+        T: Send + 'static,
+        F: FnOnce(&SearchControl) -> T + Send + 'static,
+    {
+        self.run_with(self.control(limit), job).await
+    }
+
+    /// Como [`BotWorkerPool::run`], pero con un control creado por quien
+    /// llama (con [`BotWorkerPool::control`]), que puede consultar el
+    /// progreso de la búsqueda mientras espera.
+    pub async fn run_with<T, F>(&self, control: Arc<SearchControl>, job: F) -> Result<T, ApiErrorResponse>
+    where
+        T: Send + 'static,
+        F: FnOnce(&SearchControl) -> T + Send + 'static,
+    {
+        let deadline = control.deadline().unwrap_or_else(|| Instant::now() + self.deadline);
+
+        let admission = self
+            .admission
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| self.busy("All bot workers are busy, try again later"))?;
+
+        let worker = tokio::time::timeout_at(deadline.into(), self.workers.clone().acquire_owned())
+            .await
+            .map_err(|_| self.busy("Timed out waiting for a free bot worker"))?
+            .map_err(|_| ApiErrorResponse::internal("Bot worker pool closed", "bot_pool_closed"))?;
+
+        let _cancel = CancelOnDrop(control.clone());
+
+        // Los permisos viajan con la tarea: si el cliente se va, siguen
+        // ocupados hasta que el bot termine de verdad.
+        let task = tokio::task::spawn_blocking(move || {
+            let _permits = (admission, worker);
+            job(&control)
+        });
+
+        match tokio::time::timeout_at((deadline + DEADLINE_GRACE).into(), task).await {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(e)) => Err(ApiErrorResponse::internal(format!("Bot search failed: {e}"), "bot_failed")),
+            Err(_) => Err(ApiErrorResponse::gateway_timeout(
+                "Bot did not answer within the deadline",
+                "bot_timeout",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use axum::http::StatusCode;
+
+    /// Trabajo que espera hasta que se le cancela o se agota su plazo.
+    fn wait_for_stop(control: &SearchControl) -> bool {
+        while !control.should_stop() {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        control.is_cancelled()
+    }
+
+    #[tokio::test]
+    async fn run_returns_job_result() {
+        let pool = BotWorkerPool::new(1, 0, Duration::from_secs(5));
+        assert_eq!(pool.run(None, |_| 42).await.unwrap(), 42);
+    }
+
+    #[tokio::test]
+    async fn job_sees_the_request_deadline() {
+        let pool = BotWorkerPool::new(1, 0, Duration::from_secs(5));
+        let start = Instant::now();
+        let cancelled = pool.run(Some(Duration::from_millis(50)), wait_for_stop).await.unwrap();
+
+        assert!(!cancelled);
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn saturated_pool_answers_503_with_retry_hint() {
+        let pool = BotWorkerPool::new(1, 1, Duration::from_secs(5));
+        let running = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.run(Some(Duration::from_millis(300)), wait_for_stop).await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let queued = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.run(Some(Duration::from_millis(100)), |_| ()).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let err = pool.run(None, |_| ()).await.unwrap_err();
+        assert_eq!(err.0, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(err.1.code, "bots_busy");
+        assert_eq!(err.1.retry_after_secs, Some(DEFAULT_RETRY_AFTER_SECS));
+
+        assert!(running.await.unwrap().is_ok());
+        // El plazo de la petición en cola se agotó mientras esperaba.
+        assert_eq!(queued.await.unwrap().unwrap_err().0, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(pool.run(None, |_| 1).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn dropping_the_request_cancels_the_search() {
+        let pool = BotWorkerPool::new(1, 0, Duration::from_secs(5));
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let request = tokio::spawn({
+            let pool = pool.clone();
+            let cancelled = cancelled.clone();
+            async move {
+                pool.run(None, move |control| cancelled.store(wait_for_stop(control), Ordering::SeqCst))
+                    .await
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        request.abort();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // El trabajo termina, devuelve su permiso y el pool vuelve a aceptar peticiones.
+        assert_eq!(pool.run(Some(Duration::from_secs(1)), |_| 7).await.unwrap(), 7);
+        assert!(cancelled.load(Ordering::SeqCst));
+    }
+}
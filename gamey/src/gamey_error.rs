@@ -116,6 +116,20 @@ pub enum GameYError {
         line: u32,
     },
 
+    /// A compact (binary or base64) position could not be decoded.
+    #[error("Invalid compact position: {message}")]
+    InvalidCompactPosition {
+        /// Description of what is wrong with the encoded data.
+        message: String,
+    },
+
+    /// A bot registry configuration is malformed or declares an invalid bot.
+    #[error("Invalid bot configuration: {message}")]
+    InvalidBotConfig {
+        /// Description of what is wrong with the configuration.
+        message: String,
+    },
+
     /// Server operation failed.
     #[error("Server error: {message}")]
     ServerError {
@@ -233,6 +247,26 @@ mod tests {
         assert!(msg.contains("line 3"));
     }
 
+    #[test]
+    fn test_invalid_compact_position_display() {
+        let err = GameYError::InvalidCompactPosition {
+            message: "truncated data".to_string(),
+        };
+        let msg = format!("{}", err);
+        assert!(msg.contains("Invalid compact position"));
+        assert!(msg.contains("truncated data"));
+    }
+
+    #[test]
+    fn test_invalid_bot_config_display() {
+        let err = GameYError::InvalidBotConfig {
+            message: "duplicate bot name: solver".to_string(),
+        };
+        let msg = format!("{}", err);
+        assert!(msg.contains("Invalid bot configuration"));
+        assert!(msg.contains("duplicate bot name: solver"));
+    }
+
     #[test]
     fn test_server_error_display() {
         let err = GameYError::ServerError {
@@ -9,6 +9,7 @@
 //! - [`bot`]: Bot implementations for computer opponents
 //! - [`bot_server`]: HTTP server for bot API
 //! - [`cli`]: Command-line interface for interactive play
+//! - [`engine`]: Text engine protocol (GTP-style) over stdin/stdout
 //! - [`notation`]: Game notation formats (YEN)
 //! - [`gamey_error`]: Error types for the library
 //!
@@ -31,6 +32,7 @@
 pub mod bot;
 pub mod cli;
 pub mod core;
+pub mod engine;
 pub mod gamey_error;
 pub mod notation;
 pub mod bot_server;
@@ -38,6 +40,7 @@ pub mod game_server;
 pub use bot::*;
 pub use cli::*;
 pub use core::*;
+pub use engine::*;
 pub use gamey_error::*;
 pub use notation::*;
 pub use game_server::*;
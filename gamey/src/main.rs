@@ -17,11 +17,31 @@
 //!
 //! # Start the bot server on port 3000
 //! gamey --mode server --port 3000
+//!
+//! # Let every bot play from an opening book
+//! gamey --mode server --book book.json
+//!
+//! # Declare the bots in a file and allow reloading it at runtime
+//! gamey --mode server --bots bots.json --admin-token secret
+//!
+//! # Keep the games in a file so they survive restarts
+//! gamey --mode server --sessions data/sessions.jsonl
+//!
+//! # Drop finished games after 10 minutes and allow 5 ongoing games per client
+//! gamey --mode server --finished-ttl 600 --max-games 5
+//!
+//! # Serve mcts_completo_medio to a GUI or tournament runner
+//! gamey --mode engine --bot mcts_completo_medio --size 9
 //! ```
 
 use clap::Parser;
-use gamey::{self, CliArgs, Mode, run_cli_game};
-use gamey::game_server::{create_router, state::GameServerState};
+use std::sync::Arc;
+use std::time::Duration;
+
+use gamey::{self, CliArgs, Mode, OpeningBook, run_cli_game, run_engine_stdio};
+use gamey::game_server::{create_router, file_sessions::FileSessionStore, state::GameServerState};
+use gamey::game_server::sessions::SessionStore;
+use gamey::game_server::sweeper::{SessionPolicy, spawn_sweeper};
 use tracing_subscriber::prelude::*;
 
 /// Main entry point for the GameY application.
@@ -33,8 +53,65 @@ async fn main() {
     tracing_subscriber::registry().init();
     let args = CliArgs::parse();
 
-    if args.mode == Mode::Server {
-    let state = GameServerState::new_default();
+    if args.mode == Mode::Server || args.mode == Mode::Engine {
+        let mut state = match &args.bots {
+            Some(path) => GameServerState::from_bot_config(path).unwrap_or_else(|e| {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }),
+            None => GameServerState::new_default(),
+        };
+        if let Some(token) = &args.admin_token {
+            state = state.with_admin_token(token.clone());
+        }
+        if let Some(path) = args.sessions.as_ref().filter(|_| args.mode == Mode::Server) {
+            let sessions = FileSessionStore::open(path).unwrap_or_else(|e| {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            });
+            tracing::info!("restored {} games from {}", sessions.count().await, path);
+            state = state.with_sessions(Arc::new(sessions));
+            state.events.start_existing(state.sessions.as_ref()).await;
+        }
+        if let Some(path) = &args.book {
+            let book = OpeningBook::load(path).unwrap_or_else(|e| {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            });
+            tracing::info!("opening book loaded with {} positions", book.len());
+            state = state.with_opening_book(Arc::new(book));
+        }
+        if args.mode == Mode::Engine {
+            // Only protocol replies go to stdout; errors go to stderr.
+            let Some(bot) = state.bots.find(&args.bot) else {
+                eprintln!("Bot '{}' not found. Available bots: {:?}", args.bot, state.bots.names());
+                std::process::exit(1);
+            };
+            if let Err(e) = run_engine_stdio(bot, args.size) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            return;
+        }
+        let mut policy = SessionPolicy::default();
+        if let Some(secs) = args.finished_ttl {
+            policy.finished_ttl = Duration::from_secs(secs);
+        }
+        if let Some(secs) = args.idle_ttl {
+            policy.idle_ttl = Duration::from_secs(secs);
+        }
+        if let Some(max) = args.max_games {
+            policy.max_games_per_principal = max;
+        }
+        let mut state = state.with_session_policy(policy);
+        if let Some(secs) = args.async_bot_deadline {
+            state = state.with_async_bot_deadline(Duration::from_secs(secs));
+        }
+        if let Some(max) = args.max_bot_sessions {
+            state = state.with_max_bot_sessions(max);
+        }
+        spawn_sweeper(state.clone());
+
         let app = create_router(state);
         let addr = format!("0.0.0.0:{}", args.port);
         let listener = tokio::net::TcpListener::bind(&addr).await
@@ -0,0 +1,294 @@
+//! Compact binary and URL-safe text encodings of a position.
+//!
+//! The binary form packs each cell into 2 bits after a 3-byte header:
+//!
+//! | byte | content                                  |
+//! |------|------------------------------------------|
+//! | 0    | format version ([`COMPACT_FORMAT_VERSION`]) |
+//! | 1    | board size                               |
+//! | 2    | player to move (0 or 1)                  |
+//! | 3..  | cells in index order, 4 per byte         |
+//!
+//! Cell `i` is stored in bits `2 * (i % 4)` of byte `3 + i / 4`, with `00`
+//! for an empty cell, `01` for player 0 and `10` for player 1. The text form
+//! is the binary form encoded as URL-safe base64 without padding, so it can be
+//! used directly in query parameters.
+//!
+//! Player symbols are not stored: decoded positions always use `['B', 'R']`.
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+
+use crate::{Coordinates, GameY, GameYError, YEN};
+
+/// Version byte written at the start of every compact position.
+pub const COMPACT_FORMAT_VERSION: u8 = 1;
+
+/// Number of header bytes before the packed cells.
+const HEADER_LEN: usize = 3;
+
+const EMPTY_BITS: u8 = 0b00;
+const PLAYER_0_BITS: u8 = 0b01;
+const PLAYER_1_BITS: u8 = 0b10;
+
+fn invalid(message: impl Into<String>) -> GameYError {
+    GameYError::InvalidCompactPosition {
+        message: message.into(),
+    }
+}
+
+fn total_cells(size: u32) -> usize {
+    (size as usize * (size as usize + 1)) / 2
+}
+
+impl YEN {
+    /// Encodes this position in the compact binary format.
+    ///
+    /// Fails if the board size does not fit in a byte, the turn is not 0 or 1,
+    /// or the layout contains characters other than `.` and the player symbols.
+    pub fn to_compact_bytes(&self) -> Result<Vec<u8>, GameYError> {
+        let size = u8::try_from(self.size())
+            .map_err(|_| invalid(format!("board size {} does not fit in a byte", self.size())))?;
+        let turn = u8::try_from(self.turn())
+            .ok()
+            .filter(|turn| *turn <= 1)
+            .ok_or_else(|| invalid(format!("turn {} is not 0 or 1", self.turn())))?;
+
+        let cells: Vec<char> = self.layout().chars().filter(|c| *c != '/').collect();
+        if cells.len() != total_cells(self.size()) {
+            return Err(invalid(format!(
+                "layout has {} cells, expected {}",
+                cells.len(),
+                total_cells(self.size())
+            )));
+        }
+
+        let player_0 = self.players().first().copied().unwrap_or('B');
+        let player_1 = self.players().get(1).copied().unwrap_or('R');
+
+        let mut bytes = vec![0u8; HEADER_LEN + cells.len().div_ceil(4)];
+        bytes[0] = COMPACT_FORMAT_VERSION;
+        bytes[1] = size;
+        bytes[2] = turn;
+
+        for (idx, cell) in cells.iter().enumerate() {
+            let bits = match *cell {
+                '.' => EMPTY_BITS,
+                c if c == player_0 => PLAYER_0_BITS,
+                c if c == player_1 => PLAYER_1_BITS,
+                other => return Err(invalid(format!("unexpected character '{other}' in layout"))),
+            };
+            bytes[HEADER_LEN + idx / 4] |= bits << (2 * (idx % 4));
+        }
+
+        Ok(bytes)
+    }
+
+    /// Decodes a position from the compact binary format.
+    pub fn from_compact_bytes(bytes: &[u8]) -> Result<Self, GameYError> {
+        if bytes.len() < HEADER_LEN {
+            return Err(invalid("missing header"));
+        }
+        if bytes[0] != COMPACT_FORMAT_VERSION {
+            return Err(invalid(format!("unsupported format version {}", bytes[0])));
+        }
+
+        let size = bytes[1] as u32;
+        if size == 0 {
+            return Err(invalid("board size must be at least 1"));
+        }
+        let turn = bytes[2] as u32;
+        if turn > 1 {
+            return Err(invalid(format!("turn {turn} is not 0 or 1")));
+        }
+
+        let cells = total_cells(size);
+        let expected_len = HEADER_LEN + cells.div_ceil(4);
+        if bytes.len() != expected_len {
+            return Err(invalid(format!(
+                "expected {expected_len} bytes for board size {size}, found {}",
+                bytes.len()
+            )));
+        }
+
+        let mut layout = String::with_capacity(cells + size as usize);
+        for idx in 0..cells {
+            let bits = (bytes[HEADER_LEN + idx / 4] >> (2 * (idx % 4))) & 0b11;
+            let symbol = match bits {
+                EMPTY_BITS => '.',
+                PLAYER_0_BITS => 'B',
+                PLAYER_1_BITS => 'R',
+                _ => return Err(invalid(format!("invalid cell value at index {idx}"))),
+            };
+            layout.push(symbol);
+
+            let coords = Coordinates::from_index(idx as u32, size);
+            if coords.z() == 0 && coords.x() > 0 {
+                layout.push('/');
+            }
+        }
+
+        // Unused bits in the last byte must be zero so each position has a
+        // single encoding.
+        let padding_bits = (expected_len - HEADER_LEN) * 8 - cells * 2;
+        if padding_bits > 0 && bytes[expected_len - 1] >> (8 - padding_bits) != 0 {
+            return Err(invalid("non-zero padding bits"));
+        }
+
+        Ok(YEN::new(size, turn, vec!['B', 'R'], layout))
+    }
+
+    /// Encodes this position as URL-safe base64 text.
+    pub fn to_compact_string(&self) -> Result<String, GameYError> {
+        Ok(URL_SAFE_NO_PAD.encode(self.to_compact_bytes()?))
+    }
+
+    /// Decodes a position from URL-safe base64 text.
+    pub fn from_compact_string(text: &str) -> Result<Self, GameYError> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(text.trim())
+            .map_err(|e| invalid(format!("invalid base64: {e}")))?;
+        Self::from_compact_bytes(&bytes)
+    }
+}
+
+impl GameY {
+    /// Encodes the current position in the compact binary format.
+    ///
+    /// Fails if the board size does not fit in a byte.
+    pub fn to_compact_bytes(&self) -> Result<Vec<u8>, GameYError> {
+        YEN::from(self).to_compact_bytes()
+    }
+
+    /// Encodes the current position as URL-safe base64 text.
+    pub fn to_compact_string(&self) -> Result<String, GameYError> {
+        YEN::from(self).to_compact_string()
+    }
+
+    /// Builds a game from a compact binary position.
+    pub fn from_compact_bytes(bytes: &[u8]) -> Result<Self, GameYError> {
+        GameY::try_from(YEN::from_compact_bytes(bytes)?)
+    }
+
+    /// Builds a game from a URL-safe base64 position.
+    pub fn from_compact_string(text: &str) -> Result<Self, GameYError> {
+        GameY::try_from(YEN::from_compact_string(text)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_empty_board_bytes() {
+        let yen = YEN::new(2, 0, vec!['B', 'R'], "./..".to_string());
+        let bytes = yen.to_compact_bytes().unwrap();
+        assert_eq!(bytes, vec![COMPACT_FORMAT_VERSION, 2, 0, 0]);
+    }
+
+    #[test]
+    fn test_cells_are_packed_two_bits_each() {
+        // Cells in index order: B, R, ., B, R
+        let yen = YEN::new(3, 1, vec!['B', 'R'], "B/R./BR.".to_string());
+        let bytes = yen.to_compact_bytes().unwrap();
+        assert_eq!(bytes.len(), 3 + 2);
+        assert_eq!(bytes[2], 1);
+        assert_eq!(bytes[3], 0b01_00_10_01);
+        assert_eq!(bytes[4], 0b00_00_10);
+    }
+
+    #[test]
+    fn test_roundtrip_preserves_layout_and_turn() {
+        let yen = YEN::new(4, 1, vec!['B', 'R'], "B/.R/BBR/....".to_string());
+        let text = yen.to_compact_string().unwrap();
+        let decoded = YEN::from_compact_string(&text).unwrap();
+        assert_eq!(decoded.size(), 4);
+        assert_eq!(decoded.turn(), 1);
+        assert_eq!(decoded.layout(), yen.layout());
+    }
+
+    #[test]
+    fn test_compact_string_is_url_safe() {
+        let yen = YEN::new(7, 0, vec!['B', 'R'], "R/RR/RRR/RRRR/RRRRR/RRRRRR/RRRRRRR".to_string());
+        let text = yen.to_compact_string().unwrap();
+        assert!(text.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    }
+
+    #[test]
+    fn test_custom_player_symbols_are_mapped() {
+        let yen = YEN::new(2, 0, vec!['X', 'O'], "X/O.".to_string());
+        let decoded = YEN::from_compact_bytes(&yen.to_compact_bytes().unwrap()).unwrap();
+        assert_eq!(decoded.layout(), "B/R.");
+        assert_eq!(decoded.players(), &['B', 'R']);
+    }
+
+    #[test]
+    fn test_rejects_invalid_layout_char() {
+        let yen = YEN::new(2, 0, vec!['B', 'R'], "B/X.".to_string());
+        assert!(matches!(
+            yen.to_compact_bytes(),
+            Err(GameYError::InvalidCompactPosition { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rejects_bad_header_and_length() {
+        assert!(YEN::from_compact_bytes(&[]).is_err());
+        assert!(YEN::from_compact_bytes(&[9, 2, 0, 0]).is_err());
+        assert!(YEN::from_compact_bytes(&[COMPACT_FORMAT_VERSION, 0, 0]).is_err());
+        assert!(YEN::from_compact_bytes(&[COMPACT_FORMAT_VERSION, 2, 2, 0]).is_err());
+        assert!(YEN::from_compact_bytes(&[COMPACT_FORMAT_VERSION, 2, 0]).is_err());
+        assert!(YEN::from_compact_bytes(&[COMPACT_FORMAT_VERSION, 2, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn test_rejects_invalid_cell_value_and_padding() {
+        assert!(YEN::from_compact_bytes(&[COMPACT_FORMAT_VERSION, 2, 0, 0b11]).is_err());
+        assert!(YEN::from_compact_bytes(&[COMPACT_FORMAT_VERSION, 2, 0, 0b01_00_00_00]).is_err());
+    }
+
+    #[test]
+    fn test_rejects_invalid_base64() {
+        assert!(YEN::from_compact_string("not base64!").is_err());
+    }
+
+    #[test]
+    fn test_game_roundtrip() {
+        let yen = YEN::new(3, 0, vec!['B', 'R'], "B/R./...".to_string());
+        let game = GameY::try_from(yen).unwrap();
+        let restored = GameY::from_compact_string(&game.to_compact_string().unwrap()).unwrap();
+        assert_eq!(YEN::from(&restored).layout(), YEN::from(&game).layout());
+        assert_eq!(restored.next_player(), game.next_player());
+    }
+
+    #[test]
+    fn test_game_too_large_is_an_error() {
+        let game = GameY::new(256);
+        assert!(game.to_compact_bytes().is_err());
+        assert!(game.to_compact_string().is_err());
+    }
+
+    proptest! {
+        /// Property: any layout survives a YEN -> compact -> YEN roundtrip.
+        #[test]
+        fn prop_compact_roundtrip(size in 1u32..=15, turn in 0u32..=1, seed in any::<u64>()) {
+            let mut state = seed;
+            let mut layout = String::new();
+            for idx in 0..total_cells(size) as u32 {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                layout.push(['.', 'B', 'R'][(state >> 33) as usize % 3]);
+                let coords = Coordinates::from_index(idx, size);
+                if coords.z() == 0 && coords.x() > 0 {
+                    layout.push('/');
+                }
+            }
+            let yen = YEN::new(size, turn, vec!['B', 'R'], layout);
+
+            let decoded = YEN::from_compact_string(&yen.to_compact_string().unwrap()).unwrap();
+            prop_assert_eq!(decoded.size(), yen.size());
+            prop_assert_eq!(decoded.turn(), yen.turn());
+            prop_assert_eq!(decoded.layout(), yen.layout());
+        }
+    }
+}
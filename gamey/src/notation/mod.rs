@@ -4,6 +4,9 @@
 //! in a compact, portable way. Currently supported:
 //!
 //! - [`YEN`]: Y Exchange Notation - a JSON-based format inspired by chess FEN
+//! - [`compact`]: 2-bits-per-cell binary encoding and its URL-safe base64 text form
 
+pub mod compact;
 pub mod yen;
+pub use compact::*;
 pub use yen::*;
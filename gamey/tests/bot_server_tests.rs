@@ -85,6 +85,33 @@ async fn test_choose_endpoint_with_valid_request() {
     // Coordinates should be valid (we can't predict exactly which one the random bot picks)
 }
 
+#[tokio::test]
+async fn test_choose_endpoint_with_seed_is_reproducible() {
+    let yen = YEN::new(7, 0, vec!['B', 'R'], "./../.../..../...../....../.......".to_string());
+
+    let mut moves = Vec::new();
+    for _ in 0..3 {
+        let response = test_app()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/v1/ybot/choose/random_bot?seed=42")
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_string(&yen).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let move_response: MoveResponse = serde_json::from_slice(&body).unwrap();
+        moves.push(move_response.coords);
+    }
+
+    assert!(moves.windows(2).all(|w| w[0] == w[1]));
+}
+
 #[tokio::test]
 async fn test_choose_endpoint_with_partially_filled_board() {
     let app = test_app();
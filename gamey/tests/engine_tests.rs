@@ -0,0 +1,43 @@
+use gamey::{BotDecision, ExternalBot, GameY, Movement, RandomBot, YBot};
+
+/// An `ExternalBot` that runs this crate's own binary in engine mode.
+fn gamey_engine(bot: &str) -> ExternalBot {
+    ExternalBot::new(
+        format!("external_{bot}"),
+        env!("CARGO_BIN_EXE_gamey"),
+        vec!["--mode".into(), "engine".into(), "--bot".into(), bot.into()],
+    )
+}
+
+// =============================================================================
+// ExternalBot against `gamey --mode engine`
+// =============================================================================
+
+#[test]
+fn test_external_bot_gets_a_legal_move_from_the_engine() {
+    let bot = gamey_engine("greedy_bot");
+    let game = GameY::new(5);
+
+    let coords = bot.choose_move(&game).unwrap();
+    assert!(game.available_cells().contains(&coords.to_index(5)));
+}
+
+#[test]
+fn test_external_bot_plays_a_full_game() {
+    let external = gamey_engine("random_bot");
+    let mut game = GameY::new(4);
+
+    // The engine process is reused between moves; each call replays the game.
+    while let Some(player) = game.next_player() {
+        let bot: &dyn YBot = if player.id() == 0 { &external } else { &RandomBot };
+        let coords = bot.choose_move(&game).unwrap();
+        game.add_move(Movement::Placement { player, coords }).unwrap();
+    }
+    assert!(game.check_game_over());
+}
+
+#[test]
+fn test_external_bot_with_unknown_engine_bot_gives_no_move() {
+    let bot = gamey_engine("no_such_bot");
+    assert_eq!(bot.choose_action(&GameY::new(3)), None::<BotDecision>);
+}
@@ -0,0 +1,183 @@
+use axum::{Json, Router, extract::Query, http::StatusCode, routing::get};
+use gamey::game_server::{create_router, state::GameServerState};
+use gamey::{BotDecision, Coordinates, GameY, Movement, PlayerId, RemoteBot, SearchControl, YBot, YBotRegistry};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+/// Serves `app` on a free local port from a background thread and returns its base URL.
+fn serve(app: Router) -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    listener.set_nonblocking(true).unwrap();
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            axum::serve(listener, app).await.unwrap();
+        });
+    });
+    format!("http://{addr}")
+}
+
+/// A stand-in `/play` server that always answers with the top corner.
+fn corner_server(failures_before_success: u32) -> (String, Arc<AtomicU32>) {
+    let calls = Arc::new(AtomicU32::new(0));
+    let counter = calls.clone();
+    let app = Router::new().route(
+        "/play",
+        get(move || {
+            let counter = counter.clone();
+            async move {
+                if counter.fetch_add(1, Ordering::SeqCst) < failures_before_success {
+                    return Err(StatusCode::SERVICE_UNAVAILABLE);
+                }
+                Ok(Json(json!({ "coords": { "x": 2, "y": 0, "z": 0 } })))
+            }
+        }),
+    );
+    (serve(app), calls)
+}
+
+// ============================================================================
+// Against our own game server
+// ============================================================================
+
+#[test]
+fn test_remote_bot_plays_a_legal_move() {
+    let url = serve(create_router(GameServerState::new_default()));
+    let registry = YBotRegistry::new().with_bot(Arc::new(RemoteBot::new("remote_random", url, "random_bot")));
+    let bot = registry.find("remote_random").unwrap();
+
+    let mut game = GameY::new(4);
+    game.add_move(Movement::Placement { player: PlayerId::new(0), coords: Coordinates::new(3, 0, 0) })
+        .unwrap();
+
+    let coords = bot.choose_move(&game).unwrap();
+    assert!(game.available_cells().contains(&coords.to_index(4)));
+}
+
+#[test]
+fn test_remote_bot_forwards_the_seed() {
+    let url = serve(create_router(GameServerState::new_default()));
+    let bot = RemoteBot::new("remote_random", url, "random_bot");
+    let game = GameY::new(7);
+
+    let first = bot.choose_action_seeded(&game, 42).unwrap();
+    for _ in 0..3 {
+        assert_eq!(bot.choose_action_seeded(&game, 42).unwrap(), first);
+    }
+}
+
+#[test]
+fn test_remote_bot_with_unknown_bot_id_gives_no_move() {
+    let url = serve(create_router(GameServerState::new_default()));
+    let bot = RemoteBot::new("remote_ghost", url, "no_such_bot");
+    assert!(bot.choose_action(&GameY::new(3)).is_none());
+}
+
+// ============================================================================
+// Against stand-in servers
+// ============================================================================
+
+#[test]
+fn test_remote_bot_retries_server_errors() {
+    let (url, calls) = corner_server(2);
+    let bot = RemoteBot::new("remote", url, "any").with_retries(2);
+
+    assert_eq!(
+        bot.choose_action(&GameY::new(3)),
+        Some(BotDecision::Move(Coordinates::new(2, 0, 0)))
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn test_remote_bot_gives_up_after_its_retries() {
+    let (url, calls) = corner_server(2);
+    let bot = RemoteBot::new("remote", url, "any").with_retries(1);
+
+    assert!(bot.choose_action(&GameY::new(3)).is_none());
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_remote_bot_rejects_illegal_moves() {
+    let (url, _) = corner_server(0);
+    let bot = RemoteBot::new("remote", url, "any");
+
+    let mut game = GameY::new(3);
+    game.add_move(Movement::Placement { player: PlayerId::new(0), coords: Coordinates::new(2, 0, 0) })
+        .unwrap();
+    assert!(bot.choose_action(&game).is_none());
+}
+
+#[test]
+fn test_remote_bot_times_out() {
+    let app = Router::new().route(
+        "/play",
+        get(|| async {
+            tokio::time::sleep(Duration::from_secs(3)).await;
+            Json(json!({ "coords": { "x": 2, "y": 0, "z": 0 } }))
+        }),
+    );
+    let bot = RemoteBot::new("slow", serve(app), "any")
+        .with_timeout(Duration::from_millis(200))
+        .with_retries(0);
+
+    let start = Instant::now();
+    assert!(bot.choose_action(&GameY::new(3)).is_none());
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn test_remote_bot_stops_at_the_control_deadline() {
+    let app = Router::new().route(
+        "/play",
+        get(|| async {
+            tokio::time::sleep(Duration::from_secs(3)).await;
+            Json(json!({ "coords": { "x": 2, "y": 0, "z": 0 } }))
+        }),
+    );
+    let bot = RemoteBot::new("slow", serve(app), "any");
+    let control = SearchControl::with_deadline(Instant::now() + Duration::from_millis(200));
+
+    let start = Instant::now();
+    assert!(bot.choose_action_controlled(&GameY::new(3), None, &control).is_none());
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn test_remote_bot_does_not_retry_once_cancelled() {
+    let (url, calls) = corner_server(2);
+    let bot = RemoteBot::new("remote", url, "any").with_retries(2);
+    let control = SearchControl::new();
+    control.cancel();
+
+    assert!(bot.choose_action_controlled(&GameY::new(3), None, &control).is_none());
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_remote_bot_forwards_the_remaining_time() {
+    let timeouts = Arc::new(Mutex::new(Vec::new()));
+    let seen = timeouts.clone();
+    let app = Router::new().route(
+        "/play",
+        get(move |Query(query): Query<HashMap<String, String>>| {
+            let seen = seen.clone();
+            async move {
+                seen.lock().unwrap().push(query.get("timeout_ms").and_then(|ms| ms.parse::<u64>().ok()));
+                Json(json!({ "coords": { "x": 2, "y": 0, "z": 0 } }))
+            }
+        }),
+    );
+    let bot = RemoteBot::new("remote", serve(app), "any");
+    let control = SearchControl::with_deadline(Instant::now() + Duration::from_millis(1500));
+
+    assert!(bot.choose_move_controlled(&GameY::new(3), None, &control).is_some());
+    let timeout_ms = timeouts.lock().unwrap()[0].unwrap();
+    assert!(timeout_ms > 0 && timeout_ms <= 1500);
+}